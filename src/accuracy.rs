@@ -0,0 +1,57 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+use crate::plot;
+
+/*
+ * How close whole teeth get to the given ratio, for every pinion size. The best output
+ * gear for a pinion is the rounded product, the error left over is what more teeth could
+ * still improve.
+ */
+
+pub struct Accuracy {
+    min_teeth: u32,
+    max_teeth: u32,
+}
+
+impl Accuracy {
+    pub fn new() -> Accuracy {
+        Accuracy {
+            min_teeth: 8,
+            max_teeth: 60,
+        }
+    }
+
+    // (pinion teeth, relative error in percent)
+    fn errors(&self, given: f64) -> Vec<(u32, f64)> {
+        (self.min_teeth..=self.max_teeth).map(|pinion| {
+            let wheel = (pinion as f64 * given).round().max(1.0);
+            (pinion, (wheel / pinion as f64 / given - 1.0) * 100.0)
+        }).collect()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, given_ratio: f64) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Pinion teeth"));
+            ui.add(egui::DragValue::new(&mut self.min_teeth).clamp_range(1..=self.max_teeth));
+            ui.label(tr("to"));
+            ui.add(egui::DragValue::new(&mut self.max_teeth).clamp_range(self.min_teeth..=500));
+        });
+        ui.label(format!("{} {:.3}", tr("Best error for the given ratio"), given_ratio));
+        plot::error_vs_teeth(ui, "accuracy_plot", &self.errors(given_ratio));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_error_lost_to_f32() {
+        let accuracy = Accuracy { min_teeth: 100000, max_teeth: 100000 };
+        let given = 127.0 / 100000.0 + 1.0;
+        let errors = accuracy.errors(given);
+        // as an f32 the given ratio is 6e-6 % off already
+        assert!(errors[0].1.abs() < 1e-9, "{:?}", errors);
+    }
+}
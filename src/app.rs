@@ -1,8 +1,52 @@
-use std::{fmt::Display, str::FromStr};
-
 use eframe::egui;
-use num_traits::{FromPrimitive, clamp_max};
-use num_derive::FromPrimitive;
+
+use crate::accuracy::Accuracy;
+use crate::approx::Approximation;
+use crate::audio::Audio;
+use crate::belt::BeltDrive;
+use crate::bicycle::Bicycle;
+use crate::classroom::Classroom;
+use crate::clock::Clock;
+use crate::compare::Compare;
+use crate::constraint::{ConstraintGraph, Quantity, Relation};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::config;
+use crate::conversions::Conversions;
+use crate::cvt::Cvt;
+use crate::cycloidal::Cycloidal;
+use crate::dataset::{self, Dataset};
+use crate::differential::Differential;
+use crate::favorites::Favorites;
+use crate::feedback::{self, Feedback};
+use crate::gear_train::GearTrain;
+use crate::gearbox::Gearbox;
+use crate::geometry::{self, Geometry};
+use crate::history::{self, History};
+use crate::i18n::{self, tr};
+use crate::inertia::{self, Body, Matching};
+use crate::lathe::Lathe;
+use crate::matching::TrainMatch;
+use crate::mesh_tone::MeshTone;
+use crate::motion_works::MotionWorks;
+use crate::motor::MotorOutput;
+use crate::planetary::Planetary;
+use crate::presets;
+use crate::pulley::PulleyDrive;
+use crate::quick_entry;
+use crate::ratio_model::RatioModel;
+use crate::rc::RcCar;
+use crate::project::Project;
+use crate::report::{Design, Report};
+use crate::reratio::Reratio;
+use crate::search::{CompoundSearch, TrainSearch};
+use crate::settings::{Settings, Units};
+use crate::sidereal::Sidereal;
+use crate::stepper::Stepper;
+use crate::strength::Strength;
+use crate::sweep::Sweep;
+use crate::train::Direction;
+use crate::url_state;
+use crate::vehicle::Vehicle;
 
 /*
  * There are 3 basic modes of operation:
@@ -13,43 +57,104 @@ use num_derive::FromPrimitive;
  *      - the ratio will be adapted
  * - change the ratio, keeping one of the gears fixed
  *      - again, the actual ratio will move in steps
+ *
+ * The output gear can be a ring gear, the pinion then runs inside it. Both turn the same
+ * way and the center distance is half the difference of the pitch diameters instead of
+ * half the sum. Below a minimum difference in teeth the tips of the two collide.
  */
 
-#[derive(PartialEq, FromPrimitive, Debug, Clone, Copy)]
-enum Column {
-    Left  = 0b001,
-    Ratio = 0b010,
-    Right = 0b100,
+// the relations between the quantities of the gear pair
+const PAIR_RELATIONS: &[Relation] = &[Relation::Teeth, Relation::Mesh, Relation::Speed];
+// one of the three columns is always locked
+const COLUMNS: [Quantity; 3] = [Quantity::Left, Quantity::Ratio, Quantity::Right];
+
+// fewest teeth a ring gear needs more than its pinion
+const RING_MIN_DIFFERENCE: u32 = 10;
+
+// what the central panel is calculating
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Mode {
+    Pair,
+    Belt,
+    Pulley,
+    Match,
+    Reratio,
+    Search,
+    Bicycle,
+    Rc,
+    Train,
+    Stepper,
+    Lathe,
+    Clock,
+    MotionWorks,
+    Approx,
+    Sidereal,
+    Gearbox,
+    Vehicle,
+    Cvt,
+    Cycloidal,
+    Planetary,
+    Differential,
+}
+
+impl Mode {
+    const ALL: [Mode; 21] = [Mode::Pair, Mode::Belt, Mode::Pulley, Mode::Match, Mode::Reratio, Mode::Search, Mode::Bicycle, Mode::Rc, Mode::Train, Mode::Stepper, Mode::Lathe, Mode::Clock, Mode::MotionWorks, Mode::Approx, Mode::Sidereal, Mode::Gearbox, Mode::Vehicle, Mode::Cvt, Mode::Cycloidal, Mode::Planetary, Mode::Differential];
+
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Pair => "Gear Ratio Calculator",
+            Mode::Belt => "Timing Belt Drive",
+            Mode::Pulley => "Pulley Drive",
+            Mode::Match => "Match Trains",
+            Mode::Reratio => "Re-ratio Helper",
+            Mode::Search => "Train Search",
+            Mode::Bicycle => "Bicycle Drivetrain",
+            Mode::Rc => "RC Car Final Drive",
+            Mode::Train => "Gear Train",
+            Mode::Stepper => "Stepper Steps/mm",
+            Mode::Lathe => "Lathe Change Gears",
+            Mode::Clock => "Clock Going Train",
+            Mode::MotionWorks => "Motion Works",
+            Mode::Approx => "Precise Ratio Approximation",
+            Mode::Sidereal => "Sidereal Drive",
+            Mode::Gearbox => "Multi-speed Gearbox",
+            Mode::Vehicle => "Vehicle Gearing",
+            Mode::Cvt => "CVT Ratio Range",
+            Mode::Cycloidal => "Cycloidal Drive",
+            Mode::Planetary => "Planetary Gears",
+            Mode::Differential => "Differential",
+        }
+    }
+}
+
+// narrower than this, the columns are stacked and the history goes below, for half-screen
+// windows and phones
+const COMPACT_WIDTH: f32 = 520.0;
+
+// the columns of a drive side by side, or stacked when there is no room for them
+pub fn columns_layout(ui: &egui::Ui) -> egui::Layout {
+    if ui.available_width() < COMPACT_WIDTH {
+        egui::Layout::top_down(egui::Align::Min)
+    } else {
+        egui::Layout::left_to_right(egui::Align::Min)
+    }
+}
+
+// the lock toggle of a column, returns whether it was clicked
+pub fn lock_toggle(ui: &mut egui::Ui, locked: bool, name: &str) -> bool {
+    let response = ui.selectable_label(locked, tr("locked"));
+    response.widget_info(|| egui::WidgetInfo::selected(egui::WidgetType::SelectableLabel, locked, format!("{} {}", name, tr("locked"))));
+    response.clicked()
 }
 
-impl Column {
-    // get the missing 3rd column for 2 columns. c1 and c2 may not be equal.
-    fn get_missing(c1: Column, c2: Column) -> Column {
-        assert_ne!(c1, c2);
-        let mut i = c1 as u32 | c2 as u32;
-        i = (!i) & 0b111;
-        let c: Column = FromPrimitive::from_u32(i).unwrap();
-        c
-    }
-
-    // the long and cumbersome version:
-    //fn get_missing(c1: Column, c2: Column) -> Column {
-        //match c1 {
-            //Column::Left => match c2 {
-                //Column::Ratio => Column::Right,
-                //_             => Column::Ratio,
-            //},
-            //Column::Ratio => match c2 {
-                //Column::Left => Column::Right,
-                //_            => Column::Left,
-            //},
-            //Column::Right => match c2 {
-                //Column::Left => Column::Ratio,
-                //_            => Column::Left,
-            //},
-        //}
-    //}
-    // another alternative would be looping through the values
+// F1, F2 and F3 move the lock to the left, middle and right column
+pub fn lock_shortcut(ui: &egui::Ui) -> Option<Quantity> {
+    ui.input(|i| {
+        [(egui::Key::F1, Quantity::Left), (egui::Key::F2, Quantity::Ratio), (egui::Key::F3, Quantity::Right)]
+            .into_iter()
+            .find(|(k, _)| i.key_pressed(*k))
+            .map(|(_, q)| q)
+    })
 }
 
 struct SideVars {
@@ -61,277 +166,1000 @@ impl SideVars {
     fn new(teeth: u32) -> SideVars {
         SideVars{
             teeth,
-            t_str: String::from(teeth.to_string()),
+            t_str: teeth.to_string(),
         }
     }
 }
 
-pub struct RitzelApp {
+// one calculation, every tab has its own
+struct Calculation {
+    name: String,
+    // set when the pair was recomputed, for the classroom
+    recomputed: bool,
     left: SideVars,
     right: SideVars,
-    given_ratio: f32,
-    actual_ratio: f32,
-    ar_str: String,
-    gr_str: String,
-    locked_column: Column,
-}
-
-#[derive(Clone, Copy, Default)]
-struct NumberSpinnerState {
-    offset: f32,
-    rect_max: egui::Pos2,
+    // the output gear is internal
+    ring: bool,
+    ratio: RatioModel,
+    // gear module in mm
+    module: f32,
+    geometry: Geometry,
+    strength: Strength,
+    matching_inertia: Matching,
+    center_distance: f32,
+    // rpm
+    input_speed: f32,
+    output_speed: f32,
+    graph: ConstraintGraph,
+    compound: CompoundSearch,
+    quick_str: String,
+    // parse error of the quick entry
+    quick_msg: String,
+    mode: Mode,
+    belt: BeltDrive,
+    pulley: PulleyDrive,
+    matching: TrainMatch,
+    reratio: Reratio,
+    search: TrainSearch,
+    bicycle: Bicycle,
+    rc: RcCar,
+    gear_train: GearTrain,
+    stepper: Stepper,
+    lathe: Lathe,
+    clock: Clock,
+    motion_works: MotionWorks,
+    approx: Approximation,
+    sidereal: Sidereal,
+    gearbox: Gearbox,
+    vehicle: Vehicle,
+    cvt: Cvt,
+    cycloidal: Cycloidal,
+    planetary: Planetary,
+    differential: Differential,
 }
 
-struct NumberSpinner<'a, T>
-where
-    T: num_traits::NumAssign + PartialOrd + Display + FromPrimitive + FromStr + Copy
-{
-    ui: &'a mut egui::Ui,
-    value: &'a mut T,
-    val_str: &'a mut String,
-    interactive: bool,
-    step: T,
-    min_value: T,
-    max_value: T,
-    precision: usize,
-    uiid: i32,
+pub struct RitzelApp {
+    tabs: Vec<Calculation>,
+    // index of the active tab
+    tab: usize,
+    inventory: Dataset,
+    show_datasets: bool,
+    conversions: Conversions,
+    show_conversions: bool,
+    classroom: Classroom,
+    show_classroom: bool,
+    audio: Audio,
+    mesh_tone: MeshTone,
+    show_mesh_tone: bool,
+    sweep: Sweep,
+    show_sweep: bool,
+    motors: Dataset,
+    catalog: Dataset,
+    motor_output: MotorOutput,
+    show_motor_output: bool,
+    accuracy: Accuracy,
+    show_accuracy: bool,
+    history: History,
+    show_history: bool,
+    compare: Compare,
+    show_compare: bool,
+    report: Report,
+    show_report: bool,
+    // reference files kept with the project
+    project: Project,
+    show_attachments: bool,
+    // what the URL fragment was last set to
+    fragment: String,
+    settings: Settings,
+    // what the settings start from, only the changes to them are saved
+    defaults: Settings,
+    show_settings: bool,
+    // pinned ratios, shared by the tabs
+    favorites: Favorites,
 }
 
-impl<'a, T> NumberSpinner<'a, T>
-where
-    T: num_traits::NumAssign + PartialOrd + Display + FromPrimitive + FromStr + Copy
-{
-    fn go(&mut self) -> bool {
-        number_spinner(self.ui, self.value, self.val_str, self.interactive, self.step, self.min_value, self.max_value, self.precision, self.uiid)
+impl Calculation {
+    fn new(name: String, settings: &Settings) -> Calculation {
+        let mut calc = Calculation {
+            name,
+            recomputed: false,
+            left: SideVars::new(settings.default_teeth),
+            right: SideVars::new(settings.default_teeth),
+            ring: false,
+            ratio: RatioModel::new(settings.default_ratio),
+            module: 1.0,
+            geometry: Geometry::new(),
+            strength: Strength::new(),
+            matching_inertia: Matching::new(),
+            center_distance: 0.0,
+            input_speed: 1000.0,
+            output_speed: 0.0,
+            graph: ConstraintGraph::new(PAIR_RELATIONS, &[Quantity::Ratio, Quantity::Module, Quantity::InputSpeed]),
+            compound: CompoundSearch::new(),
+            quick_str: String::new(),
+            quick_msg: String::new(),
+            mode: Mode::Pair,
+            belt: BeltDrive::new(),
+            pulley: PulleyDrive::new(),
+            matching: TrainMatch::new(),
+            reratio: Reratio::new(),
+            search: TrainSearch::new(),
+            bicycle: Bicycle::new(),
+            rc: RcCar::new(),
+            gear_train: GearTrain::new(),
+            stepper: Stepper::new(),
+            lathe: Lathe::new(),
+            clock: Clock::new(),
+            motion_works: MotionWorks::new(),
+            approx: Approximation::new(),
+            sidereal: Sidereal::new(),
+            gearbox: Gearbox::new(),
+            vehicle: Vehicle::new(),
+            cvt: Cvt::new(),
+            cycloidal: Cycloidal::new(),
+            planetary: Planetary::new(),
+            differential: Differential::new(),
+        };
+        calc.compute_r_teeth();
+        calc.solve(Quantity::CenterDistance, Relation::Mesh);
+        calc.solve(Quantity::OutputSpeed, Relation::Speed);
+        calc
     }
-}
 
-
-fn number_spinner<T>(ui: &mut egui::Ui, value: &mut T, val_str: &mut String, interactive: bool, step: T, min_value: T, max_value: T, precision: usize, uiid: i32) -> bool
-where
-    // aaaah just give me a sane number type
-    T: num_traits::NumAssign + PartialOrd + Display + FromPrimitive + FromStr + Copy
-{
-    let mut changed = false;
-    // used to keep track of dragging and scrolling state
-    let myid = egui::Id::new(34234 + uiid);
-    let mut state: NumberSpinnerState = ui.ctx().data_mut(|d| d.get_temp(myid)).unwrap_or_default();
-    ui.vertical(|ui| {
-        // handle scrolling and dragging.
-        // handling drags needs to be done before adding other ui elements to not steal their
-        // input
-        if interactive {
-            let mut delta = 0.0;
-            let mut urect = ui.min_rect();
-            urect.max = state.rect_max;
-
-            // scrolling
-            ui.input(|i| {
-                if let Some(pos) = i.pointer.latest_pos() {
-                    if urect.contains(pos){
-                        delta = i.scroll_delta.y;
-                    }
-                }
-            });
-
-            // dragging
-            let resp = ui.interact(urect, myid, egui::Sense::drag());
-            if resp.dragged() {
-                //println!("Dragged by: {:?}", resp.drag_delta());
-                delta = resp.drag_delta().y;
-            }
-
-            if delta != 0.0 {
-                state.offset += delta;
-                //println!("offset: {}", state.offset);
-                if state.offset > 20.0 {
-                    state.offset = 0.0;
-                    *value = clamp_max(*value + step, max_value);
-                    changed = true;
-                } else if state.offset < -20.0 {
-                    state.offset = 0.0;
-                    //*value = clamp_min(*value - step, min_value); // but avoid uint underflows
-                    //(-0.00001 to fix float precision problems, otherwise ratio only goes to 0.2)
-                    *value = if *value >= min_value + step - T::from_f32(0.00001).unwrap() {
-                        *value - step
-                    } else {
-                        *value
-                    };
-                    changed = true;
-                }
-                ui.ctx().data_mut(|d| d.insert_temp(myid, state));
-                // number changed from scroll/drag, so we need to update the text field
-                if changed {
-                    *val_str = format!("{0:.1$}", *value, precision).to_owned();
-                }
-            }
-        }
-
-        let te = egui::TextEdit::singleline(val_str)
-            .interactive(interactive)
-            .desired_width(80.0);
-
-        ui.label(egui::RichText::new(format!("{1:.0$}", precision, clamp_max(*value + step * T::from_f32(2.0).unwrap(), max_value))).weak());
-        ui.label(egui::RichText::new(format!("{1:.0$}", precision, clamp_max(*value + step, max_value))).weak());
-        let te_response = ui.add(te);
-        // again, a bit verbose to avoid underflows
-        ui.label(egui::RichText::new(
-                if *value  >= min_value + step - T::from_f32(0.00001).unwrap() {
-                    format!("{1:.0$}", precision, *value - step)
-                } else {
-                    "".to_owned()
-                }).weak());
-        ui.label(egui::RichText::new(
-                if *value >= min_value + step + step - T::from_f32(0.00001).unwrap() {
-                    format!("{1:.0$}", precision, *value - step - step)
-                } else {
-                    "".to_owned()
-                }).weak());
-
-        // we need the screen rect of the whole spinner to sense drags / scrolls, but we don't
-        // know it until the other UI elements have been added, so just cache it from last frame
-        if state.rect_max != ui.min_rect().max {
-            state.rect_max = ui.min_rect().max;
-            ui.ctx().data_mut(|d| d.insert_temp(myid, state));
-        }
-
-        // if enter is pressed and the entered string is no valid number, reset it
-        if te_response.lost_focus() {
-            if let Err(_) = val_str.parse::<T>() {
-                *val_str = format!("{0:.1$}", *value, precision).to_owned();
-            }
-        }
-        if te_response.changed() {
-            if let Ok(x) = val_str.parse::<T>() {
-                *value = x;
-                changed = true;
-            }
+    fn history_entry(&self) -> history::Entry {
+        history::Entry {
+            left: self.left.teeth,
+            right: self.right.teeth,
+            given: self.ratio.given,
+            module: self.module,
+            center_distance: self.center_distance,
+            input_speed: self.input_speed,
+            output_speed: self.output_speed,
         }
-    });
-    changed
-}
-
+    }
 
-impl RitzelApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        RitzelApp {
-            left: SideVars::new(10),
-            right: SideVars::new(15),
-            given_ratio: 1.5,
-            actual_ratio: 1.5,
-            ar_str: String::from(1.5.to_string()),
-            gr_str: String::from(1.5.to_string()),
-            locked_column: Column::Ratio,
-        }
+    fn restore(&mut self, e: history::Entry) {
+        self.left = SideVars::new(e.left);
+        self.right = SideVars::new(e.right);
+        self.ratio.set_given(e.given);
+        self.compute_ratio();
+        self.module = e.module;
+        self.center_distance = e.center_distance;
+        self.input_speed = e.input_speed;
+        self.output_speed = e.output_speed;
     }
 
     // left gear is the motor, right gear the wheel.
     // ratio is theeth on wheel / teeth on motor.
     fn compute_ratio(&mut self) {
-        self.actual_ratio = self.right.teeth as f32 / self.left.teeth as f32;
-        self.ar_str = String::from(format!("{:.3}", self.actual_ratio));
+        self.ratio.set_actual(self.left.teeth, self.right.teeth, self.graph.is_locked(Quantity::Ratio));
     }
 
     fn compute_l_teeth(&mut self) {
-        let lt = self.right.teeth as f32 / self.given_ratio;
-        self.left.teeth = lt.round() as u32;
-        self.left.t_str = String::from(self.left.teeth.to_string());
+        let right = self.right.teeth as f64;
+        self.left.teeth = self.ratio.round_teeth(right / self.ratio.given, |l| right / l);
+        self.left.t_str = self.left.teeth.to_string();
         // the actual ratio may not be the exact ratio due to the rounding
         self.compute_ratio();
     }
 
     fn compute_r_teeth(&mut self) {
-        let rt = self.left.teeth as f32 * self.given_ratio;
-        self.right.teeth = rt.round() as u32;
-        self.right.t_str = String::from(self.right.teeth.to_string());
+        let left = self.left.teeth as f64;
+        self.right.teeth = self.ratio.round_teeth(left * self.ratio.given, |r| r / left);
+        self.right.t_str = self.right.teeth.to_string();
         // the actual ratio may not be the exact ratio due to the rounding
         self.compute_ratio();
     }
 
-    // recomputes the value that is not fixed and not changed
-    fn recompute_from(&mut self, column: Column) {
-        let c = Column::get_missing(column, self.locked_column);
-        match c {
-            Column::Left => self.compute_l_teeth(),
-            Column::Ratio => self.compute_ratio(),
-            Column::Right => self.compute_r_teeth(),
+    // the ratio of whatever the active mode is calculating
+    fn current_ratio(&self) -> f32 {
+        match self.mode {
+            Mode::Pair => self.ratio.actual as f32,
+            Mode::Belt => self.belt.ratio(),
+            Mode::Pulley => self.pulley.ratio(),
+            Mode::Match => self.matching.ratio(),
+            Mode::Reratio => self.reratio.ratio(),
+            Mode::Search => self.search.ratio(),
+            Mode::Bicycle => self.bicycle.ratio(),
+            Mode::Rc => self.rc.final_drive(self.ratio.actual as f32),
+            Mode::Train => self.gear_train.ratio(),
+            Mode::Stepper => self.stepper.ratio(),
+            Mode::Lathe => self.lathe.ratio(),
+            Mode::Clock => self.clock.ratio(),
+            Mode::MotionWorks => self.motion_works.ratio(),
+            Mode::Approx => self.approx.ratio(),
+            Mode::Sidereal => self.sidereal.ratio(),
+            Mode::Gearbox => self.gearbox.ratio(),
+            Mode::Vehicle => self.vehicle.ratio(),
+            Mode::Cvt => self.cvt.ratio(),
+            Mode::Cycloidal => self.cycloidal.ratio(),
+            Mode::Planetary => self.planetary.ratio(),
+            Mode::Differential => self.differential.ratio(),
+        }
+    }
+
+    // recomputes one quantity from the others in the relation
+    fn solve(&mut self, q: Quantity, r: Relation) {
+        // the working center distance per mm of module, with the profile shift
+        let factor = self.geometry.center_factor(self.left.teeth, self.right.teeth, self.ring);
+        let side = if self.ring { -1.0 } else { 1.0 };
+        // a ring gear that doesn't fit around its pinion has no center distance, the last
+        // module and center distance stay until the teeth are fixed
+        let no_mesh = factor <= 0.0 || (self.ring && self.right.teeth < self.left.teeth + RING_MIN_DIFFERENCE);
+        match (r, q) {
+            (Relation::Teeth, Quantity::Left) => self.compute_l_teeth(),
+            (Relation::Teeth, Quantity::Right) => self.compute_r_teeth(),
+            (Relation::Teeth, _) => self.compute_ratio(),
+            (Relation::Mesh, Quantity::Module | Quantity::CenterDistance) if no_mesh => {}
+            (Relation::Mesh, Quantity::Module) => self.module = self.center_distance / factor,
+            // teeth from the center distance leave the shift out, they get rounded anyway
+            (Relation::Mesh, Quantity::Left) => {
+                let lt = side * (2.0 * self.center_distance / self.geometry.transverse_module(self.module) - self.right.teeth as f32);
+                let right = self.right.teeth as f64;
+                self.left = SideVars::new(self.ratio.round_teeth(lt as f64, |l| right / l));
+                self.compute_ratio();
+            }
+            (Relation::Mesh, Quantity::Right) => {
+                let rt = 2.0 * self.center_distance / self.geometry.transverse_module(self.module) - side * self.left.teeth as f32;
+                let left = self.left.teeth as f64;
+                self.right = SideVars::new(self.ratio.round_teeth(rt as f64, |r| r / left));
+                self.compute_ratio();
+            }
+            (Relation::Mesh, _) => self.center_distance = self.module * factor,
+            (Relation::Speed, Quantity::InputSpeed) => self.input_speed = self.output_speed * self.ratio.actual as f32,
+            (Relation::Speed, Quantity::Ratio) => {
+                self.ratio.set_given(self.input_speed as f64 / self.output_speed as f64);
+                self.compute_ratio();
+            }
+            (Relation::Speed, _) => self.output_speed = self.input_speed / self.ratio.actual as f32,
+        }
+    }
+
+    // recomputes everything that depends on the edited quantities
+    fn recompute_from(&mut self, edited: &[Quantity]) {
+        for (q, r) in self.graph.plan(edited) {
+            self.solve(q, r);
+        }
+        self.recomputed = true;
+    }
+
+    // applies what was typed in the quick entry. what was typed stays, only the rest of the
+    // pair gets solved, whatever is locked.
+    fn apply_quick_entry(&mut self) {
+        let intent = match quick_entry::parse(&self.quick_str) {
+            Ok(i) => i,
+            Err(e) => {
+                self.quick_msg = e;
+                return;
+            }
         };
+        self.quick_msg.clear();
+        if let Some(q) = intent.lock {
+            self.graph.lock_one_of(q, &COLUMNS);
+        }
+        if let Some(t) = intent.left {
+            self.left = SideVars::new(t);
+        }
+        if let Some(t) = intent.right {
+            self.right = SideVars::new(t);
+        }
+        if let Some(r) = intent.ratio {
+            self.ratio.set_given(r);
+        }
+        let locked = COLUMNS.into_iter().find(|q| self.graph.is_locked(*q)).unwrap_or(Quantity::Ratio);
+        let solved = match intent.solve_for(locked) {
+            Some(q) => q,
+            None => {
+                self.quick_str.clear();
+                return;
+            }
+        };
+        self.solve(solved, Relation::Teeth);
+        // the gears were given, so the given ratio is whatever they make
+        if solved == Quantity::Ratio {
+            self.ratio.set_given(self.ratio.actual);
+        }
+        let mesh = if self.graph.is_locked(Quantity::CenterDistance) { Quantity::Module } else { Quantity::CenterDistance };
+        self.solve(mesh, Relation::Mesh);
+        let speed = if self.graph.is_locked(Quantity::OutputSpeed) { Quantity::InputSpeed } else { Quantity::OutputSpeed };
+        self.solve(speed, Relation::Speed);
+        self.recomputed = true;
+        self.quick_str.clear();
     }
 
-    fn gear_column(&mut self, ui: &mut egui::Ui, column: Column) {
+    fn quick_entry_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Quick entry"));
+            let resp = ui.add(egui::TextEdit::singleline(&mut self.quick_str)
+                .hint_text(tr("13 to 40, 3:1 with 15 tooth pinion, ratio 2.6 lock left")));
+            if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                self.apply_quick_entry();
+            }
+        });
+        if !self.quick_msg.is_empty() {
+            ui.colored_label(ui.visuals().warn_fg_color, &self.quick_msg);
+        }
+    }
+
+    fn gear_column(&mut self, ui: &mut egui::Ui, column: Quantity, settings: &Settings) {
+        let name = tr(if column == Quantity::Left { "Input gear teeth" } else { "Output gear teeth" });
         ui.vertical(|ui| {
-            ui.label(egui::RichText::new(
-                if column == Column::Left { "Input Gear" } else { "Output Gear" }
-            ).strong());
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(
+                    tr(if column == Quantity::Left { "Input Gear" } else { "Output Gear" })
+                ).strong());
+                // a single external mesh reverses, a ring gear turns with its pinion
+                let direction = if column == Quantity::Left || self.ring { Direction::Same } else { Direction::Reversed };
+                direction.show(ui);
+            });
 
             let vars = match column {
-                Column::Left => &mut self.left,
+                Quantity::Left => &mut self.left,
                 _            => &mut self.right,
             };
-            let changed = NumberSpinner {
-                ui,
-                value: &mut vars.teeth,
-                val_str: &mut vars.t_str,
-                interactive: column != self.locked_column,
-                step: 1,
-                min_value: 1,
-                max_value: 100000,
-                precision: 1,
-                uiid: column as i32,
-            }.go();
+            // the ring gear keeps its distance to the pinion
+            let range = match (self.ring, column) {
+                (false, _) => settings.min_teeth..=settings.max_teeth,
+                (true, Quantity::Left) => settings.min_teeth..=settings.max_teeth.saturating_sub(RING_MIN_DIFFERENCE).max(settings.min_teeth),
+                (true, _) => (settings.min_teeth + RING_MIN_DIFFERENCE).min(settings.max_teeth)..=settings.max_teeth,
+            };
+            let changed = ui.add(settings.spinner(&mut vars.teeth)
+                .text(&mut vars.t_str)
+                .interactive(!self.graph.is_locked(column))
+                .step(settings.teeth_step)
+                .fine_step(1)
+                .range(range)
+                .label(name)
+                .id_source(("pair", column as i32))).changed();
             if changed {
-                self.recompute_from(column);
+                self.recompute_from(&[column]);
+            }
+            if lock_toggle(ui, self.graph.is_locked(column), name) {
+                self.graph.lock_one_of(column, &COLUMNS);
+            }
+            if column == Quantity::Right {
+                if ui.checkbox(&mut self.ring, tr("Ring gear")).on_hover_text(tr("The pinion runs inside the output gear")).changed() {
+                    self.solve(Quantity::CenterDistance, Relation::Mesh);
+                }
+                if self.ring && self.right.teeth < self.left.teeth + RING_MIN_DIFFERENCE {
+                    ui.colored_label(ui.visuals().error_fg_color, format!("{} {}", tr("A ring gear needs more teeth than its pinion, at least"), RING_MIN_DIFFERENCE));
+                }
             }
-            ui.selectable_value(&mut self.locked_column, column, "locked");
         });
     }
 
-    fn ratio_column(&mut self, ui: &mut egui::Ui) {
+    fn ratio_column(&mut self, ui: &mut egui::Ui, settings: &Settings, favorites: &mut Favorites) {
         ui.vertical(|ui| {
             // given ratio row
             ui.horizontal(|ui| {
-                ui.label(egui::RichText::new("Given Ratio: ").strong());
-                let changed = NumberSpinner {
-                    ui,
-                    value: &mut self.given_ratio,
-                    val_str: &mut self.gr_str,
-                    interactive: self.locked_column != Column::Ratio,
-                    step: 0.1,
-                    min_value: 0.1,
-                    max_value: 100.0,
-                    precision: 2,
-                    uiid: Column::Ratio as i32,
-                }.go();
+                ui.label(egui::RichText::new(tr("Given Ratio: ")).strong());
+                let changed = ui.add(settings.spinner(&mut self.ratio.given)
+                    .interactive(!self.graph.is_locked(Quantity::Ratio))
+                    .step(settings.ratio_step)
+                    .fine_step(settings.ratio_step / 10.0)
+                    .range(0.1..=100.0)
+                    .precision(2)
+                    .label(tr("Given ratio"))
+                    .id_source(("pair", Quantity::Ratio as i32))).changed();
                 if changed {
-                    self.recompute_from(Column::Ratio);
+                    self.recompute_from(&[Quantity::Ratio]);
                 }
+                favorites.star(ui, self.ratio.given);
             });
+            if let Some(r) = favorites.ui(ui) {
+                self.ratio.set_given(r);
+                self.recompute_from(&[Quantity::Ratio]);
+            }
 
             // actual ratio row
             ui.horizontal(|ui| {
-                ui.label(egui::RichText::new("Actual Ratio: ").strong());
-                ui.label(&self.ar_str);
+                let label_id = ui.label(egui::RichText::new(tr("Actual Ratio: ")).strong()).id;
+                let error = self.ratio.error();
+                let color = self.ratio.band.color(ui.visuals(), error / 100.0);
+                ui.colored_label(color, settings.number(self.ratio.actual, settings.decimals)).labelled_by(label_id);
+                // reduced, as it goes on drawings
+                ui.label(format!("= {}", self.design().fraction()))
+                    .on_hover_text(tr("Output teeth / input teeth, reduced"));
+                // the plain difference of two ratios is hard to judge, the relative one isn't
+                let sign = if error < 0.0 { "−" } else { "+" };
+                ui.colored_label(color, format!("({}{} %)", sign, settings.number(error.abs(), 3)))
+                    .on_hover_text(tr("Error against the given ratio"));
+                self.ratio.band.mark(ui, self.ratio.error() / 100.0);
+                // carry on from what the whole teeth gave
+                let adopt = ui.add_enabled(self.ratio.actual != self.ratio.given, egui::Button::new(tr("Adopt")).small())
+                    .on_hover_text(tr("Use the actual ratio as the given ratio"));
+                if adopt.clicked() {
+                    self.ratio.set_given(self.ratio.actual);
+                }
+            });
+            if let Some(p) = self.ratio.problem {
+                ui.colored_label(ui.visuals().error_fg_color, tr(p));
+            }
+            if let Some(w) = self.ratio.warning() {
+                ui.colored_label(ui.visuals().warn_fg_color, w);
+                feedback::note_warning(ui.ctx());
+            }
+            self.ratio.policy_ui(ui);
+
+            if lock_toggle(ui, self.graph.is_locked(Quantity::Ratio), tr("Given ratio")) {
+                self.graph.lock_one_of(Quantity::Ratio, &COLUMNS);
+            }
+        });
+    }
+
+    fn quantity_row(&mut self, ui: &mut egui::Ui, q: Quantity, units: Units) {
+        let (label, speed, suffix) = match q {
+            Quantity::Module => (units.pitch_name(), 0.01, if units == Units::Metric { " mm" } else { "" }),
+            Quantity::CenterDistance => ("Center distance", 0.1, units.length_suffix()),
+            Quantity::InputSpeed => ("Input speed", 1.0, " rpm"),
+            _ => ("Output speed", 1.0, " rpm"),
+        };
+        let label_id = ui.label(tr(label)).id;
+        let locked = self.graph.is_locked(q);
+        let value = match q {
+            Quantity::Module => &mut self.module,
+            Quantity::CenterDistance => &mut self.center_distance,
+            Quantity::InputSpeed => &mut self.input_speed,
+            _ => &mut self.output_speed,
+        };
+        // the values are kept in mm
+        let mut shown = units.show(q, *value);
+        let changed = ui.add_enabled(!locked, egui::DragValue::new(&mut shown)
+            .speed(speed)
+            .clamp_range(0.001..=1_000_000.0)
+            .max_decimals(3)
+            .suffix(suffix)).labelled_by(label_id).changed();
+        if changed {
+            *value = units.store(q, shown);
+            self.recompute_from(&[q]);
+        }
+        if lock_toggle(ui, locked, tr(label)) {
+            self.graph.set_locked(q, !locked);
+        }
+        ui.end_row();
+    }
+
+    fn mesh_ui(&mut self, ui: &mut egui::Ui, units: Units) {
+        egui::CollapsingHeader::new(tr("Mesh and speeds")).id_source("mesh_and_speeds").show(ui, |ui| {
+            egui::Grid::new("pair_quantities").show(ui, |ui| {
+                for q in [Quantity::Module, Quantity::CenterDistance, Quantity::InputSpeed, Quantity::OutputSpeed] {
+                    self.quantity_row(ui, q, units);
+                }
+            });
+            ui.separator();
+            if self.geometry.ui(ui) {
+                self.solve(Quantity::CenterDistance, Relation::Mesh);
+            }
+            let (left, right) = (self.left.teeth, self.right.teeth);
+            let needed = self.module * self.geometry.center_factor(left, right, self.ring);
+            if (needed - self.center_distance).abs() > 0.005 {
+                ui.colored_label(ui.visuals().warn_fg_color, format!("{} {:.3}{}.",
+                    tr("The teeth need a center distance of"), units.show(Quantity::CenterDistance, needed), units.length_suffix()));
+                let shift = self.geometry.shift_for(self.center_distance / self.module, left, right, self.ring);
+                let fit = ui.add_enabled(shift.is_some(), egui::Button::new(tr("Fit with profile shift")))
+                    .on_hover_text(tr("Changes x2 so the gears mesh at this center distance"))
+                    .on_disabled_hover_text(tr("No profile shift gets the gears there"));
+                if let (true, Some(shift)) = (fit.clicked(), shift) {
+                    // x2 takes what x1 doesn't
+                    let x1 = self.geometry.shift[0];
+                    self.geometry.shift[1] = if self.ring { shift + x1 } else { shift - x1 };
+                }
+            }
+            let pair = self.pair();
+            let contact = self.geometry.contact_ratio(&pair);
+            ui.horizontal(|ui| {
+                let label_id = ui.label(tr("Mesh frequency")).id;
+                ui.label(format!("{:.1} Hz", left as f32 * self.input_speed / 60.0)).labelled_by(label_id);
+            });
+            ui.horizontal(|ui| {
+                let label_id = ui.label(tr("Contact ratio")).id;
+                ui.label(format!("{:.3}", contact)).labelled_by(label_id);
             });
+            if contact < 1.2 {
+                ui.colored_label(ui.visuals().warn_fg_color, tr("Below 1.2 the mesh runs rough, fewer teeth are in contact than needed."));
+            }
+            self.geometry.export_ui(ui, &pair);
+            // a ring gear can't be undercut by a rack
+            let gears = if self.ring { &[(left, 0)][..] } else { &[(left, 0), (right, 1)][..] };
+            for &(teeth, i) in gears {
+                let shift = self.geometry.shift[i];
+                if (teeth as f32) < self.geometry.min_teeth(shift) {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("{} {} {:.3}.",
+                        tr(if i == 0 { "Input gear teeth" } else { "Output gear teeth" }),
+                        tr("get undercut, needs a profile shift of at least"),
+                        self.geometry.min_shift(teeth)));
+                }
+            }
+        });
+    }
+
+    fn pair(&self) -> geometry::Pair {
+        geometry::Pair {
+            module: self.module,
+            left: self.left.teeth,
+            right: self.right.teeth,
+            ring: self.ring,
+            center_distance: self.center_distance,
+        }
+    }
+
+    fn strength_ui(&mut self, ui: &mut egui::Ui, settings: &Settings) {
+        egui::CollapsingHeader::new(tr("Strength")).id_source("strength").show(ui, |ui| {
+            let pair = self.pair();
+            self.strength.ui(ui, &pair, self.geometry.helix, self.input_speed, &settings.materials);
+        });
+    }
 
-            ui.selectable_value(&mut self.locked_column, Column::Ratio, "locked");
+    // the gears as discs of their pitch diameter and the face width of the strength check
+    fn inertia_ui(&mut self, ui: &mut egui::Ui, settings: &Settings) {
+        egui::CollapsingHeader::new(tr("Mass and inertia")).id_source("mass_and_inertia").show(ui, |ui| {
+            let density = |gear: usize| settings.materials.get(self.strength.materials[gear]).map_or(1.0, |m| m.density);
+            let width = self.strength.face_width;
+            let m = self.geometry.transverse_module(self.module);
+            let (d1, d2) = (m * self.left.teeth as f32, m * self.right.teeth as f32);
+            let output = if self.ring {
+                Body::tube(density(1), d2, d2 + 8.0 * self.module, width)
+            } else {
+                Body::tube(density(1), 0.0, d2, width)
+            };
+            let gears = inertia::train_ui(ui, &[
+                (tr("Input gear").to_string(), Body::tube(density(0), 0.0, d1, width), 1.0),
+                (tr("Output gear").to_string(), output, self.ratio.actual as f32),
+            ]);
+            ui.weak(tr("Face width and materials are the ones of the strength check."));
+
+            ui.separator();
+            ui.label(egui::RichText::new(tr("Inertia matching")).strong());
+            if let Some(i) = self.matching_inertia.ui(ui, self.ratio.actual as f32, gears) {
+                self.ratio.set_given(i as f64);
+                self.recompute_from(&[Quantity::Ratio]);
+            }
         });
     }
 
+    fn url_fragment(&self) -> String {
+        let lock = COLUMNS.into_iter().find(|q| self.graph.is_locked(*q)).unwrap_or(Quantity::Ratio);
+        url_state::encode(self.left.teeth, self.right.teeth, self.ratio.given, lock)
+    }
+
+    // sets the pair from a link as it is, unlike the quick entry nothing gets rounded
+    fn apply_link(&mut self, link: quick_entry::Intent) {
+        if let Some(q) = link.lock {
+            self.graph.lock_one_of(q, &COLUMNS);
+        }
+        if let Some(t) = link.left {
+            self.left = SideVars::new(t);
+        }
+        if let Some(t) = link.right {
+            self.right = SideVars::new(t);
+        }
+        self.compute_ratio();
+        self.ratio.set_given(link.ratio.unwrap_or(self.ratio.actual));
+        self.solve(Quantity::CenterDistance, Relation::Mesh);
+        self.solve(Quantity::OutputSpeed, Relation::Speed);
+    }
+
+    fn design(&self) -> Design {
+        Design {
+            name: self.name.clone(),
+            left: self.left.teeth,
+            right: self.right.teeth,
+            ratio: self.ratio.clone(),
+            module: self.module,
+            center_distance: self.center_distance,
+            input_speed: self.input_speed,
+            output_speed: self.output_speed,
+        }
+    }
+
+    // the pair as text for chats and forum posts
+    fn summary(&self, multi_line: bool) -> String {
+        let (l, r) = (self.left.teeth, self.right.teeth);
+        let fraction = self.design().fraction();
+        if multi_line {
+            format!("Input gear: {} T\nOutput gear: {} T\nRatio: {} = {:.4}\nGiven ratio: {:.4} (error {:+.3} %)\n",
+                l, r, fraction, self.ratio.actual, self.ratio.given, self.ratio.error())
+        } else {
+            format!("{}T : {}T, ratio {} = {:.4} ({:+.3} % off {:.4})",
+                l, r, fraction, self.ratio.actual, self.ratio.error(), self.ratio.given)
+        }
+    }
+
+    fn copy_ui(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button(tr("Copy")).on_hover_text(self.summary(false)).clicked() {
+                ui.output_mut(|o| o.copied_text = self.summary(false));
+            }
+            if ui.button(tr("Copy details")).on_hover_text(self.summary(true)).clicked() {
+                ui.output_mut(|o| o.copied_text = self.summary(true));
+            }
+        });
+    }
+
+    fn pair_ui(&mut self, ui: &mut egui::Ui, settings: &Settings, favorites: &mut Favorites) {
+        self.quick_entry_ui(ui);
+        if let Some(q) = lock_shortcut(ui) {
+            self.graph.lock_one_of(q, &COLUMNS);
+        }
+        ui.with_layout(columns_layout(ui), |ui| {
+            self.gear_column(ui, Quantity::Left, settings);
+            self.ratio_column(ui, settings, favorites);
+            self.gear_column(ui, Quantity::Right, settings);
+        });
+        self.copy_ui(ui);
+        self.mesh_ui(ui, settings.units);
+        self.strength_ui(ui, settings);
+        self.inertia_ui(ui, settings);
+        // a single pair can't do it, maybe two can
+        if self.ratio.actual != self.ratio.given {
+            egui::CollapsingHeader::new(tr("Compound pairs")).id_source("compound_pairs").show(ui, |ui| {
+                ui.label(format!("{} {} % {}", tr("Trains of two pairs within"), self.ratio.band.percent, tr("of the given ratio.")));
+                self.compound.ui(ui, self.ratio.given, &self.ratio.band);
+            });
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, inventory: &Dataset, catalog: &Dataset, settings: &Settings, favorites: &mut Favorites) {
+        ui.heading(tr(self.mode.name()));
+        match self.mode {
+            Mode::Pair => self.pair_ui(ui, settings, favorites),
+            Mode::Belt => self.belt.ui(ui),
+            Mode::Pulley => self.pulley.ui(ui, settings),
+            Mode::Match => self.matching.ui(ui),
+            Mode::Reratio => self.reratio.ui(ui),
+            Mode::Search => self.search.ui(ui, &self.ratio.band),
+            Mode::Bicycle => self.bicycle.ui(ui),
+            Mode::Clock => self.clock.ui(ui, &self.ratio.band),
+            Mode::MotionWorks => self.motion_works.ui(ui),
+            Mode::Approx => self.approx.ui(ui, &self.ratio.band),
+            Mode::Sidereal => self.sidereal.ui(ui, &self.ratio.band),
+            Mode::Gearbox => self.gearbox.ui(ui, &self.ratio.band),
+            Mode::Vehicle => self.vehicle.ui(ui),
+            Mode::Cvt => self.cvt.ui(ui),
+            Mode::Cycloidal => self.cycloidal.ui(ui, &self.ratio.band),
+            Mode::Planetary => self.planetary.ui(ui),
+            Mode::Differential => self.differential.ui(ui),
+            Mode::Lathe => self.lathe.ui(ui, inventory, &self.ratio.band),
+            Mode::Stepper => self.stepper.ui(ui, self.ratio.actual as f32),
+            Mode::Train => self.gear_train.ui(ui, inventory, catalog, &settings.materials),
+            Mode::Rc => {
+                self.pair_ui(ui, settings, favorites);
+                ui.separator();
+                self.rc.ui(ui, self.ratio.actual as f32);
+            }
+        }
+    }
+}
+
+impl RitzelApp {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        // the built in defaults, with the config file of the native app on top
+        #[cfg(not(target_arch = "wasm32"))]
+        let config = config::settings_text();
+        #[cfg(target_arch = "wasm32")]
+        let config: Option<String> = None;
+        let defaults = || {
+            let mut settings = Settings::new();
+            if let Some(text) = &config {
+                settings.load_text(text);
+            }
+            settings
+        };
+        let mut app = RitzelApp {
+            tabs: Vec::new(),
+            tab: 0,
+            inventory: dataset::default_inventory(),
+            show_datasets: false,
+            conversions: Conversions::new(),
+            show_conversions: false,
+            classroom: Classroom::new(),
+            show_classroom: false,
+            audio: Audio::new(),
+            mesh_tone: MeshTone::new(),
+            show_mesh_tone: false,
+            sweep: Sweep::new(),
+            show_sweep: false,
+            motors: dataset::default_motors(),
+            catalog: presets::default_catalog(),
+            motor_output: MotorOutput::new(),
+            show_motor_output: false,
+            accuracy: Accuracy::new(),
+            show_accuracy: false,
+            history: History::new(),
+            show_history: false,
+            compare: Compare::new(),
+            show_compare: false,
+            report: Report::new(),
+            show_report: false,
+            project: Project::new(),
+            show_attachments: false,
+            fragment: String::new(),
+            settings: defaults(),
+            defaults: defaults(),
+            favorites: Favorites::new(),
+            show_settings: false,
+        };
+
+        // the user's datasets and settings survive restarts
+        if let Some(storage) = cc.storage {
+            for data in app.datasets_mut() {
+                if let Some(text) = storage.get_string(&data.storage_key()) {
+                    data.load_text(&text);
+                }
+            }
+            if let Some(text) = storage.get_string("settings") {
+                app.settings.load_text(&text);
+            }
+            app.history.persist = storage.get_string("history_persist").as_deref() == Some("true");
+            if let Some(text) = storage.get_string("history") {
+                app.history.load_text(&text);
+            }
+            if let Some(text) = storage.get_string("favorites") {
+                app.favorites.load_text(&text);
+            }
+        }
+        i18n::set_language(app.settings.language);
+        app.tabs.push(Calculation::new(format!("{} 1", tr("Calculation")), &app.settings));
+        // a shared link, or the command line of the native app
+        if let Some(f) = url_state::fragment() {
+            app.tabs[0].apply_link(url_state::decode(&f));
+        }
+
+        app
+    }
+
+    fn datasets_mut(&mut self) -> [&mut Dataset; 3] {
+        [&mut self.inventory, &mut self.motors, &mut self.catalog]
+    }
+
+    fn tabs_ui(&mut self, ui: &mut egui::Ui) {
+        let mut close = None;
+        ui.horizontal(|ui| {
+            for (i, t) in self.tabs.iter().enumerate() {
+                let resp = ui.selectable_label(i == self.tab, &t.name);
+                if resp.clicked() {
+                    self.tab = i;
+                }
+                if self.tabs.len() > 1 && resp.middle_clicked() {
+                    close = Some(i);
+                }
+            }
+            if ui.button("+").on_hover_text(tr("new calculation")).clicked() {
+                self.tabs.push(Calculation::new(format!("{} {}", tr("Calculation"), self.tabs.len() + 1), &self.settings));
+                self.tab = self.tabs.len() - 1;
+            }
+            ui.separator();
+            ui.add(egui::TextEdit::singleline(&mut self.tabs[self.tab].name).desired_width(120.0));
+            if ui.add_enabled(self.tabs.len() > 1, egui::Button::new(tr("Close tab"))).clicked() {
+                close = Some(self.tab);
+            }
+        });
+        if let Some(i) = close {
+            self.tabs.remove(i);
+            if self.tab > i || self.tab == self.tabs.len() {
+                self.tab -= 1;
+            }
+        }
+    }
 }
 
 impl eframe::App for RitzelApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Gear Ratio Calculator");
-            ui.horizontal(|ui| {
-                // labels
-                ui.horizontal(|ui| {
-                    self.gear_column(ui, Column::Left);
-                    self.ratio_column(ui);
-                    self.gear_column(ui, Column::Right);
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        for data in self.datasets_mut() {
+            storage.set_string(&data.storage_key(), data.to_text());
+        }
+        storage.set_string("settings", self.settings.changed_text(&self.defaults));
+        storage.set_string("history_persist", self.history.persist.to_string());
+        storage.set_string("history", if self.history.persist { self.history.to_text() } else { String::new() });
+        storage.set_string("favorites", self.favorites.to_text());
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        i18n::set_language(self.settings.language);
+        if let Some(dark) = self.settings.theme.dark(frame.info().system_theme) {
+            if ctx.style().visuals.dark_mode != dark {
+                ctx.set_visuals(if dark { egui::Visuals::dark() } else { egui::Visuals::light() });
+            }
+        }
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button(tr("Mode"), |ui| {
+                    for m in Mode::ALL {
+                        if ui.selectable_value(&mut self.tabs[self.tab].mode, m, tr(m.name())).clicked() {
+                            ui.close_menu();
+                        }
+                    }
+                });
+                if ui.button(tr("Settings")).clicked() {
+                    self.show_settings = true;
+                }
+                ui.menu_button(tr("Tolerance"), |ui| {
+                    self.tabs[self.tab].ratio.band.ui(ui);
+                });
+                ui.menu_button(tr("Tools"), |ui| {
+                    ui.checkbox(&mut self.show_conversions, tr("Conversions"));
+                    ui.checkbox(&mut self.show_datasets, tr("Datasets"));
+                    ui.checkbox(&mut self.show_classroom, tr("Classroom"));
+                    ui.checkbox(&mut self.show_mesh_tone, tr("Mesh Tone"));
+                    ui.checkbox(&mut self.show_sweep, tr("Sweep"));
+                    ui.checkbox(&mut self.show_motor_output, tr("Motor Output"));
+                    ui.checkbox(&mut self.show_accuracy, tr("Error vs Teeth"));
+                    ui.checkbox(&mut self.show_history, tr("History"));
+                    ui.checkbox(&mut self.show_compare, tr("Compare"));
+                    ui.checkbox(&mut self.show_report, tr("Report"));
+                    ui.checkbox(&mut self.show_attachments, tr("Attachments"));
+                    ui.menu_button(tr("Feedback"), |ui| {
+                        ui.add_enabled(Audio::available(), egui::Checkbox::new(&mut self.settings.feedback.sound, tr("Click sounds")))
+                            .on_disabled_hover_text(tr("built without the \"audio\" feature"));
+                        ui.add_enabled(Feedback::haptics_available(), egui::Checkbox::new(&mut self.settings.feedback.haptics, tr("Vibration")))
+                            .on_disabled_hover_text(tr("only available in the browser"));
+                    });
                 });
             });
+            self.tabs_ui(ui);
+        });
+
+        let ratio = self.tabs[self.tab].current_ratio();
+        egui::Window::new(tr("Conversions")).id(egui::Id::new("conversions")).open(&mut self.show_conversions).show(ctx, |ui| {
+            self.conversions.ui(ui, ratio);
+        });
+
+        let mut show_datasets = self.show_datasets;
+        egui::Window::new(tr("Datasets")).id(egui::Id::new("datasets")).open(&mut show_datasets).show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for data in self.datasets_mut() {
+                    egui::CollapsingHeader::new(tr(data.name)).id_source(data.name).default_open(true).show(ui, |ui| {
+                        dataset::dataset_editor(ui, data);
+                    });
+                }
+            });
+        });
+        self.show_datasets = show_datasets;
+
+        egui::Window::new(tr("Motor Output")).id(egui::Id::new("motor_output")).open(&mut self.show_motor_output).show(ctx, |ui| {
+            self.motor_output.ui(ui, &self.motors, ratio);
+        });
+
+        egui::Window::new(tr("Classroom")).id(egui::Id::new("classroom")).open(&mut self.show_classroom).show(ctx, |ui| {
+            self.classroom.ui(ui);
+        });
+
+        egui::Window::new(tr("Mesh Tone")).id(egui::Id::new("mesh_tone")).open(&mut self.show_mesh_tone).show(ctx, |ui| {
+            self.mesh_tone.ui(ui, self.tabs[self.tab].left.teeth, &mut self.audio);
+        });
+
+        egui::Window::new(tr("Sweep")).id(egui::Id::new("sweep")).open(&mut self.show_sweep).show(ctx, |ui| {
+            self.sweep.ui(ui, &self.tabs[self.tab].ratio);
+        });
+
+        egui::Window::new(tr("Error vs Teeth")).id(egui::Id::new("error_vs_teeth")).open(&mut self.show_accuracy).show(ctx, |ui| {
+            self.accuracy.ui(ui, self.tabs[self.tab].ratio.given);
+        });
+
+        egui::Window::new(tr("Settings")).id(egui::Id::new("settings")).open(&mut self.show_settings).show(ctx, |ui| {
+            self.settings.ui(ui);
+        });
+
+        egui::Window::new(tr("Compare")).id(egui::Id::new("compare")).open(&mut self.show_compare).show(ctx, |ui| {
+            let calc = &self.tabs[self.tab];
+            self.compare.ui(ui, (calc.left.teeth, calc.right.teeth), &calc.ratio);
+        });
+
+        egui::Window::new(tr("Report")).id(egui::Id::new("report")).open(&mut self.show_report).show(ctx, |ui| {
+            let calc = &self.tabs[self.tab];
+            self.report.ui(ui, &calc.design(), calc.search.results(), &self.project.attachments);
+        });
+
+        if self.project.take_dropped(ctx) {
+            self.show_attachments = true;
+        }
+        let pair = self.tabs[self.tab].url_fragment();
+        let opened = egui::SidePanel::left("attachments").show_animated(ctx, self.show_attachments, |ui| {
+            ui.heading(tr("Attachments"));
+            self.project.ui(ui, &pair)
         });
+        if let Some(pair) = opened.and_then(|r| r.inner) {
+            self.tabs[self.tab].apply_link(url_state::decode(&pair));
+        }
+
+        self.history.note(ctx, self.tabs[self.tab].history_entry());
+        let history_ui = |ui: &mut egui::Ui| {
+            ui.heading(tr("History"));
+            self.history.ui(ui)
+        };
+        let panel = if ctx.screen_rect().width() < COMPACT_WIDTH {
+            egui::TopBottomPanel::bottom("history_bottom").resizable(true).default_height(200.0).show_animated(ctx, self.show_history, history_ui)
+        } else {
+            egui::SidePanel::right("history").show_animated(ctx, self.show_history, history_ui)
+        };
+        if let Some(e) = panel.and_then(|r| r.inner) {
+            self.tabs[self.tab].restore(e);
+        }
+
+        let calc = &mut self.tabs[self.tab];
+        egui::CentralPanel::default().show(ctx, |ui| {
+            calc.ui(ui, &self.inventory, &self.catalog, &self.settings, &mut self.favorites);
+        });
+        if std::mem::take(&mut calc.recomputed) {
+            self.classroom.record(calc.left.teeth, calc.right.teeth, calc.ratio.actual as f32);
+        }
+
+        let fragment = self.tabs[self.tab].url_fragment();
+        if fragment != self.fragment {
+            url_state::set_fragment(&fragment);
+            self.fragment = fragment;
+        }
+
+        self.settings.feedback.update(ctx, &mut self.audio);
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entered(text: &str, locked: Quantity) -> Calculation {
+        let mut calc = Calculation::new(String::from("test"), &Settings::new());
+        calc.graph.lock_one_of(locked, &COLUMNS);
+        calc.quick_str = text.to_string();
+        calc.apply_quick_entry();
+        calc
+    }
+
+    #[test]
+    fn quick_entry_ratio_only() {
+        let calc = entered("ratio 3", Quantity::Left);
+        assert_eq!(calc.ratio.given, 3.0);
+        assert_eq!(calc.right.teeth, 3 * calc.left.teeth);
+        let calc = entered("ratio 3", Quantity::Right);
+        assert_eq!(calc.right.teeth, 3 * calc.left.teeth);
+        assert!(calc.quick_msg.is_empty());
+    }
+
+    #[test]
+    fn quick_entry_keeps_the_typed_pinion() {
+        for locked in COLUMNS {
+            let calc = entered("3:1 with 15 tooth pinion", locked);
+            assert_eq!((calc.left.teeth, calc.right.teeth), (15, 45));
+            assert_eq!(calc.ratio.given, 3.0);
+        }
+    }
+
+    #[test]
+    fn quick_entry_keeps_the_typed_gear() {
+        for locked in COLUMNS {
+            let calc = entered("2:1 with 40 teeth wheel", locked);
+            assert_eq!((calc.left.teeth, calc.right.teeth), (20, 40));
+        }
+        let calc = entered("13 to 40", Quantity::Ratio);
+        assert_eq!((calc.left.teeth, calc.right.teeth), (13, 40));
+        assert_eq!(calc.ratio.given, 40.0 / 13.0);
+    }
+
+    #[test]
+    fn quick_entry_bad_input_changes_nothing() {
+        let before = Calculation::new(String::from("test"), &Settings::new());
+        let calc = entered("banana", Quantity::Ratio);
+        assert_eq!((calc.left.teeth, calc.right.teeth), (before.left.teeth, before.right.teeth));
+        assert_eq!(calc.ratio.given, before.ratio.given);
+        assert!(!calc.quick_msg.is_empty());
+        assert_eq!(calc.quick_str, "banana");
+    }
+
+    #[test]
+    fn ring_too_small_keeps_the_mesh() {
+        let mut calc = Calculation::new(String::from("test"), &Settings::new());
+        calc.left = SideVars::new(20);
+        calc.right = SideVars::new(25);
+        calc.solve(Quantity::CenterDistance, Relation::Mesh);
+        let (module, center_distance) = (calc.module, calc.center_distance);
+        calc.ring = true;
+        calc.solve(Quantity::CenterDistance, Relation::Mesh);
+        calc.solve(Quantity::Module, Relation::Mesh);
+        assert_eq!((calc.module, calc.center_distance), (module, center_distance));
+        calc.right = SideVars::new(60);
+        calc.solve(Quantity::CenterDistance, Relation::Mesh);
+        assert_eq!(calc.center_distance, module * 20.0);
+    }
+}
@@ -0,0 +1,177 @@
+use eframe::egui;
+
+use crate::band::Band;
+use crate::i18n::tr;
+use crate::search::{describe, Candidate};
+use crate::train::{Stage, Train};
+use crate::worker::{self, Job, Progress};
+
+/*
+ * Close approximations of irrational ratios like 365.2422 for orreries and astronomical
+ * clocks. The product of the driven gears over the product of the driver gears is a
+ * fraction p/q. Instead of searching stage by stage, every q that is a product of the
+ * allowed tooth counts is tried with the best p for it, and kept if p factors into as many
+ * allowed tooth counts as well. Which driver is paired with which driven gear doesn't
+ * change the ratio, they are paired by size.
+ */
+
+pub const MAX_STAGES: usize = 4;
+
+// all distinct products of `count` tooth counts in the range, with non-decreasing factors
+fn products(count: usize, min: u32, max: u32) -> Vec<u64> {
+    fn rec(count: usize, from: u32, max: u32, acc: u64, out: &mut Vec<u64>) {
+        if count == 0 {
+            out.push(acc);
+            return;
+        }
+        for t in from..=max {
+            rec(count - 1, t, max, acc * t as u64, out);
+        }
+    }
+    let mut out = Vec::new();
+    rec(count, min, max, 1, &mut out);
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+// splits n into `count` tooth counts in the range, largest first
+pub fn factor(n: u64, count: usize, min: u32, max: u32) -> Option<Vec<u32>> {
+    if count == 0 {
+        return if n == 1 { Some(Vec::new()) } else { None };
+    }
+    if n < (min as u64).pow(count as u32) || n > (max as u64).pow(count as u32) {
+        return None;
+    }
+    if count == 1 {
+        return Some(vec![n as u32]);
+    }
+    for t in (min..=max).rev() {
+        if n % t as u64 == 0 {
+            if let Some(mut rest) = factor(n / t as u64, count - 1, min, t) {
+                rest.insert(0, t);
+                return Some(rest);
+            }
+        }
+    }
+    None
+}
+
+// the best `top_n` trains with exactly `stages` stages
+// None when cancelled
+pub fn approximate(target: f64, stages: usize, min: u32, max: u32, top_n: usize, progress: &Progress) -> Option<Vec<Candidate>> {
+    let mut best: Vec<Candidate> = Vec::new();
+    if target <= 0.0 {
+        return Some(best);
+    }
+    let qs = products(stages, min, max);
+    for (i, &q) in qs.iter().enumerate() {
+        if i % 4096 == 0 && !progress.report(i as f32 / qs.len() as f32) {
+            return None;
+        }
+        let exact = target * q as f64;
+        for p in [exact.round(), exact.floor(), exact.ceil()] {
+            let p = p as u64;
+            let ratio = p as f64 / q as f64;
+            let error = (ratio - target).abs() / target;
+            if best.len() >= top_n && error >= best[top_n - 1].error {
+                continue;
+            }
+            if best.iter().any(|c| (c.ratio - ratio).abs() < f64::EPSILON * ratio) {
+                continue;
+            }
+            let (driven, drivers) = match (factor(p, stages, min, max), factor(q, stages, min, max)) {
+                (Some(a), Some(b)) => (a, b),
+                _ => continue,
+            };
+            let train: Vec<Stage> = drivers.iter().zip(&driven).map(|(&d, &n)| Stage::new(d, n)).collect();
+            let c = Candidate { train: Train::new(&train), ratio, error };
+            let pos = best.iter().position(|b| error < b.error).unwrap_or(best.len());
+            best.insert(pos, c);
+            best.truncate(top_n);
+        }
+    }
+    Some(best)
+}
+
+pub struct Approximation {
+    target: f64,
+    min_teeth: u32,
+    max_teeth: u32,
+    stages: usize,
+    top_n: usize,
+    results: Vec<Vec<Candidate>>,
+    job: Option<Job<Vec<Vec<Candidate>>>>,
+}
+
+impl Approximation {
+    pub fn new() -> Approximation {
+        Approximation {
+            target: 365.2422,
+            min_teeth: 10,
+            max_teeth: 80,
+            stages: 3,
+            top_n: 5,
+            results: Vec::new(),
+            job: None,
+        }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.target as f32
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, band: &Band) {
+        egui::Grid::new("approx_params").show(ui, |ui| {
+            ui.label(tr("Target ratio"));
+            ui.add(egui::DragValue::new(&mut self.target).speed(0.01).clamp_range(0.0001..=100000.0).max_decimals(8));
+            ui.end_row();
+            ui.label(tr("Teeth per gear"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.min_teeth).clamp_range(4..=self.max_teeth));
+                ui.label(tr("to"));
+                ui.add(egui::DragValue::new(&mut self.max_teeth).clamp_range(self.min_teeth..=200));
+            });
+            ui.end_row();
+            ui.label(tr("Stages"));
+            ui.add(egui::Slider::new(&mut self.stages, 2..=MAX_STAGES).text(tr("up to")));
+            ui.end_row();
+            ui.label(tr("Results per stage count"));
+            ui.add(egui::DragValue::new(&mut self.top_n).clamp_range(1..=50));
+            ui.end_row();
+        });
+        if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Approximate"))).clicked() {
+            let (target, stages, min, max, top_n) = (self.target, self.stages, self.min_teeth, self.max_teeth, self.top_n);
+            self.job = Some(Job::spawn(ui.ctx(), move |p| {
+                (2..=stages)
+                    .map(|n| approximate(target, n, min, max, top_n, &p.part(n - 2, stages - 1)))
+                    .collect()
+            }));
+        }
+        if let Some(r) = worker::job_ui(ui, &mut self.job) {
+            self.results = r;
+        }
+        ui.label(tr("Four stages with a wide tooth range take a while."));
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, group) in self.results.iter().enumerate() {
+                ui.separator();
+                ui.label(egui::RichText::new(format!("{} {}", i + 2, tr("stages"))).strong());
+                if group.is_empty() {
+                    ui.label(tr("Nothing fits into the tooth range."));
+                } else if !group.iter().any(|c| band.shows(c.error)) {
+                    band.empty_note(ui);
+                }
+                egui::Grid::new(("approx_results", i)).striped(true).show(ui, |ui| {
+                    for c in group.iter().filter(|c| band.shows(c.error)) {
+                        ui.label(describe(&c.train));
+                        ui.label(format!("{:.8}", c.ratio));
+                        ui.label(format!("{:.3} ppm", c.error * 1e6));
+                        band.mark(ui, c.error);
+                        ui.end_row();
+                    }
+                });
+            }
+        });
+    }
+}
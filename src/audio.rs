@@ -0,0 +1,58 @@
+/*
+ * Sound output for the native build, behind the "audio" feature.
+ * Without the feature (or in the browser) everything here is a no-op.
+ */
+
+pub struct Audio {
+    #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+    output: Option<(rodio::OutputStream, rodio::OutputStreamHandle)>,
+}
+
+impl Audio {
+    pub fn new() -> Audio {
+        Audio {
+            #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+            output: None,
+        }
+    }
+
+    // true if this build can make any sound at all
+    pub fn available() -> bool {
+        cfg!(all(feature = "audio", not(target_arch = "wasm32")))
+    }
+
+    // plays sine waves of the given (frequency, amplitude) pairs at the same time.
+    // returns false if there is no sound output.
+    #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+    pub fn tone(&mut self, waves: &[(f32, f32)], millis: u64) -> bool {
+        use rodio::Source;
+        // the output is opened lazily, most people never use sound
+        if self.output.is_none() {
+            match rodio::OutputStream::try_default() {
+                Ok(o) => self.output = Some(o),
+                Err(e) => {
+                    log::warn!("no audio output: {}", e);
+                    return false;
+                }
+            }
+        }
+        if let Some((_, handle)) = &self.output {
+            // the output mixes everything that plays at the same time
+            for (freq, amplitude) in waves {
+                let wave = rodio::source::SineWave::new(*freq)
+                    .take_duration(std::time::Duration::from_millis(millis))
+                    .amplify(*amplitude);
+                if let Err(e) = handle.play_raw(wave) {
+                    log::warn!("can't play sound: {}", e);
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    #[cfg(not(all(feature = "audio", not(target_arch = "wasm32"))))]
+    pub fn tone(&mut self, _waves: &[(f32, f32)], _millis: u64) -> bool {
+        false
+    }
+}
@@ -0,0 +1,71 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * The acceptable ratio error. Every search and the gear pair mark whether a result falls
+ * inside the band, and the result tables can hide the ones that don't.
+ *
+ * The actual ratio of the pair is green inside the band, yellow up to ten times its width
+ * and red beyond.
+ */
+
+// how many band widths an error may be off before it's red
+const YELLOW: f64 = 10.0;
+
+#[derive(Clone, Copy)]
+pub struct Band {
+    // half the width of the band, in percent
+    pub percent: f64,
+    // hide results outside the band
+    pub filter: bool,
+}
+
+impl Band {
+    pub fn new() -> Band {
+        Band { percent: 1.0, filter: false }
+    }
+
+    // error is relative, 0.01 for 1 %
+    pub fn contains(&self, error: f64) -> bool {
+        error.abs() * 100.0 <= self.percent + 1e-9
+    }
+
+    // green, yellow or red for a relative error
+    pub fn color(&self, visuals: &egui::Visuals, error: f64) -> egui::Color32 {
+        if self.contains(error) {
+            if visuals.dark_mode { egui::Color32::LIGHT_GREEN } else { egui::Color32::DARK_GREEN }
+        } else if error.abs() * 100.0 <= self.percent * YELLOW {
+            visuals.warn_fg_color
+        } else {
+            visuals.error_fg_color
+        }
+    }
+
+    // whether a result with this error goes into the table
+    pub fn shows(&self, error: f64) -> bool {
+        !self.filter || self.contains(error)
+    }
+
+    // a table cell saying whether the error is acceptable
+    pub fn mark(&self, ui: &mut egui::Ui, error: f64) {
+        if self.contains(error) {
+            ui.label("✔").on_hover_text(format!("{} ±{} %", tr("within"), self.percent));
+        } else {
+            ui.colored_label(ui.visuals().warn_fg_color, "✖").on_hover_text(format!("{} ±{} %", tr("outside"), self.percent));
+        }
+    }
+
+    // for tables where the filter removed everything
+    pub fn empty_note(&self, ui: &mut egui::Ui) {
+        ui.label(format!("{} ±{} %.", tr("Nothing within"), self.percent));
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Acceptable error ±"));
+            ui.add(egui::DragValue::new(&mut self.percent).speed(0.01).clamp_range(0.0..=100.0).max_decimals(4).suffix(" %"));
+        });
+        ui.checkbox(&mut self.filter, tr("Hide results outside"));
+    }
+}
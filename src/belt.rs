@@ -0,0 +1,198 @@
+use std::f32::consts::PI;
+
+use eframe::egui;
+
+use crate::feedback;
+use crate::i18n::tr;
+
+/*
+ * Toothed belt drives: two pulleys with a fixed center distance need a belt of a certain
+ * length. Belts only come in whole teeth (and usually only in some stock lengths), so the
+ * center distance has to be recalculated for the belt that is actually used.
+ */
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Profile {
+    Gt2,
+    Htd3m,
+    Htd5m,
+    Htd8m,
+}
+
+impl Profile {
+    const ALL: [Profile; 4] = [Profile::Gt2, Profile::Htd3m, Profile::Htd5m, Profile::Htd8m];
+
+    fn name(self) -> &'static str {
+        match self {
+            Profile::Gt2   => "GT2 (2 mm)",
+            Profile::Htd3m => "HTD 3M",
+            Profile::Htd5m => "HTD 5M",
+            Profile::Htd8m => "HTD 8M",
+        }
+    }
+
+    // belt pitch in mm
+    fn pitch(self) -> f32 {
+        match self {
+            Profile::Gt2   => 2.0,
+            Profile::Htd3m => 3.0,
+            Profile::Htd5m => 5.0,
+            Profile::Htd8m => 8.0,
+        }
+    }
+
+    // closed loop belts that are commonly available, in teeth
+    fn stock_teeth(self) -> &'static [u32] {
+        match self {
+            Profile::Gt2   => &[55, 56, 61, 79, 100, 101, 125, 140, 150, 200, 250, 300, 305, 375, 426, 500],
+            Profile::Htd3m => &[67, 75, 84, 94, 100, 113, 128, 140, 151, 167, 179, 188, 200, 211, 223, 237, 250, 268, 280, 300, 334],
+            Profile::Htd5m => &[51, 60, 70, 75, 80, 85, 90, 95, 100, 107, 113, 120, 127, 134, 140, 150, 160, 170, 180, 200],
+            Profile::Htd8m => &[60, 70, 75, 80, 90, 100, 110, 120, 130, 140, 150, 160, 180, 200, 220],
+        }
+    }
+}
+
+pub struct BeltDrive {
+    profile: Profile,
+    driver_teeth: u32,
+    driven_teeth: u32,
+    // desired center distance in mm
+    center: f32,
+    snap_to_stock: bool,
+}
+
+// pitch diameter of a pulley
+fn pitch_diameter(teeth: u32, pitch: f32) -> f32 {
+    teeth as f32 * pitch / PI
+}
+
+// belt length for two pulley diameters at a center distance
+fn belt_length(d1: f32, d2: f32, center: f32) -> f32 {
+    2.0 * center + PI * (d1 + d2) / 2.0 + (d2 - d1).powi(2) / (4.0 * center)
+}
+
+// inverse of belt_length. None if the belt is too short for the pulleys.
+fn center_distance(d1: f32, d2: f32, length: f32) -> Option<f32> {
+    let b = 2.0 * length - PI * (d1 + d2);
+    let disc = b * b - 8.0 * (d2 - d1).powi(2);
+    if b <= 0.0 || disc < 0.0 {
+        return None;
+    }
+    let c = (b + disc.sqrt()) / 8.0;
+    // the pulleys may not touch
+    if c <= (d1 + d2) / 2.0 {
+        return None;
+    }
+    Some(c)
+}
+
+impl BeltDrive {
+    pub fn new() -> BeltDrive {
+        BeltDrive {
+            profile: Profile::Gt2,
+            driver_teeth: 20,
+            driven_teeth: 60,
+            center: 100.0,
+            snap_to_stock: false,
+        }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.driven_teeth as f32 / self.driver_teeth as f32
+    }
+
+    // the belt that fits the desired center distance best
+    fn belt_teeth(&self, exact: f32) -> u32 {
+        if self.snap_to_stock {
+            let stock = self.profile.stock_teeth();
+            *stock.iter()
+                .min_by(|a, b| (**a as f32 - exact).abs().total_cmp(&(**b as f32 - exact).abs()))
+                .unwrap()
+        } else {
+            exact.round() as u32
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("belt_inputs").show(ui, |ui| {
+            ui.label(tr("Profile"));
+            egui::ComboBox::from_id_source("belt_profile")
+                .selected_text(self.profile.name())
+                .show_ui(ui, |ui| {
+                    for p in Profile::ALL {
+                        ui.selectable_value(&mut self.profile, p, p.name());
+                    }
+                });
+            ui.end_row();
+
+            ui.label(tr("Driver pulley"));
+            ui.add(egui::DragValue::new(&mut self.driver_teeth).clamp_range(6..=300).suffix(" T"));
+            ui.end_row();
+
+            ui.label(tr("Driven pulley"));
+            ui.add(egui::DragValue::new(&mut self.driven_teeth).clamp_range(6..=300).suffix(" T"));
+            ui.end_row();
+
+            ui.label(tr("Center distance"));
+            ui.add(egui::DragValue::new(&mut self.center).speed(0.5).clamp_range(1.0..=5000.0).suffix(" mm"));
+            ui.end_row();
+        });
+        ui.checkbox(&mut self.snap_to_stock, tr("Only stock belt lengths"));
+        ui.separator();
+
+        let pitch = self.profile.pitch();
+        let d1 = pitch_diameter(self.driver_teeth, pitch);
+        let d2 = pitch_diameter(self.driven_teeth, pitch);
+        if self.center <= (d1 + d2) / 2.0 {
+            feedback::note_warning(ui.ctx());
+            ui.colored_label(ui.visuals().error_fg_color, tr("The pulleys overlap at this center distance."));
+            return;
+        }
+        let exact_teeth = belt_length(d1, d2, self.center) / pitch;
+        let teeth = self.belt_teeth(exact_teeth);
+        let length = teeth as f32 * pitch;
+
+        egui::Grid::new("belt_results").striped(true).show(ui, |ui| {
+            ui.label(tr("Ratio"));
+            ui.label(format!("{:.3}", self.ratio()));
+            ui.end_row();
+
+            ui.label(tr("Pitch diameters"));
+            ui.label(format!("{:.2} mm / {:.2} mm", d1, d2));
+            ui.end_row();
+
+            ui.label(tr("Required belt"));
+            ui.label(format!("{:.1} T ({:.1} mm)", exact_teeth, exact_teeth * pitch));
+            ui.end_row();
+
+            ui.label(tr("Chosen belt"));
+            ui.label(egui::RichText::new(format!("{} T ({:.0} mm)", teeth, length)).strong());
+            ui.end_row();
+
+            ui.label(tr("Center distance"));
+            match center_distance(d1, d2, length) {
+                Some(c) => {
+                    ui.label(egui::RichText::new(format!("{:.2} mm", c)).strong());
+                    ui.end_row();
+
+                    // wrap angle and engaged teeth on the smaller pulley
+                    let small_t = self.driver_teeth.min(self.driven_teeth);
+                    let wrap = PI - 2.0 * ((d1 - d2).abs() / (2.0 * c)).asin();
+                    let engaged = small_t as f32 * wrap / (2.0 * PI);
+                    ui.label(tr("Teeth in mesh"));
+                    if engaged < 6.0 {
+                        feedback::note_warning(ui.ctx());
+                        ui.colored_label(ui.visuals().warn_fg_color, format!("{:.1} ({})", engaged, tr("less than 6, the belt may skip")));
+                    } else {
+                        ui.label(format!("{:.1}", engaged));
+                    }
+                }
+                None => {
+                    feedback::note_warning(ui.ctx());
+                    ui.colored_label(ui.visuals().error_fg_color, tr("belt too short for these pulleys"));
+                }
+            }
+            ui.end_row();
+        });
+    }
+}
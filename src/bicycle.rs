@@ -0,0 +1,270 @@
+use eframe::egui;
+
+use crate::feedback;
+use crate::i18n::tr;
+use crate::plot::{self, Series};
+use crate::tire;
+
+/*
+ * Bicycle drivetrains: every chainring combined with every cog of the cassette.
+ * Cyclists quote gears as chainring / cog (a speed-up, not a reduction), in gear inches
+ * (equivalent wheel diameter of a penny-farthing) or as development (distance per
+ * crank revolution).
+ */
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Value {
+    Ratio,
+    GearInches,
+    Development,
+}
+
+pub struct Bicycle {
+    rings_str: String,
+    cogs_str: String,
+    rings: Vec<u32>,
+    cogs: Vec<u32>,
+    // rolling circumference of the wheel in mm
+    circumference: f32,
+    tire_str: String,
+    // gears on different chainrings closer than this are overlapping, in percent
+    overlap: f32,
+    value: Value,
+    // cadence range for the speed table, in rpm
+    cadence_min: u32,
+    cadence_max: u32,
+    cadence_step: u32,
+    // rear derailleur limits from the spec sheet
+    derailleur_capacity: u32,
+    max_cog: u32,
+}
+
+// common wheel sizes and their rolling circumference in mm
+const WHEELS: &[(&str, f32)] = &[
+    ("700x23c", 2096.0),
+    ("700x25c", 2105.0),
+    ("700x28c", 2136.0),
+    ("700x32c", 2155.0),
+    ("700x40c", 2200.0),
+    ("29x2.2", 2298.0),
+    ("27.5x2.2", 2180.0),
+    ("26x2.1", 2068.0),
+    ("26x1.5", 1985.0),
+    ("20x1.75", 1515.0),
+];
+
+// a list of tooth counts like "11-13-15" or "50, 34". None if any entry is invalid.
+pub fn parse_teeth(s: &str) -> Option<Vec<u32>> {
+    let teeth: Vec<u32> = s.split(|c: char| c == ',' || c == '-' || c == '/' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.parse().ok().filter(|&n| n > 0))
+        .collect::<Option<_>>()?;
+    if teeth.is_empty() { None } else { Some(teeth) }
+}
+
+impl Bicycle {
+    pub fn new() -> Bicycle {
+        let rings_str = String::from("50, 34");
+        let cogs_str = String::from("11-12-13-14-15-17-19-21-24-28");
+        Bicycle {
+            rings: parse_teeth(&rings_str).unwrap(),
+            cogs: parse_teeth(&cogs_str).unwrap(),
+            rings_str,
+            cogs_str,
+            circumference: 2105.0,
+            tire_str: String::from("700x25c"),
+            overlap: 3.0,
+            value: Value::GearInches,
+            cadence_min: 60,
+            cadence_max: 110,
+            cadence_step: 10,
+            derailleur_capacity: 39,
+            max_cog: 34,
+        }
+    }
+
+    // the tallest gear
+    pub fn ratio(&self) -> f32 {
+        let ring = self.rings.iter().max().copied().unwrap_or(1);
+        let cog = self.cogs.iter().min().copied().unwrap_or(1);
+        ring as f32 / cog as f32
+    }
+
+    fn format_value(&self, ratio: f32) -> String {
+        match self.value {
+            Value::Ratio => format!("{:.2}", ratio),
+            Value::GearInches => format!("{:.1}", ratio * self.circumference / std::f32::consts::PI / 25.4),
+            Value::Development => format!("{:.2}", ratio * self.circumference / 1000.0),
+        }
+    }
+
+    // whether a gear is within the overlap threshold of a gear on another chainring
+    fn overlaps(&self, ring: usize, cog: usize) -> bool {
+        let r = self.rings[ring] as f32 / self.cogs[cog] as f32;
+        self.rings.iter().enumerate()
+            .filter(|(i, _)| *i != ring)
+            .any(|(_, other)| self.cogs.iter().any(|c| {
+                let o = *other as f32 / *c as f32;
+                (o - r).abs() / r * 100.0 < self.overlap
+            }))
+    }
+
+    // text field for a list of teeth, keeps the last valid list while typing
+    fn teeth_list(ui: &mut egui::Ui, text: &mut String, teeth: &mut Vec<u32>) {
+        let valid = parse_teeth(text);
+        let mut te = egui::TextEdit::singleline(text).desired_width(240.0);
+        if valid.is_none() {
+            te = te.text_color(ui.visuals().error_fg_color);
+        }
+        if ui.add(te).changed() {
+            if let Some(t) = parse_teeth(text) {
+                *teeth = t;
+            }
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("bicycle_inputs").show(ui, |ui| {
+            ui.label(tr("Chainrings"));
+            Bicycle::teeth_list(ui, &mut self.rings_str, &mut self.rings);
+            ui.end_row();
+            ui.label(tr("Cassette"));
+            Bicycle::teeth_list(ui, &mut self.cogs_str, &mut self.cogs);
+            ui.end_row();
+            ui.label(tr("Wheel circumference"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.circumference).speed(1.0).clamp_range(500.0..=3000.0).suffix(" mm"));
+                egui::ComboBox::from_id_source("bicycle_wheel")
+                    .selected_text(tr("Wheel size"))
+                    .show_ui(ui, |ui| {
+                        for (name, c) in WHEELS {
+                            ui.selectable_value(&mut self.circumference, *c, *name);
+                        }
+                    });
+            });
+            ui.end_row();
+            ui.label(tr("Tire size"));
+            if let Some(c) = tire::tire_input(ui, &mut self.tire_str) {
+                self.circumference = c as f32;
+            }
+            ui.end_row();
+            ui.label(tr("Overlap threshold"));
+            ui.add(egui::DragValue::new(&mut self.overlap).speed(0.1).clamp_range(0.0..=20.0).suffix(" %"));
+            ui.end_row();
+        });
+
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.value, Value::Ratio, tr("Ratio"));
+            ui.radio_value(&mut self.value, Value::GearInches, tr("Gear inches"));
+            ui.radio_value(&mut self.value, Value::Development, tr("Development (m)"));
+        });
+        ui.separator();
+
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            egui::Grid::new("bicycle_matrix").striped(true).show(ui, |ui| {
+                ui.label("");
+                for c in &self.cogs {
+                    ui.label(egui::RichText::new(format!("{}", c)).strong());
+                }
+                ui.end_row();
+                for (ri, ring) in self.rings.iter().enumerate() {
+                    ui.label(egui::RichText::new(format!("{}", ring)).strong());
+                    for (ci, cog) in self.cogs.iter().enumerate() {
+                        let text = self.format_value(*ring as f32 / *cog as f32);
+                        if self.overlaps(ri, ci) {
+                            ui.colored_label(ui.visuals().warn_fg_color, text)
+                                .on_hover_text(tr("overlaps with a gear on another chainring"));
+                        } else {
+                            ui.label(text);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+
+        ui.separator();
+        egui::CollapsingHeader::new(tr("Derailleur")).id_source("bicycle_derailleur").default_open(true).show(ui, |ui| {
+            self.derailleur_check(ui);
+        });
+        egui::CollapsingHeader::new(tr("Speed at cadence")).id_source("bicycle_speeds").show(ui, |ui| {
+            self.speed_table(ui);
+        });
+        egui::CollapsingHeader::new(tr("Ratio steps")).id_source("bicycle_ratio_steps").show(ui, |ui| {
+            let series: Vec<Series> = self.rings.iter().map(|ring| {
+                let mut ratios: Vec<f64> = self.cogs.iter().map(|c| *ring as f64 / *c as f64).collect();
+                ratios.sort_by(|a, b| b.total_cmp(a));
+                Series { name: format!("{} T {}", ring, tr("ring")), ratios }
+            }).collect();
+            plot::ratio_steps(ui, "bicycle_steps", &series);
+        });
+    }
+
+    // the chain slack the derailleur has to take up is the difference between big-big and
+    // small-small: (big ring - small ring) + (big cog - small cog)
+    fn derailleur_check(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Capacity"));
+            ui.add(egui::DragValue::new(&mut self.derailleur_capacity).clamp_range(1..=100).suffix(" T"));
+            ui.label(tr("Largest cog"));
+            ui.add(egui::DragValue::new(&mut self.max_cog).clamp_range(1..=100).suffix(" T"));
+        });
+
+        let span = |t: &[u32]| t.iter().max().unwrap_or(&0) - t.iter().min().unwrap_or(&0);
+        let needed = span(&self.rings) + span(&self.cogs);
+        let biggest_cog = self.cogs.iter().max().copied().unwrap_or(0);
+
+        if needed > self.derailleur_capacity {
+            feedback::note_warning(ui.ctx());
+            ui.colored_label(ui.visuals().error_fg_color,
+                format!("{} {} T, {} {} T.", tr("Needs a capacity of"), needed, tr("the derailleur has"), self.derailleur_capacity));
+        } else {
+            ui.label(format!("{} {} T, {} {} T.", tr("Needs a capacity of"), needed, tr("to spare:"), self.derailleur_capacity - needed));
+        }
+        if biggest_cog > self.max_cog {
+            feedback::note_warning(ui.ctx());
+            ui.colored_label(ui.visuals().error_fg_color,
+                format!("{} {} T {} {} T.", tr("The cog of"), biggest_cog, tr("is larger than the derailleur's maximum of"), self.max_cog));
+        }
+    }
+
+    // km/h for each gear over the cadence range, gears sorted from light to tall
+    fn speed_table(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Cadence"));
+            ui.add(egui::DragValue::new(&mut self.cadence_min).clamp_range(10..=self.cadence_max));
+            ui.label(tr("to"));
+            ui.add(egui::DragValue::new(&mut self.cadence_max).clamp_range(self.cadence_min..=250));
+            ui.label(tr("rpm in steps of"));
+            ui.add(egui::DragValue::new(&mut self.cadence_step).clamp_range(1..=50));
+        });
+
+        let mut gears: Vec<(u32, u32)> = self.rings.iter()
+            .flat_map(|r| self.cogs.iter().map(move |c| (*r, *c)))
+            .collect();
+        gears.sort_by(|a, b| (a.0 as f32 / a.1 as f32).total_cmp(&(b.0 as f32 / b.1 as f32)));
+        let cadences: Vec<u32> = (self.cadence_min..=self.cadence_max)
+            .step_by(self.cadence_step as usize)
+            .collect();
+
+        egui::ScrollArea::both().max_height(300.0).show(ui, |ui| {
+            egui::Grid::new("bicycle_speeds").striped(true).show(ui, |ui| {
+                ui.label(egui::RichText::new(tr("Gear")).strong());
+                for c in &cadences {
+                    ui.label(egui::RichText::new(format!("{} rpm", c)).strong());
+                }
+                ui.end_row();
+                for (ring, cog) in &gears {
+                    ui.label(format!("{}x{}", ring, cog));
+                    // circumference in mm per crank revolution, times revolutions per hour
+                    let km_per_rev = *ring as f32 / *cog as f32 * self.circumference / 1_000_000.0;
+                    for c in &cadences {
+                        ui.label(format!("{:.1}", km_per_rev * *c as f32 * 60.0));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        ui.label(tr("Speeds in km/h."));
+    }
+}
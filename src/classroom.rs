@@ -0,0 +1,104 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * Classroom exercises: the teacher sets a target ratio, the student tries to reach it with
+ * the gear pair. While an exercise runs, every recomputation is counted and the closest
+ * result is kept, so the teacher gets a short summary at the end.
+ */
+
+pub struct Classroom {
+    student: String,
+    target: f32,
+    // input time the exercise was started at, None if no exercise is running
+    started: Option<f64>,
+    // duration of the last finished exercise
+    elapsed: f64,
+    recomputations: u32,
+    // closest pair so far as (left teeth, right teeth, relative error)
+    best: Option<(u32, u32, f32)>,
+}
+
+impl Classroom {
+    pub fn new() -> Classroom {
+        Classroom {
+            student: String::new(),
+            target: 2.5,
+            started: None,
+            elapsed: 0.0,
+            recomputations: 0,
+            best: None,
+        }
+    }
+
+    pub fn running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    // called after each recomputation of the pair
+    pub fn record(&mut self, left: u32, right: u32, ratio: f32) {
+        if !self.running() {
+            return;
+        }
+        self.recomputations += 1;
+        let error = (ratio - self.target).abs() / self.target;
+        if error.is_finite() && self.best.map_or(true, |b| error < b.2) {
+            self.best = Some((left, right, error));
+        }
+    }
+
+    fn summary(&self) -> String {
+        let mut s = format!("{} {}\n{} {:.3}\n{} {:.0} s\n{} {}\n",
+            tr("Student:"), if self.student.is_empty() { "-" } else { &self.student },
+            tr("Target ratio:"), self.target, tr("Time:"), self.elapsed, tr("Recomputations:"), self.recomputations);
+        match self.best {
+            Some((l, r, e)) => s += &format!("{} {}:{} = {:.3} ({:.2} % {})\n", tr("Best:"), l, r, r as f32 / l as f32, e * 100.0, tr("off")),
+            None => s += &format!("{} -\n", tr("Best:")),
+        }
+        s
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        let now = ui.input(|i| i.time);
+        egui::Grid::new("classroom").show(ui, |ui| {
+            ui.label(tr("Student"));
+            ui.add_enabled(!self.running(), egui::TextEdit::singleline(&mut self.student).desired_width(120.0));
+            ui.end_row();
+            ui.label(tr("Target ratio"));
+            ui.add_enabled(!self.running(), egui::DragValue::new(&mut self.target).speed(0.01).clamp_range(0.01..=100.0));
+            ui.end_row();
+        });
+
+        match self.started {
+            None => {
+                if ui.button(tr("Start exercise")).clicked() {
+                    self.started = Some(now);
+                    self.elapsed = 0.0;
+                    self.recomputations = 0;
+                    self.best = None;
+                }
+            }
+            Some(start) => {
+                self.elapsed = now - start;
+                // keep the clock ticking
+                ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+                if ui.button(tr("Finish exercise")).clicked() {
+                    self.started = None;
+                }
+            }
+        }
+
+        ui.separator();
+        ui.label(format!("{} {:.0} s", tr("Time:"), self.elapsed));
+        ui.label(format!("{} {}", tr("Recomputations:"), self.recomputations));
+        match self.best {
+            Some((l, r, e)) => ui.label(format!("{} {}:{} ({:.2} % {})", tr("Closest:"), l, r, e * 100.0, tr("off"))),
+            None => ui.label(format!("{} -", tr("Closest:"))),
+        };
+        if !self.running() && ui.button(tr("Copy summary")).clicked() {
+            let summary = self.summary();
+            ui.output_mut(|o| o.copied_text = summary);
+        }
+    }
+}
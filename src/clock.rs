@@ -0,0 +1,172 @@
+use eframe::egui;
+
+use crate::band::Band;
+use crate::i18n::tr;
+use crate::search::{self, Candidate, CenterLimit, Objective, SearchParams};
+use crate::worker::{self, Job};
+
+/*
+ * Clock going trains. The escape wheel moves one tooth per full oscillation of the pendulum
+ * or balance, so it turns once every escape teeth * period seconds. The train between the
+ * center arbor (one turn per hour) and the escape wheel has to speed that up.
+ *
+ * In a going train the wheels drive and the pinions are driven, so every stage speeds up.
+ * Clockmakers keep pinions between 6 and 16 leaves, the wheels are larger.
+ */
+
+const GRAVITY: f64 = 9.80665;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Oscillator {
+    PendulumLength,
+    PendulumPeriod,
+    Balance,
+}
+
+pub struct Clock {
+    oscillator: Oscillator,
+    // pendulum length in m
+    length: f64,
+    // full period (tick and tock) in s
+    period: f64,
+    // balance beats per hour, two beats per oscillation
+    bph: f64,
+    escape_teeth: u32,
+    // seconds per turn of the arbor the train starts from
+    arbor_seconds: f64,
+    params: SearchParams,
+    results: Vec<Vec<Candidate>>,
+    job: Option<Job<Vec<Vec<Candidate>>>>,
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        Clock {
+            oscillator: Oscillator::PendulumPeriod,
+            length: 0.994,
+            period: 2.0,
+            bph: 18000.0,
+            escape_teeth: 30,
+            arbor_seconds: 3600.0,
+            params: SearchParams {
+                target: 1.0,
+                min_teeth: 40,
+                max_teeth: 120,
+                driven_teeth: Some((6, 16)),
+                max_stages: 3,
+                top_n: 5,
+                objective: Objective::Error,
+                tolerance: 0.0,
+                center_limit: CenterLimit::Free,
+                center_distance: 0.0,
+                module: 1.0,
+            },
+            results: Vec::new(),
+            job: None,
+        }
+    }
+
+    // full oscillation period in s
+    fn oscillation(&self) -> f64 {
+        match self.oscillator {
+            Oscillator::PendulumLength => 2.0 * std::f64::consts::PI * (self.length / GRAVITY).sqrt(),
+            Oscillator::PendulumPeriod => self.period,
+            Oscillator::Balance => 2.0 * 3600.0 / self.bph,
+        }
+    }
+
+    // pinion / wheel over the whole train, below 1 since the train speeds up
+    fn target(&self) -> f64 {
+        self.escape_teeth as f64 * self.oscillation() / self.arbor_seconds
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.target() as f32
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, band: &Band) {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.oscillator, Oscillator::PendulumLength, tr("Pendulum length"));
+            ui.radio_value(&mut self.oscillator, Oscillator::PendulumPeriod, tr("Pendulum period"));
+            ui.radio_value(&mut self.oscillator, Oscillator::Balance, tr("Balance"));
+        });
+        let p = &mut self.params;
+        egui::Grid::new("clock_inputs").show(ui, |ui| {
+            match self.oscillator {
+                Oscillator::PendulumLength => {
+                    ui.label(tr("Length"));
+                    ui.add(egui::DragValue::new(&mut self.length).speed(0.001).clamp_range(0.01..=20.0).suffix(" m"));
+                }
+                Oscillator::PendulumPeriod => {
+                    ui.label(tr("Period (tick and tock)"));
+                    ui.add(egui::DragValue::new(&mut self.period).speed(0.01).clamp_range(0.01..=60.0).suffix(" s"));
+                }
+                Oscillator::Balance => {
+                    ui.label(tr("Beat rate"));
+                    ui.add(egui::DragValue::new(&mut self.bph).speed(100.0).clamp_range(3600.0..=72000.0).suffix(" bph"));
+                }
+            }
+            ui.end_row();
+            ui.label(tr("Escape wheel"));
+            ui.add(egui::DragValue::new(&mut self.escape_teeth).clamp_range(6..=200).suffix(format!(" {}", tr("teeth"))));
+            ui.end_row();
+            ui.label(tr("Start arbor turns every"));
+            ui.add(egui::DragValue::new(&mut self.arbor_seconds).speed(10.0).clamp_range(1.0..=86400.0).suffix(" s"));
+            ui.end_row();
+            ui.label(tr("Wheels"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut p.min_teeth).clamp_range(10..=p.max_teeth));
+                ui.label(tr("to"));
+                ui.add(egui::DragValue::new(&mut p.max_teeth).clamp_range(p.min_teeth..=200));
+            });
+            ui.end_row();
+            if let Some((min, max)) = &mut p.driven_teeth {
+                ui.label(tr("Pinions"));
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(min).clamp_range(4..=*max));
+                    ui.label(tr("to"));
+                    ui.add(egui::DragValue::new(max).clamp_range(*min..=30));
+                });
+                ui.end_row();
+            }
+            ui.label(tr("Stages"));
+            ui.add(egui::Slider::new(&mut p.max_stages, 1..=search::MAX_STAGES).text(tr("at most")));
+            ui.end_row();
+        });
+
+        let target = self.target();
+        ui.label(format!("{} {:.4} s, {} {:.2} s, {} {:.4}",
+            tr("Period"), self.oscillation(), tr("escape wheel turns every"), self.escape_teeth as f64 * self.oscillation(),
+            tr("train step-up"), 1.0 / target));
+        if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Search trains"))).clicked() {
+            self.params.target = target;
+            let params = self.params.clone();
+            self.job = Some(Job::spawn(ui.ctx(), move |p| search::search(&params, p)));
+        }
+        if let Some(r) = worker::job_ui(ui, &mut self.job) {
+            self.results = r;
+        }
+
+        for (i, group) in self.results.iter().enumerate() {
+            if group.is_empty() {
+                continue;
+            }
+            ui.separator();
+            ui.label(egui::RichText::new(format!("{} {}, {}", tr("Stages:"), i + 1, tr("wheel/pinion"))).strong());
+            if !group.iter().any(|c| band.shows(c.error)) {
+                band.empty_note(ui);
+            }
+            egui::Grid::new(("clock_results", i)).striped(true).show(ui, |ui| {
+                for c in group.iter().filter(|c| band.shows(c.error)) {
+                    let stages: Vec<String> = c.train.stages.iter().map(|s| format!("{}/{}", s.driver, s.driven)).collect();
+                    ui.label(stages.join(" × "));
+                    ui.label(format!("{:.4}", 1.0 / c.ratio));
+                    // seconds of drift per day
+                    ui.label(format!("{:+.2} s/{}", (c.ratio / target - 1.0) * 86400.0, tr("day")));
+                    band.mark(ui, c.error);
+                    ui.end_row();
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+use crate::ratio_model::RatioModel;
+
+/*
+ * Comparing two gear pairs: the current pair is pinned, a copy of it can be edited next to
+ * it. The delta column shows what the change does to the ratio and to the output speed at
+ * a reference input speed, and the error row how far each is off the given ratio of the
+ * active pair.
+ */
+
+pub struct Compare {
+    // (input teeth, output teeth)
+    pinned: Option<(u32, u32)>,
+    variant: (u32, u32),
+    // input speed in rpm
+    reference_rpm: f32,
+}
+
+fn ratio((input, output): (u32, u32)) -> f32 {
+    output as f32 / input as f32
+}
+
+impl Compare {
+    pub fn new() -> Compare {
+        Compare {
+            pinned: None,
+            variant: (10, 15),
+            reference_rpm: 1000.0,
+        }
+    }
+
+    // current is the pair of the active calculation, model its ratio
+    pub fn ui(&mut self, ui: &mut egui::Ui, current: (u32, u32), model: &RatioModel) {
+        ui.horizontal(|ui| {
+            if ui.button(tr("Pin current pair")).clicked() {
+                self.pinned = Some(current);
+                self.variant = current;
+            }
+            ui.label(tr("Reference speed"));
+            ui.add(egui::DragValue::new(&mut self.reference_rpm).speed(10.0).clamp_range(0.1..=100000.0).suffix(" rpm"));
+        });
+        let pinned = match self.pinned {
+            Some(p) => p,
+            None => {
+                ui.label(tr("Pin a pair to compare it with a variant."));
+                return;
+            }
+        };
+        let (rp, rv) = (ratio(pinned), ratio(self.variant));
+        let (sp, sv) = (self.reference_rpm / rp, self.reference_rpm / rv);
+        egui::Grid::new("compare").striped(true).show(ui, |ui| {
+            for h in ["", "Pinned", "Variant", "Delta"] {
+                ui.label(egui::RichText::new(tr(h)).strong());
+            }
+            ui.end_row();
+            ui.label(tr("Input gear"));
+            ui.label(format!("{}", pinned.0));
+            ui.add(egui::DragValue::new(&mut self.variant.0).clamp_range(1..=100000));
+            ui.label(format!("{:+}", self.variant.0 as i64 - pinned.0 as i64));
+            ui.end_row();
+            ui.label(tr("Output gear"));
+            ui.label(format!("{}", pinned.1));
+            ui.add(egui::DragValue::new(&mut self.variant.1).clamp_range(1..=100000));
+            ui.label(format!("{:+}", self.variant.1 as i64 - pinned.1 as i64));
+            ui.end_row();
+            ui.label(tr("Ratio"));
+            ui.label(format!("{:.4}", rp));
+            ui.label(format!("{:.4}", rv));
+            ui.label(format!("{:+.4} ({:+.2} %)", rv - rp, (rv / rp - 1.0) * 100.0));
+            ui.end_row();
+            ui.label(tr("Error"));
+            for r in [rp, rv] {
+                let error = model.error_of(r as f64);
+                if model.diverges(r as f64) {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("{:+.3} %", error));
+                } else {
+                    ui.label(format!("{:+.3} %", error));
+                }
+            }
+            ui.label("");
+            ui.end_row();
+            ui.label(tr("Output speed"));
+            ui.label(format!("{:.1} rpm", sp));
+            ui.label(format!("{:.1} rpm", sv));
+            ui.label(format!("{:+.1} rpm ({:+.2} %)", sv - sp, (sv / sp - 1.0) * 100.0));
+            ui.end_row();
+        });
+    }
+}
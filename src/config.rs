@@ -0,0 +1,146 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+/*
+ * The native app reads defaults from a TOML file, $XDG_CONFIG_HOME/gear-ratio/config.toml
+ * (~/.config/gear-ratio/config.toml without XDG_CONFIG_HOME) or the one given with
+ * --config. The keys are the ones the settings are saved with:
+ *     units = "imperial"
+ *     decimals = 4
+ *     min_teeth = 8
+ *     max_teeth = 200
+ *     theme = "dark"
+ * A key that isn't known or a value of the wrong type makes the whole file be ignored, with
+ * a warning in the log.
+ *
+ * The file replaces the built in defaults. What is changed in the app is saved with the app
+ * state and goes over the file, everything else follows the file, also after it was edited.
+ */
+
+const FILE: &str = "gear-ratio/config.toml";
+
+#[derive(Deserialize, Default, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    teeth_step: Option<u32>,
+    ratio_step: Option<f64>,
+    drag_detent: Option<f32>,
+    scroll_detent: Option<f32>,
+    invert_scroll: Option<bool>,
+    min_teeth: Option<u32>,
+    max_teeth: Option<u32>,
+    preview: Option<usize>,
+    decimals: Option<usize>,
+    decimal_comma: Option<bool>,
+    units: Option<String>,
+    language: Option<String>,
+    theme: Option<String>,
+    default_teeth: Option<u32>,
+    default_ratio: Option<f64>,
+    sound: Option<bool>,
+    haptics: Option<bool>,
+}
+
+impl Config {
+    pub fn parse(toml: &str) -> Result<Config, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    // the keys that are set, in the format of Settings::load_text, which also checks the
+    // ranges and the names of units, language and theme
+    pub fn settings_text(&self) -> String {
+        fn line<T: Display>(lines: &mut Vec<String>, key: &str, value: &Option<T>) {
+            if let Some(v) = value {
+                lines.push(format!("{}={}", key, v));
+            }
+        }
+        let mut lines = Vec::new();
+        line(&mut lines, "teeth_step", &self.teeth_step);
+        line(&mut lines, "ratio_step", &self.ratio_step);
+        line(&mut lines, "drag_detent", &self.drag_detent);
+        line(&mut lines, "scroll_detent", &self.scroll_detent);
+        line(&mut lines, "invert_scroll", &self.invert_scroll);
+        line(&mut lines, "min_teeth", &self.min_teeth);
+        line(&mut lines, "max_teeth", &self.max_teeth);
+        line(&mut lines, "preview", &self.preview);
+        line(&mut lines, "decimals", &self.decimals);
+        line(&mut lines, "decimal_comma", &self.decimal_comma);
+        line(&mut lines, "units", &self.units);
+        line(&mut lines, "language", &self.language);
+        line(&mut lines, "theme", &self.theme);
+        line(&mut lines, "default_teeth", &self.default_teeth);
+        line(&mut lines, "default_ratio", &self.default_ratio);
+        line(&mut lines, "sound", &self.sound);
+        line(&mut lines, "haptics", &self.haptics);
+        lines.join("\n")
+    }
+}
+
+// the file and whether it was asked for on the command line
+fn path() -> Option<(PathBuf, bool)> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args.next().map(|p| (PathBuf::from(p), true));
+        }
+        if let Some(p) = arg.strip_prefix("--config=") {
+            return Some((PathBuf::from(p), true));
+        }
+    }
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some((dir.join(FILE), false))
+}
+
+// the config file in the format of Settings::load_text, if there is a good one
+pub fn settings_text() -> Option<String> {
+    let (path, asked) = path()?;
+    let toml = match std::fs::read_to_string(&path) {
+        Ok(toml) => toml,
+        Err(e) => {
+            if asked {
+                log::warn!("can't read config {}: {}", path.display(), e);
+            }
+            return None;
+        }
+    };
+    match Config::parse(&toml) {
+        Ok(config) => Some(config.settings_text()),
+        Err(e) => {
+            log::warn!("ignoring config {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{Settings, Units};
+
+    #[test]
+    fn typed_keys() {
+        let config = Config::parse("units = \"imperial\"  # inch\ndecimals = 4\ndecimal_comma = true\n").unwrap();
+        assert_eq!(config.settings_text(), "decimals=4\ndecimal_comma=true\nunits=imperial");
+        let mut settings = Settings::new();
+        settings.load_text(&config.settings_text());
+        assert_eq!((settings.units, settings.decimals, settings.decimal_comma), (Units::Imperial, 4, true));
+    }
+
+    #[test]
+    fn empty_file_sets_nothing() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+        assert_eq!(Config::default().settings_text(), "");
+    }
+
+    #[test]
+    fn bad_files_are_refused() {
+        assert!(Config::parse("decimals = \"four\"").is_err());
+        assert!(Config::parse("decimal = 4").is_err());
+        assert!(Config::parse("decimals = ").is_err());
+    }
+}
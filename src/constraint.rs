@@ -0,0 +1,106 @@
+/*
+ * A small constraint graph over the named quantities of a drive. Each relation is an
+ * equation between a few quantities that can be solved for any one of them:
+ * - Teeth:  ratio = right / left
+ * - Mesh:   center distance = module * (left + right) / 2
+ * - Speed:  output speed = input speed / ratio
+ *
+ * When a quantity is edited, every relation containing it gets solved for one of its
+ * quantities that is neither locked nor already determined, and the solved quantity is
+ * edited in turn. This is what the locked column of the gear pair used to do with a
+ * bitmask, just for more than three quantities.
+ *
+ * Every relation is solved once per edit, so when two relations share two unknowns (like
+ * the center distance with locked module and ratio) the result is only approximate.
+ */
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Quantity {
+    // size of the left (input) wheel, teeth or diameter
+    Left,
+    Ratio,
+    // size of the right (output) wheel
+    Right,
+    Module,
+    CenterDistance,
+    InputSpeed,
+    OutputSpeed,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Relation {
+    Teeth,
+    Mesh,
+    Speed,
+}
+
+impl Relation {
+    // if there is a choice, the quantity listed last gets solved
+    pub fn quantities(self) -> &'static [Quantity] {
+        match self {
+            Relation::Teeth => &[Quantity::Left, Quantity::Ratio, Quantity::Right],
+            Relation::Mesh => &[Quantity::Module, Quantity::Left, Quantity::Right, Quantity::CenterDistance],
+            Relation::Speed => &[Quantity::InputSpeed, Quantity::Ratio, Quantity::OutputSpeed],
+        }
+    }
+}
+
+pub struct ConstraintGraph {
+    relations: &'static [Relation],
+    locked: Vec<Quantity>,
+}
+
+impl ConstraintGraph {
+    pub fn new(relations: &'static [Relation], locked: &[Quantity]) -> ConstraintGraph {
+        ConstraintGraph { relations, locked: locked.to_vec() }
+    }
+
+    pub fn is_locked(&self, q: Quantity) -> bool {
+        self.locked.contains(&q)
+    }
+
+    pub fn set_locked(&mut self, q: Quantity, locked: bool) {
+        self.locked.retain(|&l| l != q);
+        if locked {
+            self.locked.push(q);
+        }
+    }
+
+    // locks q and unlocks the rest of the group, like the radio buttons of the columns
+    pub fn lock_one_of(&mut self, q: Quantity, group: &[Quantity]) {
+        self.locked.retain(|l| !group.contains(l));
+        self.locked.push(q);
+    }
+
+    // the quantities to recompute after some were edited, in order, together with the
+    // relation to compute them from. Explicit edits beat locks: if a relation has nothing
+    // free left, a locked quantity that wasn't edited gets recomputed anyway.
+    pub fn plan(&self, edited: &[Quantity]) -> Vec<(Quantity, Relation)> {
+        let mut changed: Vec<Quantity> = edited.to_vec();
+        let mut done: Vec<Relation> = Vec::new();
+        let mut plan = Vec::new();
+        loop {
+            let next = self.relations.iter().copied().find(|r| {
+                !done.contains(r) && r.quantities().iter().any(|q| changed.contains(q))
+            });
+            let r = match next {
+                Some(r) => r,
+                None => break,
+            };
+            done.push(r);
+            let qs = r.quantities();
+            let free = qs.iter().rev().find(|q| !changed.contains(q) && !self.is_locked(**q))
+                .or_else(|| qs.iter().rev().find(|q| !changed.contains(q)));
+            // with no free quantity the relation is already determined
+            if let Some(&q) = free {
+                plan.push((q, r));
+                changed.push(q);
+                // rounding whole teeth moves the actual ratio as well
+                if r == Relation::Teeth && q != Quantity::Ratio && !changed.contains(&Quantity::Ratio) {
+                    changed.push(Quantity::Ratio);
+                }
+            }
+        }
+        plan
+    }
+}
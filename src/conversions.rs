@@ -0,0 +1,66 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * Different communities quote ratios differently: machinists say 3:1, RC people 1:3,
+ * cyclists count how many cassette steps two gears are apart.
+ * This panel shows the current ratio in all of these notations at once.
+ */
+
+pub struct Conversions {
+    // relative size of one step of the progression, in percent
+    step_percent: f32,
+}
+
+impl Conversions {
+    pub fn new() -> Conversions {
+        Conversions {
+            step_percent: 11.0,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, ratio: f32) {
+        if !ratio.is_finite() || ratio <= 0.0 {
+            ui.label(tr("No valid ratio."));
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(tr("Progression step:"));
+            ui.add(egui::DragValue::new(&mut self.step_percent)
+                .speed(0.1)
+                .clamp_range(0.1..=100.0)
+                .suffix(" %"));
+        });
+
+        // a ratio of r spans ln(r) / ln(1 + step) steps of the progression
+        let steps = ratio.ln() / (1.0 + self.step_percent / 100.0).ln();
+
+        egui::Grid::new("conversions").striped(true).show(ui, |ui| {
+            ui.label(tr("Reduction"));
+            ui.label(format!("{:.3}:1", ratio));
+            ui.end_row();
+
+            ui.label(tr("Inverse"));
+            ui.label(format!("1:{:.3}", 1.0 / ratio));
+            ui.end_row();
+
+            ui.label(tr("Change vs. 1:1"));
+            ui.label(format!("{:+.1} %", (ratio - 1.0) * 100.0));
+            ui.end_row();
+
+            ui.label(tr("Output speed"));
+            ui.label(format!("{:.1} % {}", 100.0 / ratio, tr("of the input")));
+            ui.end_row();
+
+            ui.label(tr("Decibels"));
+            ui.label(format!("{:.2} dB", 20.0 * ratio.log10()));
+            ui.end_row();
+
+            ui.label(format!("{} {} %", tr("Steps of"), self.step_percent));
+            ui.label(format!("{:.2}", steps));
+            ui.end_row();
+        });
+    }
+}
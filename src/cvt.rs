@@ -0,0 +1,101 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * Continuously variable drives, like variator belts on scooters, cone drives and the toroidal
+ * CVTs of cars. There are no teeth to choose, only the range between the lowest and highest
+ * ratio the drive can reach. For a given input the output sweeps
+ *     speed  from input speed / max ratio  to  input speed / min ratio
+ *     torque from input torque · min ratio  to  input torque · max ratio
+ * times the efficiency, which is lower than for gears since they transmit by friction.
+ * The spread, max ratio / min ratio, is how much of the range one input speed covers.
+ */
+
+pub struct Cvt {
+    min_ratio: f64,
+    max_ratio: f64,
+    // where the drive is set now, between min and max
+    ratio: f64,
+    // rpm
+    input_speed: f64,
+    // Nm
+    input_torque: f64,
+    // percent
+    efficiency: f64,
+}
+
+impl Cvt {
+    pub fn new() -> Cvt {
+        Cvt {
+            min_ratio: 0.8,
+            max_ratio: 2.6,
+            ratio: 1.5,
+            input_speed: 6000.0,
+            input_torque: 10.0,
+            efficiency: 85.0,
+        }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.ratio as f32
+    }
+
+    fn output_speed(&self, ratio: f64) -> f64 {
+        self.input_speed / ratio
+    }
+
+    fn output_torque(&self, ratio: f64) -> f64 {
+        self.input_torque * ratio * self.efficiency / 100.0
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("cvt_inputs").show(ui, |ui| {
+            ui.label(tr("Lowest ratio"));
+            ui.add(egui::DragValue::new(&mut self.min_ratio).speed(0.01).clamp_range(0.01..=self.max_ratio).max_decimals(3));
+            ui.end_row();
+            ui.label(tr("Highest ratio"));
+            ui.add(egui::DragValue::new(&mut self.max_ratio).speed(0.01).clamp_range(self.min_ratio..=1000.0).max_decimals(3));
+            ui.end_row();
+            ui.label(tr("Input speed"));
+            ui.add(egui::DragValue::new(&mut self.input_speed).speed(10.0).clamp_range(0.0..=100000.0).suffix(" rpm"));
+            ui.end_row();
+            ui.label(tr("Input torque"));
+            ui.add(egui::DragValue::new(&mut self.input_torque).speed(0.1).clamp_range(0.0..=100000.0).suffix(" Nm"));
+            ui.end_row();
+            ui.label(tr("Efficiency"));
+            ui.add(egui::DragValue::new(&mut self.efficiency).speed(0.5).clamp_range(1.0..=100.0).suffix(" %"));
+            ui.end_row();
+        });
+        ui.label(format!("{} {:.2}", tr("Spread:"), self.max_ratio / self.min_ratio));
+
+        ui.separator();
+        egui::Grid::new("cvt_output").striped(true).show(ui, |ui| {
+            for h in ["", "Ratio", "Output speed", "Output torque"] {
+                ui.label(egui::RichText::new(tr(h)).strong());
+            }
+            ui.end_row();
+            for (name, ratio) in [("Lowest", self.min_ratio), ("Highest", self.max_ratio)] {
+                ui.label(tr(name));
+                ui.label(format!("{:.3}", ratio));
+                ui.label(format!("{:.1} rpm", self.output_speed(ratio)));
+                ui.label(format!("{:.2} Nm", self.output_torque(ratio)));
+                ui.end_row();
+            }
+        });
+
+        ui.separator();
+        self.ratio = self.ratio.clamp(self.min_ratio, self.max_ratio);
+        ui.horizontal(|ui| {
+            ui.label(tr("Set to"));
+            ui.add(egui::Slider::new(&mut self.ratio, self.min_ratio..=self.max_ratio).max_decimals(3));
+        });
+        ui.label(format!(
+            "{:.1} rpm {} {:.2} Nm {}",
+            self.output_speed(self.ratio),
+            tr("and"),
+            self.output_torque(self.ratio),
+            tr("at the output")
+        ));
+    }
+}
@@ -0,0 +1,140 @@
+use eframe::egui;
+
+use crate::band::Band;
+use crate::i18n::tr;
+use crate::train::Direction;
+
+/*
+ * Cycloidal reducers: an eccentric on the input shaft wobbles a disc with n lobes inside a
+ * ring of N pins. Every input turn the disc rolls one pin pitch back, so it turns
+ * (N − n) / n times slower and the other way. Usually N − n = 1, then the ratio is simply
+ * the number of lobes.
+ *     pins fixed, output from the disc:  i = n / (N − n), reversed
+ *     disc held, output from the pins:   i = N / (N − n), same direction
+ * The disc's wobble is taken off with output pins in holes of the disc.
+ */
+
+// pins more than lobes searched when back-solving
+const MAX_DIFFERENCE: u32 = 3;
+
+#[derive(PartialEq, Clone, Copy)]
+enum Output {
+    Disc,
+    Pins,
+}
+
+pub struct Cycloidal {
+    lobes: u32,
+    pins: u32,
+    output: Output,
+    target: f64,
+}
+
+impl Cycloidal {
+    pub fn new() -> Cycloidal {
+        Cycloidal { lobes: 11, pins: 12, output: Output::Disc, target: 20.0 }
+    }
+
+    fn reduction(&self, lobes: u32, pins: u32) -> Option<f64> {
+        let difference = pins.checked_sub(lobes).filter(|d| *d > 0)? as f64;
+        Some(match self.output {
+            Output::Disc => lobes as f64 / difference,
+            Output::Pins => pins as f64 / difference,
+        })
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.reduction(self.lobes, self.pins).unwrap_or(1.0) as f32
+    }
+
+    fn direction(&self) -> Direction {
+        match self.output {
+            Output::Disc => Direction::Reversed,
+            Output::Pins => Direction::Same,
+        }
+    }
+
+    // (lobes, pins, ratio) closest to the target for every pin difference
+    fn candidates(&self) -> Vec<(u32, u32, f64)> {
+        (1..=MAX_DIFFERENCE)
+            .filter_map(|d| {
+                // the ratio is linear in the lobes, so rounding is the best there is
+                let lobes = match self.output {
+                    Output::Disc => (self.target * d as f64).round(),
+                    Output::Pins => (self.target * d as f64).round() - d as f64,
+                };
+                if lobes < 2.0 {
+                    return None;
+                }
+                let lobes = lobes as u32;
+                self.reduction(lobes, lobes + d).map(|r| (lobes, lobes + d, r))
+            })
+            .collect()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, band: &Band) {
+        egui::Grid::new("cycloidal_inputs").show(ui, |ui| {
+            ui.label(tr("Disc lobes"));
+            ui.add(egui::DragValue::new(&mut self.lobes).clamp_range(2..=500));
+            ui.end_row();
+            ui.label(tr("Ring pins"));
+            ui.add(egui::DragValue::new(&mut self.pins).clamp_range(3..=501));
+            ui.end_row();
+            ui.label(tr("Output"));
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.output, Output::Disc, tr("Disc, pins fixed"));
+                ui.radio_value(&mut self.output, Output::Pins, tr("Pins, disc held"));
+            });
+            ui.end_row();
+        });
+
+        match self.reduction(self.lobes, self.pins) {
+            Some(r) => {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(format!("{} {:.3} : 1", tr("Reduction:"), r)).strong());
+                    self.direction().show(ui);
+                });
+                if self.pins - self.lobes > 1 {
+                    ui.weak(tr("More than one pin difference needs a disc with that many teeth per lobe gap."));
+                }
+            }
+            None => {
+                ui.colored_label(ui.visuals().error_fg_color, tr("The ring needs more pins than the disc has lobes."));
+            }
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(tr("Target reduction"));
+            ui.add(egui::DragValue::new(&mut self.target).speed(0.1).clamp_range(1.0..=1000.0).max_decimals(4));
+        });
+        let mut apply = None;
+        egui::Grid::new("cycloidal_candidates").striped(true).show(ui, |ui| {
+            for h in ["Lobes", "Pins", "Reduction", "Error", ""] {
+                ui.label(egui::RichText::new(tr(h)).strong());
+            }
+            ui.end_row();
+            for (lobes, pins, r) in self.candidates() {
+                let error = r / self.target - 1.0;
+                if !band.shows(error) {
+                    continue;
+                }
+                ui.label(lobes.to_string());
+                ui.label(pins.to_string());
+                ui.label(format!("{:.3}", r));
+                ui.horizontal(|ui| {
+                    ui.label(format!("{:+.3} %", error * 100.0));
+                    band.mark(ui, error);
+                });
+                if ui.button(tr("Use")).clicked() {
+                    apply = Some((lobes, pins));
+                }
+                ui.end_row();
+            }
+        });
+        if let Some((lobes, pins)) = apply {
+            self.lobes = lobes;
+            self.pins = pins;
+        }
+    }
+}
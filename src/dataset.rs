@@ -0,0 +1,255 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * A dataset is a small user editable table (gear inventory, materials, motors, ...).
+ * Every dataset has a fixed schema describing its columns, which is used to validate
+ * the cells while they are being typed, so nobody has to hand-edit CSV or JSON files.
+ * The first column is the key of a row and has to be unique within the dataset.
+ * Cells are kept as strings, so half-typed values survive until they are fixed.
+ */
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum FieldKind {
+    Text,
+    // whole number in the inclusive range
+    Integer { min: i64, max: i64 },
+    // real number in the inclusive range
+    Real { min: f64, max: f64 },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Field {
+    pub name: &'static str,
+    pub kind: FieldKind,
+}
+
+impl Field {
+    // returns a description of what's wrong with the cell, if anything
+    fn check(&self, cell: &str) -> Option<String> {
+        let cell = cell.trim();
+        match self.kind {
+            FieldKind::Text => {
+                if cell.is_empty() {
+                    return Some(format!("{} {}", tr(self.name), tr("may not be empty")));
+                }
+            }
+            FieldKind::Integer { min, max } => match cell.parse::<i64>() {
+                Ok(x) if (min..=max).contains(&x) => {}
+                _ => return Some(format!("{} {} {} {} {}", tr(self.name), tr("must be a whole number from"), min, tr("to"), max)),
+            },
+            FieldKind::Real { min, max } => match cell.parse::<f64>() {
+                Ok(x) if (min..=max).contains(&x) => {}
+                _ => return Some(format!("{} {} {} {} {}", tr(self.name), tr("must be a number from"), min, tr("to"), max)),
+            },
+        }
+        None
+    }
+
+    fn default_cell(&self) -> String {
+        match self.kind {
+            FieldKind::Text => String::new(),
+            FieldKind::Integer { min, .. } => min.to_string(),
+            FieldKind::Real { min, .. } => min.to_string(),
+        }
+    }
+}
+
+pub struct Dataset {
+    pub name: &'static str,
+    pub fields: &'static [Field],
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Dataset {
+    pub fn new(name: &'static str, fields: &'static [Field], rows: &[&[&str]]) -> Dataset {
+        Dataset {
+            name,
+            fields,
+            rows: rows.iter()
+                .map(|r| r.iter().map(|c| c.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    // the error of a single cell, including duplicate keys
+    pub fn cell_error(&self, row: usize, col: usize) -> Option<String> {
+        if let Some(e) = self.fields[col].check(&self.rows[row][col]) {
+            return Some(e);
+        }
+        if col == 0 {
+            let key = self.rows[row][0].trim();
+            let dup = self.rows.iter().enumerate()
+                .any(|(i, r)| i != row && r[0].trim().eq_ignore_ascii_case(key));
+            if dup {
+                return Some(format!("{} {} \"{}\"", tr("duplicate"), tr(self.fields[0].name), key));
+            }
+        }
+        None
+    }
+
+    pub fn error_count(&self) -> usize {
+        (0..self.rows.len())
+            .map(|r| (0..self.fields.len()).filter(|&c| self.cell_error(r, c).is_some()).count())
+            .sum()
+    }
+
+    fn field_index(&self, name: &str) -> usize {
+        self.fields.iter().position(|f| f.name == name)
+            .unwrap_or_else(|| panic!("dataset {} has no field {}", self.name, name))
+    }
+
+    pub fn row_is_valid(&self, row: usize) -> bool {
+        (0..self.fields.len()).all(|c| self.cell_error(row, c).is_none())
+    }
+
+    // indices of the rows other parts of the app may use
+    pub fn valid_rows(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.rows.len()).filter(|&r| self.row_is_valid(r))
+    }
+
+    // the row with the given key
+    pub fn find(&self, key: &str) -> Option<usize> {
+        self.rows.iter().position(|r| r[0].trim().eq_ignore_ascii_case(key.trim()))
+    }
+
+    pub fn text(&self, row: usize, field: &str) -> &str {
+        self.rows[row][self.field_index(field)].trim()
+    }
+
+    pub fn real(&self, row: usize, field: &str) -> f64 {
+        self.text(row, field).parse().unwrap_or_default()
+    }
+
+    // storage key for persisting the dataset
+    pub fn storage_key(&self) -> String {
+        format!("dataset_{}", self.name)
+    }
+
+    // one row per line, cells separated by tabs
+    pub fn to_text(&self) -> String {
+        self.rows.iter()
+            .map(|r| r.iter()
+                .map(|c| c.replace(['\t', '\n'], " "))
+                .collect::<Vec<_>>()
+                .join("\t"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // replaces the rows with the ones from to_text. rows with the wrong number of cells
+    // are dropped, the schema may have changed since they were saved.
+    pub fn load_text(&mut self, text: &str) {
+        self.rows = text.lines()
+            .map(|l| l.split('\t').map(|c| c.to_string()).collect::<Vec<_>>())
+            .filter(|r| r.len() == self.fields.len())
+            .collect();
+    }
+
+    fn new_row(&self) -> Vec<String> {
+        self.fields.iter().map(|f| f.default_cell()).collect()
+    }
+}
+
+// shows an editable table of the dataset. invalid cells are colored and explain
+// themselves when hovered.
+pub fn dataset_editor(ui: &mut egui::Ui, data: &mut Dataset) {
+    let mut delete = None;
+    egui::Grid::new(("dataset", data.name)).striped(true).show(ui, |ui| {
+        for f in data.fields {
+            ui.label(egui::RichText::new(tr(f.name)).strong());
+        }
+        ui.end_row();
+
+        for r in 0..data.rows.len() {
+            for c in 0..data.fields.len() {
+                let err = data.cell_error(r, c);
+                let mut te = egui::TextEdit::singleline(&mut data.rows[r][c])
+                    .desired_width(if data.fields[c].kind == FieldKind::Text { 120.0 } else { 60.0 });
+                if err.is_some() {
+                    te = te.text_color(ui.visuals().error_fg_color);
+                }
+                let resp = ui.add(te);
+                if let Some(e) = err {
+                    resp.on_hover_text(e);
+                }
+            }
+            if ui.small_button("🗑").on_hover_text(tr("Delete row")).clicked() {
+                delete = Some(r);
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(r) = delete {
+        data.rows.remove(r);
+    }
+
+    ui.horizontal(|ui| {
+        if ui.button(tr("Add row")).clicked() {
+            let row = data.new_row();
+            data.rows.push(row);
+        }
+        let n = data.error_count();
+        if n > 0 {
+            ui.colored_label(ui.visuals().error_fg_color, format!("{} {}", tr("Invalid cells, those rows are ignored:"), n));
+        }
+    });
+}
+
+// the change gears on hand, used by the searches
+pub const INVENTORY_FIELDS: &[Field] = &[
+    Field { name: "Name", kind: FieldKind::Text },
+    Field { name: "Teeth", kind: FieldKind::Integer { min: 4, max: 1000 } },
+    Field { name: "Count", kind: FieldKind::Integer { min: 1, max: 100 } },
+];
+
+pub fn default_inventory() -> Dataset {
+    Dataset::new("Gear inventory", INVENTORY_FIELDS, &[
+        &["20T", "20", "2"],
+        &["25T", "25", "1"],
+        &["30T", "30", "1"],
+        &["35T", "35", "1"],
+        &["40T", "40", "1"],
+        &["45T", "45", "1"],
+        &["50T", "50", "1"],
+        &["55T", "55", "1"],
+        &["60T", "60", "1"],
+        &["65T", "65", "1"],
+        &["127T", "127", "1"],
+    ])
+}
+
+// the distinct tooth counts in the inventory, sorted
+pub fn inventory_teeth(data: &Dataset) -> Vec<u32> {
+    let mut teeth: Vec<u32> = data.valid_rows().map(|r| data.real(r, "Teeth") as u32).collect();
+    teeth.sort_unstable();
+    teeth.dedup();
+    teeth
+}
+
+pub const MOTOR_FIELDS: &[Field] = &[
+    Field { name: "Name", kind: FieldKind::Text },
+    Field { name: "Free speed (rpm)", kind: FieldKind::Real { min: 1.0, max: 1_000_000.0 } },
+    Field { name: "Stall torque (Nm)", kind: FieldKind::Real { min: 0.0, max: 100_000.0 } },
+    Field { name: "kV (rpm/V)", kind: FieldKind::Real { min: 0.0, max: 100_000.0 } },
+];
+
+// black-box gearboxes for the gear train, the defaults are in presets
+pub const CATALOG_FIELDS: &[Field] = &[
+    Field { name: "Name", kind: FieldKind::Text },
+    Field { name: "Series", kind: FieldKind::Text },
+    Field { name: "Ratio", kind: FieldKind::Real { min: 0.001, max: 100_000.0 } },
+    Field { name: "Efficiency (%)", kind: FieldKind::Real { min: 1.0, max: 100.0 } },
+];
+
+pub fn default_motors() -> Dataset {
+    Dataset::new("Motors", MOTOR_FIELDS, &[
+        &["CIM", "5330", "2.41", "443"],
+        &["NEO", "5676", "2.6", "473"],
+        &["Falcon 500", "6380", "4.69", "531"],
+        &["775pro", "18730", "0.71", "1561"],
+        &["NEMA 17 stepper", "600", "0.45", "0"],
+        &["540 brushed", "16000", "0.03", "2100"],
+    ])
+}
@@ -0,0 +1,105 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * An open differential splits one input into two outputs. The pinion drives the crown gear,
+ * which carries the spider gears around, and those let the two side gears turn at different
+ * speeds as long as their average is the crown gear's speed:
+ *     ω_left + ω_right = 2 · ω_crown,    ω_crown = ω_input · pinion / crown
+ * If one side is slowed down, the other speeds up by as much. The torque is always split in
+ * half, so the side that slips limits what the other one gets.
+ *
+ * On a robot or car with a track width b between the wheels, the speed difference makes it
+ * drive a curve with the radius r = b / 2 · (ω_right + ω_left) / (ω_right − ω_left)
+ * around the middle between the wheels.
+ */
+
+pub struct Differential {
+    pinion: u32,
+    crown: u32,
+    // rpm
+    input_speed: f64,
+    // Nm
+    input_torque: f64,
+    // speed of the left output, in rpm
+    left: f64,
+    // mm
+    track_width: f64,
+}
+
+impl Differential {
+    pub fn new() -> Differential {
+        Differential {
+            pinion: 10,
+            crown: 41,
+            input_speed: 3000.0,
+            input_torque: 100.0,
+            left: 731.7,
+            track_width: 1500.0,
+        }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.crown as f32 / self.pinion as f32
+    }
+
+    fn crown_speed(&self) -> f64 {
+        self.input_speed * self.pinion as f64 / self.crown as f64
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("differential_inputs").show(ui, |ui| {
+            ui.label(tr("Pinion teeth"));
+            ui.add(egui::DragValue::new(&mut self.pinion).clamp_range(3..=200));
+            ui.end_row();
+            ui.label(tr("Crown gear teeth"));
+            ui.add(egui::DragValue::new(&mut self.crown).clamp_range(5..=500));
+            ui.end_row();
+            ui.label(tr("Input speed"));
+            ui.add(egui::DragValue::new(&mut self.input_speed).speed(10.0).clamp_range(0.0..=100000.0).suffix(" rpm"));
+            ui.end_row();
+            ui.label(tr("Input torque"));
+            ui.add(egui::DragValue::new(&mut self.input_torque).speed(0.1).clamp_range(0.0..=100000.0).suffix(" Nm"));
+            ui.end_row();
+            ui.label(tr("Track width"));
+            ui.add(egui::DragValue::new(&mut self.track_width).speed(1.0).clamp_range(1.0..=10000.0).suffix(" mm"));
+            ui.end_row();
+        });
+        let crown = self.crown_speed();
+        ui.label(format!("{} {:.3}, {} {:.1} rpm.", tr("Reduction"), self.ratio(), tr("the crown gear turns at"), crown));
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(tr("Left output"));
+            ui.add(egui::Slider::new(&mut self.left, 0.0..=2.0 * crown).suffix(" rpm"));
+            if ui.button(tr("Straight ahead")).clicked() {
+                self.left = crown;
+            }
+        });
+        self.left = self.left.clamp(0.0, 2.0 * crown);
+        let right = 2.0 * crown - self.left;
+        let torque = self.input_torque * self.ratio() as f64 / 2.0;
+        egui::Grid::new("differential_outputs").striped(true).show(ui, |ui| {
+            for h in ["", "Speed", "Torque"] {
+                ui.label(egui::RichText::new(tr(h)).strong());
+            }
+            ui.end_row();
+            for (name, speed) in [("Left", self.left), ("Right", right)] {
+                ui.label(tr(name));
+                ui.label(format!("{:.1} rpm", speed));
+                ui.label(format!("{:.2} Nm", torque));
+                ui.end_row();
+            }
+        });
+
+        let difference = right - self.left;
+        if difference.abs() < 1e-6 {
+            ui.label(tr("Driving straight."));
+        } else {
+            let radius = self.track_width / 2.0 * (right + self.left) / difference;
+            let side = if radius > 0.0 { "Turning left with a radius of" } else { "Turning right with a radius of" };
+            ui.label(format!("{} {:.0} mm.", tr(side), radius.abs()));
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * Ratios that come up again and again, like the 3.73, 4.10 and 4.56 of axles, pinned as
+ * buttons under the given ratio. They are shared by all tabs and saved with the app state,
+ * one ratio per line.
+ */
+
+// ratios closer than this are the same favorite
+const SAME: f64 = 1e-6;
+
+pub struct Favorites {
+    ratios: Vec<f64>,
+}
+
+impl Favorites {
+    pub fn new() -> Favorites {
+        Favorites { ratios: Vec::new() }
+    }
+
+    fn contains(&self, ratio: f64) -> bool {
+        self.ratios.iter().any(|r| (r - ratio).abs() < SAME)
+    }
+
+    fn toggle(&mut self, ratio: f64) {
+        if self.contains(ratio) {
+            self.ratios.retain(|r| (r - ratio).abs() >= SAME);
+        } else {
+            self.ratios.push(ratio);
+            self.ratios.sort_by(f64::total_cmp);
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        self.ratios.iter().map(|r| r.to_string()).collect::<Vec<_>>().join("\n")
+    }
+
+    // lines that don't parse are dropped
+    pub fn load_text(&mut self, text: &str) {
+        self.ratios = text.lines().filter_map(|l| l.trim().parse().ok()).filter(|r: &f64| *r > 0.0).collect();
+        self.ratios.sort_by(f64::total_cmp);
+        self.ratios.dedup_by(|a, b| (*a - *b).abs() < SAME);
+    }
+
+    // a star that pins or unpins the given ratio
+    pub fn star(&mut self, ui: &mut egui::Ui, given: f64) {
+        let pinned = self.contains(given);
+        let (icon, hint) = if pinned { ("★", "Unpin this ratio") } else { ("☆", "Pin this ratio") };
+        if ui.small_button(icon).on_hover_text(tr(hint)).clicked() {
+            self.toggle(given);
+        }
+    }
+
+    // the pinned ratios as buttons. returns the one clicked.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<f64> {
+        let mut picked = None;
+        let mut unpin = None;
+        if self.ratios.is_empty() {
+            return None;
+        }
+        ui.horizontal_wrapped(|ui| {
+            for &r in &self.ratios {
+                // 4.1 and not 4.1000
+                let text = format!("{:.4}", r);
+                let text = text.trim_end_matches('0').trim_end_matches('.');
+                let resp = ui.small_button(text).on_hover_text(tr("Right click to unpin"));
+                if resp.clicked() {
+                    picked = Some(r);
+                }
+                if resp.secondary_clicked() {
+                    unpin = Some(r);
+                }
+            }
+        });
+        if let Some(r) = unpin {
+            self.toggle(r);
+        }
+        picked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_text() {
+        let mut f = Favorites::new();
+        f.load_text("4.56\n 3.73 \nbanana\n\n-2\n0\n4.1\n3.7300000001\n");
+        assert_eq!(f.ratios, [3.73, 4.1, 4.56]);
+        let mut loaded = Favorites::new();
+        loaded.load_text(&f.to_text());
+        assert_eq!(loaded.ratios, f.ratios);
+    }
+
+    #[test]
+    fn toggle() {
+        let mut f = Favorites::new();
+        f.toggle(4.1);
+        f.toggle(3.73);
+        assert_eq!(f.ratios, [3.73, 4.1]);
+        f.toggle(4.1 + SAME / 2.0);
+        assert_eq!(f.ratios, [3.73]);
+    }
+}
@@ -0,0 +1,85 @@
+use eframe::egui;
+
+use crate::audio::Audio;
+
+/*
+ * Optional clicks (and vibration, where the platform has it) when a spinner steps or a
+ * warning shows up, so values can be scrubbed while looking at the workpiece.
+ *
+ * Widgets don't know about the app, they just leave a note in the egui context data.
+ * The app picks the notes up once per frame and plays the feedback.
+ * Vibration only exists in the web build.
+ */
+
+const STEP_ID: &str = "feedback_step";
+const WARN_ID: &str = "feedback_warn";
+
+// called by widgets when their value stepped
+pub fn note_step(ctx: &egui::Context) {
+    ctx.data_mut(|d| d.insert_temp(egui::Id::new(STEP_ID), true));
+}
+
+// called every frame while a warning is shown, the feedback only plays when it appears
+pub fn note_warning(ctx: &egui::Context) {
+    ctx.data_mut(|d| d.insert_temp(egui::Id::new(WARN_ID), true));
+}
+
+fn take(ctx: &egui::Context, id: &str) -> bool {
+    ctx.data_mut(|d| {
+        let v = d.get_temp(egui::Id::new(id)).unwrap_or(false);
+        d.remove::<bool>(egui::Id::new(id));
+        v
+    })
+}
+
+pub struct Feedback {
+    pub sound: bool,
+    pub haptics: bool,
+    // whether a warning was shown last frame
+    warned: bool,
+}
+
+impl Feedback {
+    pub fn new() -> Feedback {
+        Feedback {
+            sound: false,
+            haptics: false,
+            warned: false,
+        }
+    }
+
+    pub fn haptics_available() -> bool {
+        cfg!(target_arch = "wasm32")
+    }
+
+    // plays whatever the widgets asked for during this frame
+    pub fn update(&mut self, ctx: &egui::Context, audio: &mut Audio) {
+        if take(ctx, STEP_ID) {
+            self.play(audio, 2400.0, 6);
+        }
+        let warning = take(ctx, WARN_ID);
+        if warning && !self.warned {
+            self.play(audio, 440.0, 120);
+        }
+        self.warned = warning;
+    }
+
+    fn play(&mut self, audio: &mut Audio, freq: f32, millis: u64) {
+        if self.sound && !audio.tone(&[(freq, 0.15)], millis) {
+            self.sound = false;
+        }
+        if self.haptics {
+            vibrate(millis);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn vibrate(millis: u64) {
+    if let Some(w) = web_sys::window() {
+        w.navigator().vibrate_with_duration(millis as u32);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn vibrate(_millis: u64) {}
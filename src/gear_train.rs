@@ -0,0 +1,548 @@
+use eframe::egui;
+
+use crate::app::lock_toggle;
+use crate::dataset::{self, Dataset};
+use crate::i18n::tr;
+use crate::inertia::{self, Body};
+use crate::ratio_model::RatioModel;
+use crate::schematic;
+use crate::strength::Material;
+use crate::train::{Direction, Stage};
+
+/*
+ * A general gear train: gear stages mixed with off-the-shelf gearboxes, which only have a
+ * ratio and get treated as a black box. The total ratio is the product of all of them.
+ * Gearboxes are taken to keep the direction, like inline planetary boxes do.
+ *
+ * With the input speed, every gear stage shows its mesh frequency, teeth × rpm / 60 of
+ * the shaft the driver sits on. Gearboxes hide their teeth, so they have none.
+ *
+ * The train can have constraints. Inserting a gearbox that breaks them doesn't just happen,
+ * the violations are listed first and the user decides to override, adjust or cancel.
+ *
+ * Any gear can be locked. When the total is held at the target, changing a gear or the
+ * target spreads the change the total needs evenly over the gears that aren't locked:
+ * with n of them, each one takes the n-th root of the factor, driven gears growing and
+ * drivers shrinking. The last free gear takes up what the rounding left.
+ *
+ * The inertia at the input counts the drivers and driven gears of the gear stages as discs
+ * of one module, face width and material. Idlers and gearboxes aren't in it.
+ */
+
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct Locks {
+    pub driver: bool,
+    pub driven: bool,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Element {
+    Gears(Stage, Locks),
+    // efficiency in percent
+    Gearbox { name: String, ratio: f32, efficiency: f32 },
+}
+
+impl Element {
+    pub fn ratio(&self) -> f32 {
+        match self {
+            Element::Gears(s, _) => s.ratio(),
+            Element::Gearbox { ratio, .. } => *ratio,
+        }
+    }
+
+    pub fn direction(&self) -> Direction {
+        match self {
+            Element::Gears(s, _) => s.direction(),
+            Element::Gearbox { .. } => Direction::Same,
+        }
+    }
+}
+
+// of the gearboxes, the gear stages are taken as lossless
+fn total_efficiency(elements: &[Element]) -> Option<f32> {
+    elements.iter()
+        .filter_map(|e| match e {
+            Element::Gearbox { efficiency, .. } => Some(efficiency / 100.0),
+            Element::Gears(..) => None,
+        })
+        .reduce(|a, b| a * b)
+}
+
+fn total_ratio(elements: &[Element]) -> f32 {
+    elements.iter().map(|e| e.ratio()).product()
+}
+
+// of the output shaft, relative to the input
+fn total_direction(elements: &[Element]) -> Direction {
+    elements.iter().fold(Direction::Same, |d, e| d.then(e.direction()))
+}
+
+// a tooth count with its lock. returns true if the teeth were changed.
+fn gear_ui(ui: &mut egui::Ui, teeth: &mut u32, locked: &mut bool, name: &str) -> bool {
+    ui.horizontal(|ui| {
+        let changed = ui.add_enabled(!*locked, egui::DragValue::new(teeth).clamp_range(1..=1000)).changed();
+        if lock_toggle(ui, *locked, name) {
+            *locked = !*locked;
+        }
+        changed
+    }).inner
+}
+
+#[derive(PartialEq, Clone)]
+pub struct Constraints {
+    pub use_target: bool,
+    pub target: f32,
+    // allowed deviation from the target in percent
+    pub tolerance: f32,
+    // gear stages may only use gears from the inventory
+    pub inventory_only: bool,
+    // how many stages fit into the housing
+    pub max_stages: usize,
+    // keep the total at the target by changing the unlocked gears
+    pub hold: bool,
+}
+
+impl Constraints {
+    fn new() -> Constraints {
+        Constraints {
+            use_target: false,
+            target: 50.0,
+            tolerance: 2.0,
+            inventory_only: false,
+            max_stages: 4,
+            hold: false,
+        }
+    }
+
+    fn target_model(&self) -> RatioModel {
+        RatioModel::target(self.target as f64, self.tolerance as f64)
+    }
+
+    pub fn holds(&self) -> bool {
+        self.use_target && self.hold
+    }
+
+    // everything that is wrong with a train, empty if it's fine
+    pub fn violations(&self, elements: &[Element], inventory: &[u32]) -> Vec<String> {
+        let mut v = Vec::new();
+        if elements.len() > self.max_stages {
+            v.push(format!("{} {}, {} {}.", elements.len(), tr("stages"), tr("only this many fit:"), self.max_stages));
+        }
+        let target = self.target_model();
+        let total = total_ratio(elements) as f64;
+        if self.use_target && target.diverges(total) {
+            v.push(format!("{} {:.3} {} {:+.2} % {} {:.3}.", tr("Total ratio"), total, tr("is"), target.error_of(total), tr("off the target"), self.target));
+        }
+        if self.inventory_only {
+            for (i, e) in elements.iter().enumerate() {
+                if let Element::Gears(s, _) = e {
+                    for t in [s.driver, s.driven] {
+                        if !inventory.contains(&t) {
+                            v.push(format!("{} {}: {} {}.", tr("Stage"), i + 1, tr("no gear in the inventory with teeth:"), t));
+                        }
+                    }
+                }
+            }
+        }
+        v
+    }
+
+    // unlocked gears that aren't in the inventory become the closest ones that are
+    fn snap_to_inventory(&self, elements: &[Element], inventory: &[u32]) -> Vec<Element> {
+        let closest = |t: u32| inventory.iter().copied().min_by_key(|i| i.abs_diff(t)).unwrap_or(t);
+        elements.iter()
+            .map(|e| match e {
+                Element::Gears(s, locks) if self.inventory_only => {
+                    let driver = if locks.driver { s.driver } else { closest(s.driver) };
+                    let driven = if locks.driven { s.driven } else { closest(s.driven) };
+                    Element::Gears(Stage { driver, driven, ..*s }, *locks)
+                }
+                e => e.clone(),
+            })
+            .collect()
+    }
+
+    // a train that breaks fewer constraints: gears from the inventory if only those may be
+    // used, and with a target the driven gear of one stage changed so the total gets as close
+    // to it as possible. None if nothing could be changed.
+    fn adjust(&self, elements: &[Element], inventory: &[u32]) -> Option<Vec<Element>> {
+        let snapped = self.snap_to_inventory(elements, inventory);
+        if !self.use_target {
+            return (snapped != elements).then_some(snapped);
+        }
+        let candidates: Vec<u32> = if self.inventory_only { inventory.to_vec() } else { (1..=1000).collect() };
+        let target = self.target_model();
+        let mut best: Option<(f64, Vec<Element>)> = None;
+        for (i, e) in snapped.iter().enumerate() {
+            if let Element::Gears(s, locks) = e {
+                if locks.driven {
+                    continue;
+                }
+                for &driven in &candidates {
+                    let mut adjusted = snapped.clone();
+                    adjusted[i] = Element::Gears(Stage { driven, ..*s }, *locks);
+                    let error = target.error_of(total_ratio(&adjusted) as f64).abs();
+                    if best.as_ref().map_or(true, |(b, _)| error < *b) {
+                        best = Some((error, adjusted));
+                    }
+                }
+            }
+        }
+        best.map(|(_, a)| a).filter(|a| a != elements)
+    }
+}
+
+// the auto-adjusted train for a conflict, kept until the train, the inventory or the
+// constraints change, since finding it tries every tooth count
+struct Suggestion {
+    train: Vec<Element>,
+    inventory: Vec<u32>,
+    constraints: Constraints,
+    adjusted: Option<Vec<Element>>,
+}
+
+pub struct GearTrain {
+    pub elements: Vec<Element>,
+    pub constraints: Constraints,
+    // of the input shaft, in rpm
+    input_speed: f32,
+    // a gearbox waiting for the user to resolve its conflicts
+    pending: Option<Element>,
+    suggestion: Option<Suggestion>,
+    // of the gears, for the inertia. module and face width in mm.
+    module: f32,
+    face_width: f32,
+    material: usize,
+}
+
+impl GearTrain {
+    pub fn new() -> GearTrain {
+        GearTrain {
+            elements: vec![Element::Gears(Stage::new(12, 36), Locks::default())],
+            constraints: Constraints::new(),
+            input_speed: 1000.0,
+            pending: None,
+            suggestion: None,
+            module: 1.0,
+            face_width: 10.0,
+            material: 0,
+        }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        total_ratio(&self.elements)
+    }
+
+    // brings the total to the target with the gears that aren't locked, leaving the gear
+    // the user just changed alone. gears are (element, driven) pairs.
+    fn distribute(&mut self, keep: Option<(usize, bool)>) {
+        let free: Vec<(usize, bool)> = self.elements.iter().enumerate()
+            .filter_map(|(i, e)| match e {
+                Element::Gears(_, locks) => Some([(i, false, locks.driver), (i, true, locks.driven)]),
+                Element::Gearbox { .. } => None,
+            })
+            .flatten()
+            .filter(|&(i, driven, locked)| !locked && keep != Some((i, driven)))
+            .map(|(i, driven, _)| (i, driven))
+            .collect();
+        for (n, &(i, driven)) in free.iter().enumerate() {
+            // even share for all but the last, which gets exactly what is missing
+            let factor = (self.constraints.target / self.ratio()) as f64;
+            let share = if n + 1 == free.len() { factor } else { factor.powf(1.0 / (free.len() - n) as f64) };
+            if let Element::Gears(s, _) = &mut self.elements[i] {
+                let gear = if driven { &mut s.driven } else { &mut s.driver };
+                let exact = if driven { *gear as f64 * share } else { *gear as f64 / share };
+                *gear = exact.round().clamp(1.0, 1000.0) as u32;
+            }
+        }
+    }
+
+    // inserts right away if nothing breaks, asks otherwise
+    fn insert(&mut self, e: Element, inventory: &[u32]) {
+        let mut with = self.elements.clone();
+        with.push(e.clone());
+        if self.constraints.violations(&with, inventory).is_empty() {
+            self.elements = with;
+        } else {
+            self.pending = Some(e);
+        }
+    }
+
+    fn constraints_ui(&mut self, ui: &mut egui::Ui) {
+        let c = &mut self.constraints;
+        let mut target_changed = false;
+        egui::CollapsingHeader::new(tr("Constraints")).id_source("gear_train_constraints").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut c.use_target, tr("Target ratio"));
+                target_changed = ui.add_enabled(c.use_target, egui::DragValue::new(&mut c.target).speed(0.1).clamp_range(0.01..=100000.0)).changed();
+                ui.label("±");
+                ui.add_enabled(c.use_target, egui::DragValue::new(&mut c.tolerance).speed(0.1).clamp_range(0.0..=100.0).suffix(" %"));
+            });
+            let held = ui.add_enabled(c.use_target, egui::Checkbox::new(&mut c.hold, tr("Hold the total at the target")))
+                .on_hover_text(tr("Changing a gear changes the unlocked gears so the total stays on target"))
+                .changed();
+            target_changed |= held && c.hold;
+            ui.checkbox(&mut c.inventory_only, tr("Only gears from the inventory"));
+            ui.horizontal(|ui| {
+                ui.label(tr("Max stages"));
+                ui.add(egui::DragValue::new(&mut c.max_stages).clamp_range(1..=20));
+            });
+        });
+        if target_changed && self.constraints.holds() {
+            self.distribute(None);
+        }
+    }
+
+    fn conflict_ui(&mut self, ctx: &egui::Context, inventory: &[u32]) {
+        let e = match &self.pending {
+            Some(e) => e.clone(),
+            None => return,
+        };
+        let mut with = self.elements.clone();
+        with.push(e);
+        let violations = self.constraints.violations(&with, inventory);
+        let fresh = self.suggestion.as_ref()
+            .is_some_and(|s| s.train == with && s.inventory == inventory && s.constraints == self.constraints);
+        if !fresh {
+            let adjusted = self.constraints.adjust(&with, inventory)
+                .filter(|a| self.constraints.violations(a, inventory).is_empty());
+            self.suggestion = Some(Suggestion {
+                train: with.clone(),
+                inventory: inventory.to_vec(),
+                constraints: self.constraints.clone(),
+                adjusted,
+            });
+        }
+        let adjusted = self.suggestion.as_ref().and_then(|s| s.adjusted.clone());
+
+        egui::Window::new(tr("Preset conflict")).id(egui::Id::new("preset_conflict")).collapsible(false).resizable(false).show(ctx, |ui| {
+            ui.label(tr("Inserting this gearbox breaks the constraints:"));
+            for v in &violations {
+                ui.colored_label(ui.visuals().warn_fg_color, format!("• {}", v));
+            }
+            ui.horizontal(|ui| {
+                if ui.button(tr("Insert anyway")).clicked() {
+                    self.elements = with.clone();
+                    self.pending = None;
+                }
+                if ui.add_enabled(adjusted.is_some(), egui::Button::new(tr("Auto-adjust")))
+                    .on_hover_text(tr("change one gear stage to get back within the constraints"))
+                    .on_disabled_hover_text(tr("no gear stage change fixes this"))
+                    .clicked()
+                {
+                    self.elements = adjusted.clone().unwrap();
+                    self.pending = None;
+                }
+                if ui.button(tr("Cancel")).clicked() {
+                    self.pending = None;
+                }
+            });
+        });
+    }
+
+    // the gears of the gear stages as discs, each at the speed of its shaft
+    fn inertia_ui(&mut self, ui: &mut egui::Ui, materials: &[Material]) {
+        egui::Grid::new("gear_train_inertia_inputs").show(ui, |ui| {
+            ui.label(tr("Module"));
+            ui.add(egui::DragValue::new(&mut self.module).speed(0.01).clamp_range(0.05..=100.0).suffix(" mm"));
+            ui.end_row();
+            ui.label(tr("Face width"));
+            ui.add(egui::DragValue::new(&mut self.face_width).speed(0.1).clamp_range(0.1..=1000.0).suffix(" mm"));
+            ui.end_row();
+            ui.label(tr("Material"));
+            self.material = self.material.min(materials.len().saturating_sub(1));
+            egui::ComboBox::from_id_source("gear_train_material")
+                .selected_text(materials.get(self.material).map_or("", |m| m.name.as_str()))
+                .show_ui(ui, |ui| {
+                    for (i, m) in materials.iter().enumerate() {
+                        ui.selectable_value(&mut self.material, i, m.name.as_str());
+                    }
+                });
+            ui.end_row();
+        });
+        let density = materials.get(self.material).map_or(1.0, |m| m.density);
+        let disc = |teeth: u32| Body::tube(density, 0.0, self.module * teeth as f32, self.face_width);
+        let mut gears = Vec::new();
+        // of the shaft the next driver sits on
+        let mut ratio = 1.0;
+        for (i, e) in self.elements.iter().enumerate() {
+            if let Element::Gears(s, _) = e {
+                gears.push((format!("{} {} {}", tr("Stage"), i + 1, tr("driver")), disc(s.driver), ratio));
+                gears.push((format!("{} {} {}", tr("Stage"), i + 1, tr("driven")), disc(s.driven), ratio * e.ratio()));
+            }
+            ratio *= e.ratio();
+        }
+        inertia::train_ui(ui, &gears);
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, inventory: &Dataset, catalog: &Dataset, materials: &[Material]) {
+        let teeth = dataset::inventory_teeth(inventory);
+        self.constraints_ui(ui);
+
+        ui.horizontal(|ui| {
+            ui.label(tr("Input speed"));
+            ui.add(egui::DragValue::new(&mut self.input_speed).speed(10.0).clamp_range(0.0..=100000.0).suffix(" rpm"));
+        });
+
+        let mut edited = None;
+        let (mut duplicate, mut delete) = (None, None);
+        let count = self.elements.len();
+        egui::Grid::new("gear_train").striped(true).show(ui, |ui| {
+            ui.label(egui::RichText::new(tr("Stage")).strong());
+            ui.label(egui::RichText::new(tr("Driver")).strong());
+            ui.label(egui::RichText::new(tr("Idlers")).strong());
+            ui.label(egui::RichText::new(tr("Driven")).strong());
+            ui.label(egui::RichText::new(tr("Ratio")).strong());
+            ui.label(egui::RichText::new(tr("Mesh frequency")).strong());
+            ui.label("");
+            ui.end_row();
+
+            let mut direction = Direction::Same;
+            let mut speed = self.input_speed;
+            for (i, e) in self.elements.iter_mut().enumerate() {
+                ui.label(format!("{}", i + 1));
+                match e {
+                    Element::Gears(s, locks) => {
+                        if gear_ui(ui, &mut s.driver, &mut locks.driver, tr("Driver")) {
+                            edited = Some((i, false));
+                        }
+                        ui.add(egui::DragValue::new(&mut s.idlers).clamp_range(0..=9))
+                            .on_hover_text(tr("Idler gears between driver and driven, each one flips the direction"));
+                        if gear_ui(ui, &mut s.driven, &mut locks.driven, tr("Driven")) {
+                            edited = Some((i, true));
+                        }
+                    }
+                    Element::Gearbox { name, efficiency, .. } => {
+                        ui.label(name.as_str());
+                        ui.label("");
+                        ui.label(format!("η {:.0} %", efficiency)).on_hover_text(tr("Efficiency"));
+                    }
+                }
+                ui.label(format!("{:.3}", e.ratio()));
+                match e {
+                    Element::Gears(s, _) => ui.label(format!("{:.1} Hz", s.driver as f32 * speed / 60.0)),
+                    Element::Gearbox { .. } => ui.label("–"),
+                };
+                speed /= e.ratio();
+                // of this element's output shaft
+                direction = direction.then(e.direction());
+                direction.show(ui);
+                if ui.small_button("⎘").on_hover_text(tr("Duplicate stage")).clicked() {
+                    duplicate = Some(i);
+                }
+                if ui.add_enabled(count > 1, egui::Button::new("🗑").small()).on_hover_text(tr("Delete stage")).clicked() {
+                    delete = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = duplicate {
+            self.elements.insert(i + 1, self.elements[i].clone());
+        }
+        if let Some(i) = delete {
+            self.elements.remove(i);
+        }
+        // the remaining gears make up for the added or removed ratio
+        let reshaped = duplicate.is_some() || delete.is_some();
+        if (edited.is_some() || reshaped) && self.constraints.holds() {
+            self.distribute(edited);
+        }
+
+        let resized = ui.horizontal(|ui| {
+            let mut resized = false;
+            if ui.button(tr("Add gear stage")).clicked() {
+                self.elements.push(Element::Gears(Stage::new(12, 36), Locks::default()));
+                resized = true;
+            }
+            ui.add_enabled_ui(self.pending.is_none(), |ui| {
+                ui.menu_button(tr("Add gearbox"), |ui| {
+                    let mut series: Vec<&str> = Vec::new();
+                    for r in catalog.valid_rows() {
+                        let s = catalog.text(r, "Series");
+                        if !series.contains(&s) {
+                            series.push(s);
+                        }
+                    }
+                    for s in series {
+                        ui.menu_button(s, |ui| {
+                            for r in catalog.valid_rows().filter(|&r| catalog.text(r, "Series") == s) {
+                                let name = catalog.text(r, "Name");
+                                let ratio = catalog.real(r, "Ratio") as f32;
+                                let efficiency = catalog.real(r, "Efficiency (%)") as f32;
+                                if ui.button(format!("{} ({:.2}:1, {:.0} %)", name, ratio, efficiency)).clicked() {
+                                    self.insert(Element::Gearbox { name: name.to_string(), ratio, efficiency }, &teeth);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    }
+                    ui.separator();
+                    ui.weak(tr("More in Tools → Datasets."));
+                });
+            });
+            if self.elements.len() > 1 && ui.button(tr("Remove stage")).clicked() {
+                self.elements.pop();
+                resized = true;
+            }
+            resized
+        }).inner;
+        // like a duplicated or deleted stage
+        if resized && self.constraints.holds() {
+            self.distribute(None);
+        }
+        ui.label(egui::RichText::new(format!("{} {:.4}", tr("Total ratio:"), self.ratio())).strong());
+        if let Some(e) = total_efficiency(&self.elements) {
+            ui.label(format!("{} {:.1} %", tr("Efficiency of the gearboxes:"), e * 100.0));
+        }
+        ui.horizontal(|ui| {
+            let direction = total_direction(&self.elements);
+            ui.label(format!("{} {}", tr("Output turns"), tr(direction.name())));
+            direction.show(ui);
+        });
+        for v in self.constraints.violations(&self.elements, &teeth) {
+            ui.colored_label(ui.visuals().warn_fg_color, v);
+        }
+
+        egui::CollapsingHeader::new(tr("Schematic")).id_source("gear_train_schematic").default_open(true).show(ui, |ui| {
+            schematic::train_schematic(ui, &self.elements);
+        });
+        egui::CollapsingHeader::new(tr("Mass and inertia")).id_source("gear_train_inertia").show(ui, |ui| {
+            self.inertia_ui(ui, materials);
+        });
+
+        self.conflict_ui(ui.ctx(), &teeth);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_target_no_search() {
+        let train = vec![Element::Gears(Stage::new(12, 37), Locks::default())];
+        let constraints = Constraints::new();
+        assert_eq!(constraints.adjust(&train, &[12, 36]), None);
+        // only snapped to the inventory
+        let constraints = Constraints { inventory_only: true, ..Constraints::new() };
+        assert_eq!(constraints.adjust(&train, &[12, 36]), Some(vec![Element::Gears(Stage::new(12, 36), Locks::default())]));
+    }
+
+    #[test]
+    fn target_changes_a_driven_gear() {
+        let train = vec![Element::Gears(Stage::new(12, 36), Locks::default())];
+        let constraints = Constraints { use_target: true, target: 4.0, ..Constraints::new() };
+        assert_eq!(constraints.adjust(&train, &[]), Some(vec![Element::Gears(Stage::new(12, 48), Locks::default())]));
+    }
+
+    #[test]
+    fn added_stage_is_made_up_for() {
+        let mut train = GearTrain::new();
+        train.constraints = Constraints { use_target: true, hold: true, target: 3.0, ..Constraints::new() };
+        train.elements = vec![Element::Gears(Stage::new(12, 36), Locks { driver: true, driven: true })];
+        train.elements.push(Element::Gears(Stage::new(12, 36), Locks::default()));
+        train.distribute(None);
+        assert_eq!(train.elements[0], Element::Gears(Stage::new(12, 36), Locks { driver: true, driven: true }));
+        assert!((train.ratio() - 3.0).abs() < 0.1, "{}", train.ratio());
+    }
+}
@@ -0,0 +1,165 @@
+use eframe::egui;
+
+use crate::band::Band;
+use crate::i18n::tr;
+use crate::plot::{self, Series};
+use crate::train::Stage;
+use crate::worker::{self, Job, Progress};
+
+/*
+ * Multi-speed gearboxes like in machine tools: every speed is one gear pair between the
+ * same two shafts, so with one module all pairs need the same tooth sum.
+ *
+ * The ratios follow a series from the first to the last speed. Geometric series keep the
+ * step between speeds constant. Progressive series (after Jante) make the steps smaller
+ * towards the top speeds, with the progression factor psi; psi = 1 is geometric.
+ *     i_k = i_z^((z - k) / (z - 1)) * psi^((z - k)(k - 1) / 2)
+ * where z is the number of speeds, i_z is the overall spread first / last, and the series
+ * is scaled to end at the last ratio.
+ */
+
+struct Layout {
+    tooth_sum: u32,
+    pairs: Vec<Stage>,
+    // largest relative error of any speed
+    worst: f64,
+}
+
+// tooth pairs for every ratio of the series, one layout per tooth sum, the best ten first
+fn layouts(series: &[f64], min_teeth: u32, max_sum: u32, progress: &Progress) -> Option<Vec<Layout>> {
+    let sums = (2 * min_teeth)..=max_sum;
+    let count = sums.clone().count();
+    let mut results = Vec::new();
+    for (i, sum) in sums.enumerate() {
+        if !progress.report(i as f32 / count as f32) {
+            return None;
+        }
+        let pairs: Vec<Stage> = series.iter().map(|&r| {
+            // driven / driver = r with driver + driven = sum
+            let driver = (sum as f64 / (1.0 + r)).round() as u32;
+            let driver = driver.clamp(min_teeth, sum - min_teeth);
+            Stage::new(driver, sum - driver)
+        }).collect();
+        let worst = pairs.iter().zip(series)
+            .map(|(s, r)| (s.ratio() as f64 / r - 1.0).abs())
+            .fold(0.0, f64::max);
+        results.push(Layout { tooth_sum: sum, pairs, worst });
+    }
+    results.sort_by(|a, b| a.worst.total_cmp(&b.worst));
+    results.truncate(10);
+    Some(results)
+}
+
+pub struct Gearbox {
+    first: f64,
+    last: f64,
+    speeds: usize,
+    psi: f64,
+    min_teeth: u32,
+    max_sum: u32,
+    // the series the results were searched for
+    wanted: Vec<f64>,
+    results: Vec<Layout>,
+    // the series and the layouts found for it
+    job: Option<Job<(Vec<f64>, Vec<Layout>)>>,
+}
+
+impl Gearbox {
+    pub fn new() -> Gearbox {
+        Gearbox {
+            first: 4.0,
+            last: 0.8,
+            speeds: 5,
+            psi: 1.0,
+            min_teeth: 14,
+            max_sum: 120,
+            wanted: Vec::new(),
+            results: Vec::new(),
+            job: None,
+        }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.first as f32
+    }
+
+    // the wanted ratio of every speed, first to last
+    fn series(&self) -> Vec<f64> {
+        let z = self.speeds as f64;
+        let phi = (self.first / self.last).powf(1.0 / (z - 1.0));
+        (1..=self.speeds).map(|k| {
+            let k = k as f64;
+            self.last * phi.powf(z - k) * self.psi.powf((z - k) * (k - 1.0) / 2.0)
+        }).collect()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, band: &Band) {
+        egui::Grid::new("gearbox_inputs").show(ui, |ui| {
+            ui.label(tr("First speed ratio"));
+            ui.add(egui::DragValue::new(&mut self.first).speed(0.01).clamp_range(0.05..=50.0));
+            ui.end_row();
+            ui.label(tr("Last speed ratio"));
+            ui.add(egui::DragValue::new(&mut self.last).speed(0.01).clamp_range(0.05..=50.0));
+            ui.end_row();
+            ui.label(tr("Speeds"));
+            ui.add(egui::DragValue::new(&mut self.speeds).clamp_range(2..=12));
+            ui.end_row();
+            ui.label(tr("Progression ψ"));
+            ui.add(egui::DragValue::new(&mut self.psi).speed(0.005).clamp_range(0.8..=1.3))
+                .on_hover_text(tr("1 is a geometric series, above 1 the steps get smaller towards the last speed"));
+            ui.end_row();
+            ui.label(tr("Smallest gear"));
+            ui.add(egui::DragValue::new(&mut self.min_teeth).clamp_range(6..=100).suffix(" T"));
+            ui.end_row();
+            ui.label(tr("Largest tooth sum"));
+            ui.add(egui::DragValue::new(&mut self.max_sum).clamp_range(2 * self.min_teeth..=500));
+            ui.end_row();
+        });
+
+        let series = self.series();
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr("Wanted ratios:"));
+            for (i, r) in series.iter().enumerate() {
+                let step = if i > 0 { format!(" ({} {:.3})", tr("step"), series[i - 1] / r) } else { String::new() };
+                ui.label(format!("{:.3}{}", r, step));
+            }
+        });
+        plot::ratio_steps(ui, "gearbox_steps", &[Series { name: tr("Wanted").to_string(), ratios: series.clone() }]);
+        if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Find tooth pairs"))).clicked() {
+            let (min_teeth, max_sum) = (self.min_teeth, self.max_sum);
+            self.job = Some(Job::spawn(ui.ctx(), move |p| {
+                layouts(&series, min_teeth, max_sum, p).map(|found| (series, found))
+            }));
+        }
+        if let Some((wanted, results)) = worker::job_ui(ui, &mut self.job) {
+            self.wanted = wanted;
+            self.results = results;
+        }
+
+        if !self.results.is_empty() && !self.results.iter().any(|l| band.shows(l.worst)) {
+            band.empty_note(ui);
+        }
+        for (n, l) in self.results.iter().enumerate().filter(|(_, l)| band.shows(l.worst)) {
+            egui::CollapsingHeader::new(format!("{} {}: {} {:.2} %", tr("Tooth sum"), l.tooth_sum, tr("worst error"), l.worst * 100.0))
+                .id_source(("gearbox_layout", n))
+                .default_open(n == 0)
+                .show(ui, |ui| {
+                    egui::Grid::new(("gearbox_pairs", n)).striped(true).show(ui, |ui| {
+                        for h in ["Gear", "Driver", "Driven", "Ratio", "Wanted", ""] {
+                            ui.label(egui::RichText::new(tr(h)).strong());
+                        }
+                        ui.end_row();
+                        for (i, (s, r)) in l.pairs.iter().zip(&self.wanted).enumerate() {
+                            ui.label(format!("{}", i + 1));
+                            ui.label(format!("{}", s.driver));
+                            ui.label(format!("{}", s.driven));
+                            ui.label(format!("{:.3}", s.ratio()));
+                            ui.label(format!("{:.3}", r));
+                            band.mark(ui, s.ratio() as f64 / r - 1.0);
+                            ui.end_row();
+                        }
+                    });
+                });
+        }
+    }
+}
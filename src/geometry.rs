@@ -0,0 +1,396 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * Involute geometry of the gear pair.
+ *
+ * Profile shift moves the cutting tool away from the gear (x > 0) or into it (x < 0), in
+ * multiples of the module. The teeth get thicker at the root, so small gears escape undercut,
+ * and the pair meshes at a working pressure angle that follows from
+ *     inv(αw) = inv(α) + 2 tan(α) (x1 + x2) / (z1 + z2),    inv(α) = tan(α) - α
+ * which changes the center distance to
+ *     aw = m (z1 + z2) / 2 · cos(α) / cos(αw)
+ * Ring gears use the differences z2 - z1 and x2 - x1 instead of the sums.
+ *
+ * Helical gears are given by their normal module and pressure angle, the ones of the
+ * cutter. Across the axis the teeth are wider apart: the transverse module is m / cos(β)
+ * and tan(αt) = tan(α) / cos(β), and everything above works with those. A herringbone gear
+ * is two helical halves of opposite hand, the geometry is the same but the axial thrust
+ * cancels out.
+ *
+ * Going the other way, the shift sum for a center distance that doesn't fit the teeth, lets
+ * the gears mesh there without changing their tooth counts.
+ *
+ * The transverse contact ratio is the length of the path of contact, where the tip circles
+ * cut the line of action, over the base pitch. It says how many teeth are in mesh on
+ * average, below about 1.2 there are moments where a single pair carries everything and
+ * the mesh runs rough.
+ *
+ * The tooth outlines for export are involutes from the base circle to the tip, with a
+ * radial flank below the base circle and the root as a circle. Printed gears need some play
+ * to turn, the backlash is taken off the tooth thickness at the pitch circle, half on each
+ * gear. A ring gear is drawn as its tooth spaces, which are shaped like the teeth of an
+ * external gear.
+ */
+
+// points per involute flank
+const FLANK_POINTS: usize = 8;
+
+pub struct Geometry {
+    // in degrees
+    pub pressure_angle: f32,
+    // profile shift coefficients of the input and output gear
+    pub shift: [f32; 2],
+    // addendum in multiples of the module, the dedendum is a quarter more
+    pub addendum: f32,
+    // helix angle in degrees, 0 for spur gears
+    pub helix: f32,
+    pub herringbone: bool,
+    // play between the flanks at the pitch circle, in mm
+    pub backlash: f32,
+    #[cfg(not(target_arch = "wasm32"))]
+    path: String,
+    status: String,
+}
+
+// where the gear outlines go, everything in mm
+pub struct Pair {
+    pub module: f32,
+    pub left: u32,
+    pub right: u32,
+    pub ring: bool,
+    pub center_distance: f32,
+}
+
+// an outline of teeth shaped like external gear teeth, around the origin. thickness is
+// the arc at the pitch circle, offset the angle of the first tooth.
+fn outline(teeth: u32, module: f64, alpha: f64, thickness: f64, tip: f64, root: f64, offset: f64) -> Vec<(f64, f64)> {
+    let r = module * teeth as f64 / 2.0;
+    let base = r * alpha.cos();
+    // half the angle a tooth takes at a radius, measured from its middle
+    let half = |radius: f64| {
+        let at = (base / radius.max(base)).acos();
+        (thickness / (2.0 * r) + involute(alpha) - involute(at)).max(0.0)
+    };
+    let start = root.max(base).min(tip);
+    let flank: Vec<f64> = (0..=FLANK_POINTS).map(|i| start + (tip - start) * i as f64 / FLANK_POINTS as f64).collect();
+    let polar = |radius: f64, angle: f64| (radius * angle.cos(), radius * angle.sin());
+
+    let mut points = Vec::new();
+    for k in 0..teeth {
+        let middle = offset + std::f64::consts::TAU * k as f64 / teeth as f64;
+        points.push(polar(root, middle - half(start)));
+        points.extend(flank.iter().map(|&radius| polar(radius, middle - half(radius))));
+        points.extend(flank.iter().rev().map(|&radius| polar(radius, middle + half(radius))));
+        points.push(polar(root, middle + half(start)));
+    }
+    points
+}
+
+fn svg_path(points: &[(f64, f64)], x: f64, y: f64) -> String {
+    let coordinates: Vec<String> = points.iter().map(|(px, py)| format!("{:.4},{:.4}", x + px, y - py)).collect();
+    format!("<path d=\"M {} Z\" fill=\"none\" stroke=\"black\" stroke-width=\"0.1\"/>\n", coordinates.join(" L "))
+}
+
+fn involute(angle: f64) -> f64 {
+    angle.tan() - angle
+}
+
+// the angle to an involute function value, by Newton's method
+fn inverse_involute(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 0.0;
+    }
+    let mut angle = (3.0 * value).cbrt();
+    for _ in 0..20 {
+        angle -= (involute(angle) - value) / angle.tan().powi(2);
+    }
+    angle
+}
+
+impl Geometry {
+    pub fn new() -> Geometry {
+        Geometry {
+            pressure_angle: 20.0,
+            shift: [0.0, 0.0],
+            backlash: 0.2,
+            addendum: 1.0,
+            helix: 0.0,
+            herringbone: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            path: String::from("gears.svg"),
+            status: String::new(),
+        }
+    }
+
+    // normal pressure angle
+    fn alpha_n(&self) -> f64 {
+        (self.pressure_angle as f64).to_radians()
+    }
+
+    fn cos_beta(&self) -> f64 {
+        (self.helix as f64).to_radians().cos()
+    }
+
+    // the module across the axis
+    pub fn transverse_module(&self, module: f32) -> f32 {
+        (module as f64 / self.cos_beta()) as f32
+    }
+
+    // transverse pressure angle
+    fn alpha(&self) -> f64 {
+        (self.alpha_n().tan() / self.cos_beta()).atan()
+    }
+
+    // teeth and shift across the center distance
+    fn across(&self, left: u32, right: u32, ring: bool) -> (f64, f64) {
+        if ring {
+            (right as f64 - left as f64, (self.shift[1] - self.shift[0]) as f64)
+        } else {
+            ((left + right) as f64, (self.shift[0] + self.shift[1]) as f64)
+        }
+    }
+
+    // in radians
+    pub fn working_pressure_angle(&self, left: u32, right: u32, ring: bool) -> f64 {
+        let (teeth, shift) = self.across(left, right, ring);
+        if teeth <= 0.0 {
+            return self.alpha();
+        }
+        inverse_involute(involute(self.alpha()) + 2.0 * self.alpha_n().tan() * shift / teeth)
+    }
+
+    // the working center distance for a module of 1 mm
+    pub fn center_factor(&self, left: u32, right: u32, ring: bool) -> f32 {
+        let (teeth, _) = self.across(left, right, ring);
+        let alpha_w = self.working_pressure_angle(left, right, ring);
+        (teeth / (2.0 * self.cos_beta()) * self.alpha().cos() / alpha_w.cos()) as f32
+    }
+
+    // the shift across the center distance that makes the pair mesh at this center
+    // distance per mm of module, None if no shift gets there
+    pub fn shift_for(&self, factor: f32, left: u32, right: u32, ring: bool) -> Option<f32> {
+        let (teeth, _) = self.across(left, right, ring);
+        let cos_w = teeth / (2.0 * self.cos_beta()) * self.alpha().cos() / factor as f64;
+        if teeth <= 0.0 || !(0.0..1.0).contains(&cos_w) {
+            return None;
+        }
+        let shift = (involute(cos_w.acos()) - involute(self.alpha())) * teeth / (2.0 * self.alpha_n().tan());
+        Some(shift as f32)
+    }
+
+    // fewest teeth a gear with this shift has before the tool cuts into its flanks
+    pub fn min_teeth(&self, shift: f32) -> f32 {
+        (2.0 * (1.0 - shift as f64) * self.cos_beta() / self.alpha().sin().powi(2)) as f32
+    }
+
+    // the smallest shift that keeps a gear with these teeth free of undercut
+    pub fn min_shift(&self, teeth: u32) -> f32 {
+        (1.0 - teeth as f64 * self.alpha().sin().powi(2) / (2.0 * self.cos_beta())) as f32
+    }
+
+    // tip and root radius of the input (0) or output (1) gear
+    fn radii(&self, pair: &Pair, gear: usize) -> (f64, f64) {
+        let m = pair.module as f64;
+        let teeth = if gear == 0 { pair.left } else { pair.right };
+        let r = self.transverse_module(pair.module) as f64 * teeth as f64 / 2.0;
+        let (ha, x) = (self.addendum as f64, self.shift[gear] as f64);
+        // the teeth of a ring point inwards
+        if gear == 1 && pair.ring {
+            (r - m * (ha - x), r + m * (ha + 0.25 + x))
+        } else {
+            (r + m * (ha + x), r - m * (ha + 0.25 - x))
+        }
+    }
+
+    // transverse contact ratio at the center distance of the pair, 0 if they don't mesh
+    pub fn contact_ratio(&self, pair: &Pair) -> f64 {
+        let mt = self.transverse_module(pair.module) as f64;
+        let alpha = self.alpha();
+        let base = |teeth: u32| mt * teeth as f64 / 2.0 * alpha.cos();
+        // from the tangent point on the base circle to the tip circle
+        let path = |gear: usize, teeth: u32| (self.radii(pair, gear).0.powi(2) - base(teeth).powi(2)).max(0.0).sqrt();
+        let a = pair.center_distance as f64;
+        let (rb1, rb2) = (base(pair.left), base(pair.right));
+        let cos_w = if pair.ring { (rb2 - rb1) / a } else { (rb1 + rb2) / a };
+        if !(f64::EPSILON..=1.0).contains(&cos_w) {
+            return 0.0;
+        }
+        let line = a * (1.0 - cos_w.powi(2)).sqrt();
+        let length = if pair.ring {
+            path(0, pair.left) - path(1, pair.right) + line
+        } else {
+            path(0, pair.left) + path(1, pair.right) - line
+        };
+        (length / (std::f64::consts::PI * mt * alpha.cos())).max(0.0)
+    }
+
+    // both gears meshing, the input gear on the left, as an SVG in mm. helical gears are
+    // cut across the axis.
+    pub fn svg(&self, pair: &Pair) -> String {
+        let m = pair.module as f64;
+        let mt = m / self.cos_beta();
+        let alpha = self.alpha();
+        let play = self.backlash as f64 / 2.0;
+        let pitch = |teeth: u32| mt * teeth as f64 / 2.0;
+        let thickness = |x: f32| mt * (std::f64::consts::FRAC_PI_2 + 2.0 * x as f64 * self.alpha_n().tan());
+        let a = pair.center_distance as f64;
+
+        let (tip, root) = self.radii(pair, 0);
+        let pinion = outline(pair.left, mt, alpha, thickness(self.shift[0]) - play, tip, root, 0.0);
+        let (tip, root) = self.radii(pair, 1);
+        let (wheel, outside) = if pair.ring {
+            // the spaces of the ring, the play widens them
+            let space = std::f64::consts::PI * mt - thickness(-self.shift[1]) + play;
+            (outline(pair.right, mt, alpha, space, root, tip, 0.0), root + 3.0 * m)
+        } else {
+            let turn = std::f64::consts::PI + std::f64::consts::PI / pair.right as f64;
+            (outline(pair.right, mt, alpha, thickness(self.shift[1]) - play, tip, root, turn), tip)
+        };
+
+        // the ring around the pinion, otherwise side by side
+        let (pinion_x, wheel_x) = if pair.ring { (outside + a, outside) } else { (pitch(pair.left) + 2.0 * m, pitch(pair.left) + 2.0 * m + a) };
+        let width = if pair.ring { 2.0 * outside } else { wheel_x + outside + m };
+        let height = 2.0 * outside.max(pitch(pair.left) + 2.0 * m);
+        let y = height / 2.0;
+
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0:.3}mm\" height=\"{1:.3}mm\" viewBox=\"0 0 {0:.3} {1:.3}\">\n", width, height);
+        if self.helix > 0.0 {
+            let kind = if self.herringbone { "herringbone" } else { "helical" };
+            svg += &format!("<desc>{}, helix angle {}°, normal module {}, transverse section</desc>\n", kind, self.helix, pair.module);
+        }
+        svg += &svg_path(&pinion, pinion_x, y);
+        svg += &svg_path(&wheel, wheel_x, y);
+        if pair.ring {
+            svg += &format!("<circle cx=\"{:.4}\" cy=\"{:.4}\" r=\"{:.4}\" fill=\"none\" stroke=\"black\" stroke-width=\"0.1\"/>\n", wheel_x, y, outside);
+        }
+        for x in [pinion_x, wheel_x] {
+            svg += &format!("<circle cx=\"{:.4}\" cy=\"{:.4}\" r=\"{:.4}\" fill=\"none\" stroke=\"black\" stroke-width=\"0.1\"/>\n", x, y, m);
+        }
+        svg += "</svg>\n";
+        svg
+    }
+
+    // returns true if anything changed
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        egui::Grid::new("pair_geometry").show(ui, |ui| {
+            ui.label(tr("Pressure angle"));
+            changed |= ui.add(egui::DragValue::new(&mut self.pressure_angle).speed(0.1).clamp_range(10.0..=35.0).suffix("°")).changed();
+            ui.end_row();
+            ui.label(tr("Profile shift"));
+            ui.horizontal(|ui| {
+                for (x, name) in self.shift.iter_mut().zip(["x1", "x2"]) {
+                    ui.label(name);
+                    changed |= ui.add(egui::DragValue::new(x).speed(0.005).clamp_range(-1.0..=1.5).max_decimals(3)).changed();
+                }
+            });
+            ui.end_row();
+            ui.label(tr("Addendum"));
+            changed |= ui.add(egui::DragValue::new(&mut self.addendum).speed(0.01).clamp_range(0.5..=1.5).max_decimals(2).suffix(" × m")).changed();
+            ui.end_row();
+            ui.label(tr("Helix angle"));
+            ui.horizontal(|ui| {
+                changed |= ui.add(egui::DragValue::new(&mut self.helix).speed(0.1).clamp_range(0.0..=45.0).suffix("°")).changed();
+                ui.add_enabled(self.helix > 0.0, egui::Checkbox::new(&mut self.herringbone, tr("Herringbone")))
+                    .on_hover_text(tr("Two halves of opposite hand, the axial thrust cancels out"));
+            });
+            ui.end_row();
+            ui.label(tr("Backlash"));
+            ui.add(egui::DragValue::new(&mut self.backlash).speed(0.01).clamp_range(0.0..=2.0).max_decimals(2).suffix(" mm"))
+                .on_hover_text(tr("Printed gears need 0.1 to 0.3 mm to turn"));
+            ui.end_row();
+        });
+        changed
+    }
+
+    pub fn export_ui(&mut self, ui: &mut egui::Ui, pair: &Pair) {
+        ui.horizontal(|ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.add(egui::TextEdit::singleline(&mut self.path).desired_width(160.0));
+                if ui.button(tr("Export SVG")).clicked() {
+                    self.status = match std::fs::write(&self.path, self.svg(pair)) {
+                        Ok(()) => format!("{} {}", tr("Saved"), self.path),
+                        Err(e) => format!("{} {}", tr("Saving failed:"), e),
+                    };
+                }
+            }
+            // no file system in the browser
+            if ui.button(tr("Copy SVG")).clicked() {
+                let svg = self.svg(pair);
+                ui.output_mut(|o| o.copied_text = svg);
+                self.status = String::from(tr("Copied to clipboard."));
+            }
+        });
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(left: u32, right: u32, ring: bool, center_distance: f32) -> Pair {
+        Pair { module: 1.0, left, right, ring, center_distance }
+    }
+
+    #[test]
+    fn standard_center_distance() {
+        let g = Geometry::new();
+        assert!((g.center_factor(20, 40, false) - 30.0).abs() < 1e-5);
+        assert!((g.center_factor(20, 60, true) - 20.0).abs() < 1e-5);
+        assert!((g.min_teeth(0.0) - 17.1).abs() < 0.01);
+        assert!(g.min_shift(12) > 0.0 && g.min_shift(18) < 0.0);
+    }
+
+    #[test]
+    fn shift_for_center_distance() {
+        let mut g = Geometry::new();
+        g.shift = [0.3, 0.2];
+        let factor = g.center_factor(20, 40, false);
+        assert!(factor > 30.0);
+        let sum = Geometry::new().shift_for(factor, 20, 40, false).unwrap();
+        assert!((sum - 0.5).abs() < 1e-4);
+        // closer than the base circles touch
+        assert_eq!(Geometry::new().shift_for(28.0, 20, 40, false), None);
+    }
+
+    #[test]
+    fn backlash_thins_the_teeth() {
+        let mut g = Geometry::new();
+        let p = pair(20, 40, false, 30.0);
+        g.backlash = 0.0;
+        let tight = g.svg(&p);
+        g.backlash = 0.4;
+        assert_ne!(tight, g.svg(&p));
+        assert_eq!(tight.matches("<path").count(), 2);
+    }
+
+    #[test]
+    fn helical() {
+        let mut g = Geometry::new();
+        g.helix = 30.0;
+        let cos = 30f32.to_radians().cos();
+        assert!((g.transverse_module(2.0) - 2.0 / cos).abs() < 1e-5);
+        assert!((g.center_factor(20, 40, false) - 30.0 / cos).abs() < 1e-4);
+        // wider apart across the axis, so less undercut
+        assert!(g.min_teeth(0.0) < Geometry::new().min_teeth(0.0));
+    }
+
+    #[test]
+    fn contact_ratio() {
+        let g = Geometry::new();
+        let spur = g.contact_ratio(&pair(20, 40, false, 30.0));
+        assert!((1.6..1.7).contains(&spur), "{}", spur);
+        // more teeth in mesh with a ring
+        assert!(g.contact_ratio(&pair(20, 60, true, 20.0)) > spur);
+        // pulled apart the teeth barely touch, then not at all
+        assert!(g.contact_ratio(&pair(20, 40, false, 30.5)) < spur);
+        assert_eq!(g.contact_ratio(&pair(20, 40, false, 40.0)), 0.0);
+    }
+
+}
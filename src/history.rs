@@ -0,0 +1,124 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * The gear pairs the user settled on. A configuration counts as settled once it stayed the
+ * same for a few seconds, so scrolling through the teeth doesn't fill the history with every
+ * step on the way. Clicking an entry restores it.
+ *
+ * The history lives in memory and only survives a restart if the user wants it to.
+ */
+
+// seconds a configuration has to stay unchanged
+const SETTLE_SECONDS: f64 = 2.0;
+const MAX_ENTRIES: usize = 100;
+
+#[derive(Clone, PartialEq)]
+pub struct Entry {
+    pub left: u32,
+    pub right: u32,
+    pub given: f64,
+    pub module: f32,
+    pub center_distance: f32,
+    pub input_speed: f32,
+    pub output_speed: f32,
+}
+
+impl Entry {
+    fn to_line(&self) -> String {
+        format!("{}\t{}\t{}\t{}\t{}\t{}\t{}", self.left, self.right, self.given, self.module,
+            self.center_distance, self.input_speed, self.output_speed)
+    }
+
+    fn from_line(line: &str) -> Option<Entry> {
+        let c: Vec<&str> = line.split('\t').collect();
+        if c.len() != 7 {
+            return None;
+        }
+        Some(Entry {
+            left: c[0].parse().ok().filter(|t| *t > 0)?,
+            right: c[1].parse().ok().filter(|t| *t > 0)?,
+            given: c[2].parse().ok()?,
+            module: c[3].parse().ok()?,
+            center_distance: c[4].parse().ok()?,
+            input_speed: c[5].parse().ok()?,
+            output_speed: c[6].parse().ok()?,
+        })
+    }
+}
+
+pub struct History {
+    // oldest first
+    entries: Vec<Entry>,
+    // the configuration that may settle, and since when it is unchanged
+    candidate: Option<(Entry, f64)>,
+    // save the history with the app state
+    pub persist: bool,
+}
+
+impl History {
+    pub fn new() -> History {
+        History {
+            entries: Vec::new(),
+            candidate: None,
+            persist: false,
+        }
+    }
+
+    // called every frame with the current configuration
+    pub fn note(&mut self, ctx: &egui::Context, entry: Entry) {
+        let now = ctx.input(|i| i.time);
+        match &self.candidate {
+            Some((c, since)) if *c == entry => {
+                if now - since >= SETTLE_SECONDS && self.entries.last() != Some(&entry) {
+                    self.entries.push(entry);
+                    if self.entries.len() > MAX_ENTRIES {
+                        self.entries.remove(0);
+                    }
+                }
+            }
+            _ => {
+                self.candidate = Some((entry, now));
+                // nothing else may cause a frame until then
+                ctx.request_repaint_after(std::time::Duration::from_secs_f64(SETTLE_SECONDS));
+            }
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        self.entries.iter().map(|e| e.to_line()).collect::<Vec<_>>().join("\n")
+    }
+
+    // lines that don't parse are dropped
+    pub fn load_text(&mut self, text: &str) {
+        self.entries = text.lines().filter_map(Entry::from_line).collect();
+    }
+
+    // the entry to restore, if one was clicked
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<Entry> {
+        let mut restore = None;
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.persist, tr("Keep after restart"));
+            if ui.button(tr("Clear")).clicked() {
+                self.entries.clear();
+            }
+        });
+        ui.separator();
+        if self.entries.is_empty() {
+            ui.weak(tr("Gear pairs you stay with for a moment show up here."));
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, e) in self.entries.iter().enumerate().rev() {
+                let text = format!("{}. {}:{}  {} {:.3}", i + 1, e.left, e.right, tr("ratio"), e.right as f32 / e.left as f32);
+                let details = format!("{} {:.3}\n{} {} mm, {} {} mm\n{} rpm {}, {:.1} rpm {}",
+                    tr("given ratio"), e.given, tr("module"), e.module, tr("center distance"), e.center_distance,
+                    e.input_speed, tr("in"), e.output_speed, tr("out"));
+                if ui.button(text).on_hover_text(details).clicked() {
+                    restore = Some(e.clone());
+                }
+            }
+        });
+        restore
+    }
+}
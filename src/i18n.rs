@@ -0,0 +1,667 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/*
+ * Translations of the UI strings. The English text is the key, so strings that aren't
+ * translated yet just show up in English. The language is taken from the settings at the
+ * start of every frame, so tr() can be called anywhere without passing it around.
+ */
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Language {
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    // in the language itself, so it can be found when the UI is in a foreign one
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Language> {
+        Language::ALL.into_iter().find(|l| l.key() == key)
+    }
+
+    fn table(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => &[],
+            Language::German => GERMAN,
+        }
+    }
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+fn language() -> Language {
+    Language::ALL.get(LANGUAGE.load(Ordering::Relaxed) as usize).copied().unwrap_or(Language::English)
+}
+
+pub fn tr(text: &'static str) -> &'static str {
+    language().table().iter().find(|(en, _)| *en == text).map_or(text, |(_, t)| t)
+}
+
+const GERMAN: &[(&str, &str)] = &[
+    // gear pair
+    ("Input Gear", "Antriebsrad"),
+    ("Output Gear", "Abtriebsrad"),
+    ("Given Ratio: ", "Vorgabe: "),
+    ("Actual Ratio: ", "Übersetzung: "),
+    ("locked", "fest"),
+    ("Input gear teeth", "Zähne Antriebsrad"),
+    ("Output gear teeth", "Zähne Abtriebsrad"),
+    ("Given ratio", "Vorgegebene Übersetzung"),
+    ("Quick entry", "Schnelleingabe"),
+    ("Mesh and speeds", "Eingriff und Drehzahlen"),
+    ("Module", "Modul"),
+    ("Diametral pitch", "Diametral Pitch"),
+    ("Center distance", "Achsabstand"),
+    ("Input speed", "Antriebsdrehzahl"),
+    ("Output speed", "Abtriebsdrehzahl"),
+    ("Output teeth / input teeth, reduced", "Zähne Abtrieb / Zähne Antrieb, gekürzt"),
+    ("Error against the given ratio", "Abweichung von der Vorgabe"),
+    ("Ring gear", "Hohlrad"),
+    ("The pinion runs inside the output gear", "Das Ritzel läuft im Abtriebsrad"),
+    ("A ring gear needs more teeth than its pinion, at least", "Ein Hohlrad braucht mehr Zähne als sein Ritzel, mindestens"),
+    ("Pressure angle", "Eingriffswinkel"),
+    ("Profile shift", "Profilverschiebung"),
+    ("Fit with profile shift", "Mit Profilverschiebung anpassen"),
+    ("Changes x2 so the gears mesh at this center distance", "Ändert x2, damit die Räder bei diesem Achsabstand kämmen"),
+    ("No profile shift gets the gears there", "Keine Profilverschiebung bringt die Räder dorthin"),
+    ("get undercut, needs a profile shift of at least", "werden unterschnitten, nötig ist eine Profilverschiebung von mindestens"),
+    ("Addendum", "Kopfhöhe"),
+    ("Contact ratio", "Profilüberdeckung"),
+    ("Below 1.2 the mesh runs rough, fewer teeth are in contact than needed.", "Unter 1,2 läuft der Eingriff unruhig, es sind zu wenige Zähne im Eingriff."),
+    ("Helix angle", "Schrägungswinkel"),
+    ("Herringbone", "Pfeilverzahnung"),
+    ("Two halves of opposite hand, the axial thrust cancels out", "Zwei Hälften mit entgegengesetzter Steigung, die Axialkräfte heben sich auf"),
+    ("Backlash", "Flankenspiel"),
+    ("Printed gears need 0.1 to 0.3 mm to turn", "Gedruckte Zahnräder brauchen 0,1 bis 0,3 mm, um sich zu drehen"),
+    ("Export SVG", "SVG exportieren"),
+    ("Copy SVG", "SVG kopieren"),
+    ("Strength", "Festigkeit"),
+    ("Face width", "Zahnbreite"),
+    ("Input torque", "Antriebsmoment"),
+    ("Material", "Werkstoff"),
+    ("Stress", "Spannung"),
+    ("Safety", "Sicherheit"),
+    ("Output gear", "Abtriebsrad"),
+    ("fewer teeth than the Lewis table, the stress is higher than shown.", "weniger Zähne als die Lewis-Tabelle, die Spannung ist höher als angezeigt."),
+    ("Lewis formula with the Barth velocity factor, bending only.", "Lewis-Formel mit Barth-Geschwindigkeitsfaktor, nur Biegung."),
+    ("Mass and inertia", "Masse und Trägheit"),
+    ("driver", "treibend"),
+    ("driven", "getrieben"),
+    ("Mass", "Masse"),
+    ("Inertia", "Trägheitsmoment"),
+    ("At the input", "Am Antrieb"),
+    ("Total at the input shaft:", "Gesamt an der Antriebswelle:"),
+    ("Face width and materials are the ones of the strength check.", "Zahnbreite und Werkstoffe wie bei der Festigkeit."),
+    ("Inertia matching", "Trägheitsanpassung"),
+    ("Load inertia", "Lastträgheit"),
+    ("Motor inertia", "Motorträgheit"),
+    ("Target ratio", "Zielverhältnis"),
+    ("Load at the motor:", "Last am Motor:"),
+    ("Load to motor inertia:", "Last- zu Motorträgheit:"),
+    ("Reduction for the target:", "Untersetzung für das Ziel:"),
+    ("Use", "Übernehmen"),
+    ("Pin this ratio", "Übersetzung anheften"),
+    ("Unpin this ratio", "Übersetzung lösen"),
+    ("Right click to unpin", "Rechtsklick zum Lösen"),
+    ("The gears alone exceed the target.", "Schon die Zahnräder überschreiten das Ziel."),
+    ("Adopt", "Übernehmen"),
+    ("Use the actual ratio as the given ratio", "Die tatsächliche Übersetzung als Vorgabe übernehmen"),
+    ("Compound pairs", "Zweistufige Paare"),
+    ("Copy", "Kopieren"),
+    ("Copy details", "Details kopieren"),
+    ("The ratio has to be a positive number.", "Die Übersetzung muss eine positive Zahl sein."),
+    ("A gear needs at least one tooth.", "Ein Zahnrad braucht mindestens einen Zahn."),
+    ("Round to nearest", "Runden"),
+    ("Round down", "Abrunden"),
+    ("Round up", "Aufrunden"),
+    ("Smallest ratio error", "Kleinster Übersetzungsfehler"),
+    ("Tolerate", "Hinnehmen"),
+    ("Warn", "Warnen"),
+    ("Snap", "Angleichen"),
+    // gear trains
+    ("Idlers", "Zwischenräder"),
+    ("Mesh frequency", "Zahneingriffsfrequenz"),
+    ("Idler gears between driver and driven, each one flips the direction", "Zwischenräder zwischen Antrieb und Abtrieb, jedes kehrt die Drehrichtung um"),
+    ("Output turns", "Abtrieb dreht"),
+    ("Schematic", "Schema"),
+    ("Duplicate stage", "Stufe verdoppeln"),
+    ("Delete stage", "Stufe löschen"),
+    ("Efficiency", "Wirkungsgrad"),
+    ("Efficiency of the gearboxes:", "Wirkungsgrad der Getriebe:"),
+    ("More in Tools → Datasets.", "Weitere unter Werkzeuge → Datensätze."),
+    ("Hold the total at the target", "Gesamtübersetzung auf dem Ziel halten"),
+    ("Changing a gear changes the unlocked gears so the total stays on target", "Ein geändertes Rad passt die nicht gesperrten Räder an, damit die Gesamtübersetzung auf dem Ziel bleibt"),
+    ("idler", "Zwischenrad"),
+    ("same direction as the input", "in Richtung des Antriebs"),
+    ("reversed", "entgegengesetzt"),
+    // planetary gears
+    ("Stage", "Stufe"),
+    ("Sun", "Sonnenrad"),
+    ("Ring", "Hohlrad"),
+    ("Carrier", "Steg"),
+    ("held", "fest"),
+    ("Configuration", "Anordnung"),
+    ("Add planetary stage", "Planetenstufe hinzufügen"),
+    ("Planet", "Planetenrad"),
+    ("Planets", "Planeten"),
+    ("The ring needs more teeth than the sun.", "Das Hohlrad braucht mehr Zähne als das Sonnenrad."),
+    ("Ring minus sun teeth have to be even for whole planet teeth.", "Hohlrad- minus Sonnenradzähne müssen gerade sein, damit die Planeten ganze Zähne haben."),
+    ("Nearby tooth counts for stage", "Zähnezahlen in der Nähe für Stufe"),
+    ("Nothing buildable close by.", "Nichts Baubares in der Nähe."),
+    ("Sun plus ring teeth have to divide by the number of planets.", "Sonnenrad- plus Hohlradzähne müssen durch die Zahl der Planeten teilbar sein."),
+    ("The planets are too big to fit next to each other.", "Die Planeten sind zu groß, um nebeneinander zu passen."),
+    // modes
+    ("Mode", "Modus"),
+    ("Gear Ratio Calculator", "Übersetzungsrechner"),
+    ("Timing Belt Drive", "Zahnriementrieb"),
+    ("Pulley Drive", "Riementrieb"),
+    ("Match Trains", "Getriebe abgleichen"),
+    ("Re-ratio Helper", "Umübersetzung"),
+    ("Train Search", "Getriebesuche"),
+    ("Bicycle Drivetrain", "Fahrradantrieb"),
+    ("RC Car Final Drive", "RC-Auto Endübersetzung"),
+    ("Gear Train", "Rädergetriebe"),
+    ("Stepper Steps/mm", "Schrittmotor Schritte/mm"),
+    ("Lathe Change Gears", "Wechselräder Drehbank"),
+    ("Clock Going Train", "Uhr Gehwerk"),
+    ("Motion Works", "Zeigerwerk"),
+    ("Precise Ratio Approximation", "Genaue Näherung"),
+    ("Sidereal Drive", "Siderischer Antrieb"),
+    ("Multi-speed Gearbox", "Schaltgetriebe"),
+    ("Vehicle Gearing", "Fahrzeugübersetzung"),
+    ("CVT Ratio Range", "Stufenloses Getriebe"),
+    ("Cycloidal Drive", "Zykloidgetriebe"),
+    ("Planetary Gears", "Planetengetriebe"),
+    ("Differential", "Differential"),
+    // menus and tools
+    ("Settings", "Einstellungen"),
+    ("Tolerance", "Toleranz"),
+    ("Tools", "Werkzeuge"),
+    ("Conversions", "Umrechnungen"),
+    ("Datasets", "Datensätze"),
+    ("Classroom", "Unterricht"),
+    ("Mesh Tone", "Eingriffston"),
+    ("Sweep", "Durchlauf"),
+    ("Motor Output", "Motorleistung"),
+    ("Error vs Teeth", "Fehler über Zähne"),
+    ("History", "Verlauf"),
+    ("Compare", "Vergleich"),
+    ("Report", "Bericht"),
+    ("Attachments", "Anhänge"),
+    ("Attached", "Angehängt:"),
+    ("Attach", "Anhängen"),
+    ("File to attach", "Datei zum Anhängen"),
+    ("Remove attachment", "Anhang entfernen"),
+    ("Drop images or files on the window to attach them.", "Bilder oder Dateien auf das Fenster ziehen, um sie anzuhängen."),
+    ("Save project", "Projekt speichern"),
+    ("Open project", "Projekt öffnen"),
+    ("Opened", "Geöffnet:"),
+    ("Opening failed:", "Öffnen fehlgeschlagen:"),
+    ("Saved", "Gespeichert:"),
+    ("Saving failed:", "Speichern fehlgeschlagen:"),
+    ("Can't read", "Nicht lesbar:"),
+    ("Feedback", "Rückmeldung"),
+    ("Click sounds", "Klickgeräusche"),
+    ("Vibration", "Vibration"),
+    ("Calculation", "Berechnung"),
+    ("Close tab", "Tab schließen"),
+    ("new calculation", "neue Berechnung"),
+    ("Acceptable error ±", "Zulässiger Fehler ±"),
+    ("Hide results outside", "Ergebnisse außerhalb ausblenden"),
+    // settings
+    ("Spinners", "Drehfelder"),
+    ("Teeth step", "Schritt Zähne"),
+    ("Ratio step", "Schritt Übersetzung"),
+    ("Dragging", "Ziehen"),
+    ("Scrolling", "Scrollen"),
+    ("Invert", "Umkehren"),
+    ("Teeth", "Zähne"),
+    ("to", "bis"),
+    ("Holding shift takes finer steps, ctrl ten steps at once.", "Mit Umschalt feinere Schritte, mit Strg zehn Schritte auf einmal."),
+    ("Tab goes from field to field, F1, F2 and F3 move the lock.", "Tab springt von Feld zu Feld, F1, F2 und F3 verschieben die Sperre."),
+    ("Display", "Anzeige"),
+    ("Preview rows", "Vorschauzeilen"),
+    ("Ratio decimals", "Nachkommastellen"),
+    ("Decimal separator", "Dezimaltrennzeichen"),
+    ("Point (1.5)", "Punkt (1.5)"),
+    ("Comma (1,5)", "Komma (1,5)"),
+    ("Ratio error", "Übersetzungsfehler"),
+    ("Units", "Einheiten"),
+    ("Language", "Sprache"),
+    ("Theme", "Farbschema"),
+    ("Follow system", "Wie System"),
+    ("Light", "Hell"),
+    ("Dark", "Dunkel"),
+    ("New calculations", "Neue Berechnungen"),
+    ("Materials", "Werkstoffe"),
+    ("Allowable bending stress", "Zulässige Biegespannung"),
+    ("Delete material", "Werkstoff löschen"),
+    ("Density", "Dichte"),
+    ("Add material", "Werkstoff hinzufügen"),
+    ("Reset materials", "Werkstoffe zurücksetzen"),
+    ("Input gear", "Antriebsrad"),
+    ("Ratio", "Übersetzung"),
+    ("Metric (module, mm)", "Metrisch (Modul, mm)"),
+    ("Imperial (diametral pitch, in)", "Zöllig (Diametral Pitch, in)"),
+    ("Increase", "Erhöhen"),
+    ("Decrease", "Verringern"),
+    ("Allowed:", "Erlaubt:"),
+    ("At least", "Mindestens"),
+    ("At most", "Höchstens"),
+    ("Enter a number", "Zahl eingeben"),
+    // gear train constraints
+    ("stages", "Stufen"),
+    ("only this many fit:", "es passen nur"),
+    ("Total ratio", "Gesamtübersetzung"),
+    ("is", "liegt"),
+    ("off the target", "neben dem Ziel"),
+    ("no gear in the inventory with teeth:", "kein Rad im Bestand mit Zähnen:"),
+    ("Constraints", "Randbedingungen"),
+    ("Only gears from the inventory", "Nur Räder aus dem Bestand"),
+    ("Max stages", "Höchstens Stufen"),
+    ("Preset conflict", "Konflikt mit der Vorlage"),
+    ("Inserting this gearbox breaks the constraints:", "Dieses Getriebe verletzt die Randbedingungen:"),
+    ("Insert anyway", "Trotzdem einfügen"),
+    ("Auto-adjust", "Automatisch anpassen"),
+    ("change one gear stage to get back within the constraints", "eine Radstufe ändern, um die Randbedingungen wieder einzuhalten"),
+    ("no gear stage change fixes this", "keine Änderung einer Radstufe behebt das"),
+    ("Cancel", "Abbrechen"),
+    ("Driver", "Treibendes Rad"),
+    ("Driven", "Getriebenes Rad"),
+    ("Add gear stage", "Radstufe hinzufügen"),
+    ("Add gearbox", "Getriebe hinzufügen"),
+    ("Remove stage", "Stufe entfernen"),
+    ("Total ratio:", "Gesamtübersetzung:"),
+    // bicycle
+    ("Chainrings", "Kettenblätter"),
+    ("Cassette", "Kassette"),
+    ("Wheel circumference", "Radumfang"),
+    ("Wheel size", "Radgröße"),
+    ("Tire size", "Reifengröße"),
+    ("Overlap threshold", "Überschneidungsgrenze"),
+    ("Gear inches", "Gear Inches"),
+    ("Development (m)", "Entfaltung (m)"),
+    ("overlaps with a gear on another chainring", "überschneidet sich mit einem Gang auf einem anderen Kettenblatt"),
+    ("Derailleur", "Schaltwerk"),
+    ("Speed at cadence", "Geschwindigkeit bei Trittfrequenz"),
+    ("Ratio steps", "Übersetzungssprünge"),
+    ("ring", "Kettenblatt"),
+    ("Capacity", "Kapazität"),
+    ("Largest cog", "Größtes Ritzel"),
+    ("Needs a capacity of", "Benötigt eine Kapazität von"),
+    ("the derailleur has", "das Schaltwerk hat"),
+    ("to spare:", "Reserve:"),
+    ("The cog of", "Das Ritzel mit"),
+    ("is larger than the derailleur's maximum of", "ist größer als das Maximum des Schaltwerks von"),
+    ("Cadence", "Trittfrequenz"),
+    ("rpm in steps of", "U/min in Schritten von"),
+    ("Gear", "Gang"),
+    ("Speeds in km/h.", "Geschwindigkeiten in km/h."),
+    // train search
+    ("Smallest error", "Kleinster Fehler"),
+    ("Fewest teeth", "Wenigste Zähne"),
+    ("Smallest largest gear", "Kleinstes größtes Rad"),
+    ("Shortest center distance", "Kürzester Achsabstand"),
+    ("Total teeth", "Zähne gesamt"),
+    ("Largest gear", "Größtes Rad"),
+    ("Widest stage", "Breiteste Stufe"),
+    ("Any", "Beliebig"),
+    ("Fixed", "Fest"),
+    ("Teeth per gear", "Zähne je Rad"),
+    ("Stages", "Stufen"),
+    ("at most", "höchstens"),
+    ("Results per stage count", "Ergebnisse je Stufenzahl"),
+    ("module", "Modul"),
+    ("Optimize for", "Optimieren auf"),
+    ("Teeth of every stage together:", "Zähne jeder Stufe zusammen:"),
+    ("No whole tooth sum gives this center distance with this module.", "Keine ganze Zähnesumme ergibt diesen Achsabstand mit diesem Modul."),
+    ("Among the trains within", "Unter den Getrieben innerhalb"),
+    ("Tolerance menu", "Menü Toleranz"),
+    ("Search", "Suchen"),
+    ("Best train", "Bestes Getriebe"),
+    ("Error", "Fehler"),
+    ("Best trains with stages:", "Beste Getriebe mit Stufen:"),
+    ("Search compound pairs", "Zweistufige Paare suchen"),
+    ("exact", "exakt"),
+    // tolerance band
+    ("within", "innerhalb"),
+    ("outside", "außerhalb"),
+    ("Nothing within", "Nichts innerhalb"),
+    // clocks
+    ("Pendulum length", "Pendellänge"),
+    ("Pendulum period", "Pendelperiode"),
+    ("Balance", "Unruh"),
+    ("Length", "Länge"),
+    ("Period (tick and tock)", "Periode (Tick und Tack)"),
+    ("Beat rate", "Halbschwingungen"),
+    ("Escape wheel", "Ankerrad"),
+    ("teeth", "Zähne"),
+    ("Start arbor turns every", "Ausgangswelle dreht sich alle"),
+    ("Wheels", "Räder"),
+    ("Pinions", "Triebe"),
+    ("Period", "Periode"),
+    ("escape wheel turns every", "Ankerrad dreht sich alle"),
+    ("train step-up", "Übersetzung ins Schnelle"),
+    ("Search trains", "Räderwerke suchen"),
+    ("Stages:", "Stufen:"),
+    ("wheel/pinion", "Rad/Trieb"),
+    ("day", "Tag"),
+    // vehicles
+    ("Original final drive", "Ursprüngliche Achsübersetzung"),
+    ("Original tire size", "Ursprüngliche Reifengröße"),
+    ("Original circumference", "Ursprünglicher Abrollumfang"),
+    ("The speedometer reads correctly.", "Der Tacho zeigt richtig an."),
+    ("The speedometer reads", "Der Tacho zeigt"),
+    ("high", "zu viel"),
+    ("low", "zu wenig"),
+    ("Showing 100 km/h, you drive", "Bei angezeigten 100 km/h fahren Sie"),
+    ("Set as original", "Als ursprünglich übernehmen"),
+    ("Recalibrate for the current final drive and tire", "Auf die aktuelle Achsübersetzung und Reifen abgleichen"),
+    ("Final drive", "Achsübersetzung"),
+    ("Low range", "Geländeuntersetzung"),
+    ("Engaged", "Eingelegt"),
+    ("Tire circumference", "Abrollumfang"),
+    ("Redline", "Höchstdrehzahl"),
+    ("Crawl ratio:", "Kriechübersetzung:"),
+    ("Overall", "Gesamt"),
+    ("km/h per 1000 rpm", "km/h je 1000 U/min"),
+    ("At redline", "Bei Höchstdrehzahl"),
+    ("Upshift to", "Hochschalten auf"),
+    ("Delete gear", "Gang löschen"),
+    ("Add gear", "Gang hinzufügen"),
+    ("Speedometer error", "Tachoabweichung"),
+    // charts
+    ("gear", "Gang"),
+    ("ratio (log)", "Übersetzung (log)"),
+    ("Built without the \"plot\" feature, no chart.", "Ohne das Feature \"plot\" gebaut, kein Diagramm."),
+    ("steps", "Sprünge"),
+    ("torque", "Drehmoment"),
+    ("speed", "Drehzahl"),
+    ("output", "Abtrieb"),
+    ("at the load", "bei der Last"),
+    ("load", "Last"),
+    ("At", "Bei"),
+    ("at", "bei"),
+    ("redline", "Höchstdrehzahl"),
+    ("pinion teeth", "Zähne Ritzel"),
+    ("error", "Fehler"),
+    // belts and motors
+    ("Profile", "Profil"),
+    ("Driver pulley", "Treibende Scheibe"),
+    ("Driven pulley", "Getriebene Scheibe"),
+    ("Only stock belt lengths", "Nur lieferbare Riemenlängen"),
+    ("The pulleys overlap at this center distance.", "Die Scheiben überlappen bei diesem Achsabstand."),
+    ("Pitch diameters", "Wirkdurchmesser"),
+    ("Required belt", "Benötigter Riemen"),
+    ("Chosen belt", "Gewählter Riemen"),
+    ("Teeth in mesh", "Zähne im Eingriff"),
+    ("less than 6, the belt may skip", "weniger als 6, der Riemen kann überspringen"),
+    ("belt too short for these pulleys", "Riemen zu kurz für diese Scheiben"),
+    ("Motor", "Motor"),
+    ("Gearbox efficiency", "Getriebewirkungsgrad"),
+    ("Select a motor, they can be edited in Tools → Datasets.", "Einen Motor wählen, sie lassen sich unter Werkzeuge → Datensätze bearbeiten."),
+    ("Reduction", "Untersetzung"),
+    ("Output free speed", "Leerlaufdrehzahl am Abtrieb"),
+    ("Output stall torque", "Blockiermoment am Abtrieb"),
+    ("Output speed constant", "Drehzahlkonstante am Abtrieb"),
+    ("Load torque", "Lastmoment"),
+    ("The load stalls the motor.", "Die Last bringt den Motor zum Stehen."),
+    // approximation
+    ("up to", "bis zu"),
+    ("Approximate", "Annähern"),
+    ("Four stages with a wide tooth range take a while.", "Vier Stufen mit großem Zähnebereich dauern eine Weile."),
+    ("Nothing fits into the tooth range.", "Nichts passt in den Zähnebereich."),
+    // reports
+    ("Include the train search results", "Ergebnisse der Getriebesuche aufnehmen"),
+    ("run a train search first", "zuerst eine Getriebesuche starten"),
+    ("Save", "Speichern"),
+    ("Copied to clipboard.", "In die Zwischenablage kopiert."),
+    ("Save PDF", "PDF speichern"),
+    ("built without the \"pdf\" feature", "ohne das Feature \"pdf\" gebaut"),
+    ("Save HTML", "HTML speichern"),
+    ("Copy HTML", "HTML kopieren"),
+    ("Attachments:", "Anhänge:"),
+    // telescopes and re-ratioing
+    ("Motor speed", "Motordrehzahl"),
+    ("Worm wheels", "Schneckenräder"),
+    ("Worm starts", "Gänge der Schnecke"),
+    ("Stages before the worm", "Stufen vor der Schnecke"),
+    ("Total reduction", "Gesamtuntersetzung"),
+    ("one turn per sidereal day", "eine Umdrehung je Sterntag"),
+    ("Search drives", "Antriebe suchen"),
+    ("Worm wheel", "Schneckenrad"),
+    ("Gear train", "Rädergetriebe"),
+    ("Total", "Gesamt"),
+    ("Tracking error", "Nachführfehler"),
+    ("Original setup", "Ursprünglicher Aufbau"),
+    ("Wheel diameter", "Raddurchmesser"),
+    ("What changed", "Was sich geändert hat"),
+    ("New wheel diameter", "Neuer Raddurchmesser"),
+    ("Gear to replace", "Zu ersetzendes Rad"),
+    ("Available driver", "Vorhandenes treibendes Rad"),
+    ("Available driven", "Vorhandenes getriebenes Rad"),
+    ("Replacement", "Ersatz"),
+    ("Exact", "Exakt"),
+    ("output speed", "Abtriebsdrehzahl"),
+    // lathes and motion works
+    ("Cutters", "Fräser"),
+    ("e.g. 8, 10, 12, 30, 32, 36 (empty: all)", "z. B. 8, 10, 12, 30, 32, 36 (leer: alle)"),
+    ("Cutters have to be whole tooth counts, searching with the last valid list.", "Fräser müssen ganze Zähnezahlen sein, gesucht wird mit der letzten gültigen Liste."),
+    ("Same module for both stages (equal tooth sums)", "Gleiches Modul für beide Stufen (gleiche Zähnesummen)"),
+    ("Exact solutions:", "Exakte Lösungen:"),
+    ("Cannon pinion", "Viertelrohr"),
+    ("Minute wheel", "Wechselrad"),
+    ("Hour pinion", "Wechseltrieb"),
+    ("Hour wheel", "Stundenrad"),
+    ("metric", "metrisch"),
+    ("Leadscrew", "Leitspindel"),
+    ("Thread", "Gewinde"),
+    ("Results", "Ergebnisse"),
+    ("Compound trains (two stages)", "Zweistufige Räderzüge"),
+    ("Spindle to leadscrew reduction:", "Untersetzung Spindel zu Leitspindel:"),
+    ("Search change gears", "Wechselräder suchen"),
+    ("Gears come from the gear inventory in Tools → Datasets.", "Die Räder kommen aus dem Räderbestand unter Werkzeuge → Datensätze."),
+    ("Gears (driven/driver)", "Räder (getrieben/treibend)"),
+    ("Pitch error", "Steigungsfehler"),
+    // multi-speed gearboxes
+    ("First speed ratio", "Übersetzung erster Gang"),
+    ("Last speed ratio", "Übersetzung letzter Gang"),
+    ("Speeds", "Gänge"),
+    ("Progression ψ", "Progression ψ"),
+    ("1 is a geometric series, above 1 the steps get smaller towards the last speed", "1 ist eine geometrische Reihe, über 1 werden die Sprünge zum letzten Gang hin kleiner"),
+    ("Smallest gear", "Kleinstes Rad"),
+    ("Largest tooth sum", "Größte Zähnesumme"),
+    ("Wanted ratios:", "Gewünschte Übersetzungen:"),
+    ("step", "Sprung"),
+    ("Wanted", "Gewünscht"),
+    ("Find tooth pairs", "Zahnradpaare finden"),
+    ("Tooth sum", "Zähnesumme"),
+    ("worst error", "größter Fehler"),
+    // differentials and cycloidal drives
+    ("Pinion teeth", "Zähne Ritzel"),
+    ("Crown gear teeth", "Zähne Tellerrad"),
+    ("Track width", "Spurweite"),
+    ("the crown gear turns at", "das Tellerrad dreht mit"),
+    ("Left output", "Linker Abtrieb"),
+    ("Straight ahead", "Geradeaus"),
+    ("Speed", "Drehzahl"),
+    ("Torque", "Drehmoment"),
+    ("Left", "Links"),
+    ("Right", "Rechts"),
+    ("Driving straight.", "Geradeausfahrt."),
+    ("Turning left with a radius of", "Linkskurve mit einem Radius von"),
+    ("Turning right with a radius of", "Rechtskurve mit einem Radius von"),
+    ("Disc lobes", "Kurvenscheibe Höcker"),
+    ("Ring pins", "Bolzen im Ring"),
+    ("Output", "Abtrieb"),
+    ("Disc, pins fixed", "Scheibe, Bolzen fest"),
+    ("Pins, disc held", "Bolzen, Scheibe festgehalten"),
+    ("Reduction:", "Untersetzung:"),
+    ("More than one pin difference needs a disc with that many teeth per lobe gap.", "Mehr als ein Bolzen Unterschied braucht eine Scheibe mit so vielen Zähnen je Höckerlücke."),
+    ("The ring needs more pins than the disc has lobes.", "Der Ring braucht mehr Bolzen als die Scheibe Höcker hat."),
+    ("Target reduction", "Zieluntersetzung"),
+    ("Lobes", "Höcker"),
+    ("Pins", "Bolzen"),
+    // comparing
+    ("Pin current pair", "Aktuelles Paar anheften"),
+    ("Reference speed", "Bezugsdrehzahl"),
+    ("Pin a pair to compare it with a variant.", "Ein Paar anheften, um es mit einer Variante zu vergleichen."),
+    ("Pinned", "Angeheftet"),
+    ("Variant", "Variante"),
+    ("Delta", "Differenz"),
+    // rc cars and conversions
+    ("Internal ratio", "Getriebeübersetzung"),
+    ("Tire diameter", "Reifendurchmesser"),
+    ("Battery", "Akku"),
+    ("Final drive ratio", "Endübersetzung"),
+    ("Top speed", "Höchstgeschwindigkeit"),
+    ("Pinion is the input gear, spur the output gear.", "Das Ritzel ist das Antriebsrad, das Hauptzahnrad das Abtriebsrad."),
+    ("No valid ratio.", "Keine gültige Übersetzung."),
+    ("Progression step:", "Stufensprung:"),
+    ("Inverse", "Kehrwert"),
+    ("Change vs. 1:1", "Änderung gegenüber 1:1"),
+    ("of the input", "des Antriebs"),
+    ("Decibels", "Dezibel"),
+    ("Steps of", "Stufen zu"),
+    ("Add stage", "Stufe hinzufügen"),
+    // sweeps
+    ("Input teeth", "Zähne Antrieb"),
+    ("in steps of", "in Schritten von"),
+    ("is kept fixed.", "bleibt fest."),
+    ("Input", "Antrieb"),
+    ("Output rpm", "Abtrieb U/min"),
+    ("Export CSV", "CSV exportieren"),
+    ("Rows saved:", "Zeilen gespeichert:"),
+    ("Copy CSV", "CSV kopieren"),
+    // matching trains and CVTs
+    ("Train A", "Getriebe A"),
+    ("Train B", "Getriebe B"),
+    ("Mismatch:", "Abweichung:"),
+    ("Tolerance:", "Toleranz:"),
+    ("The trains already match.", "Die Getriebe stimmen schon überein."),
+    ("No single gear swap matches the trains within the tolerance.", "Kein einzelner Radtausch gleicht die Getriebe innerhalb der Toleranz an."),
+    ("Swap", "Tausch"),
+    ("Remaining error", "Restfehler"),
+    ("stage", "Stufe"),
+    ("Apply", "Anwenden"),
+    ("Lowest ratio", "Kleinste Übersetzung"),
+    ("Highest ratio", "Größte Übersetzung"),
+    ("Spread:", "Spreizung:"),
+    ("Output torque", "Abtriebsmoment"),
+    ("Lowest", "Kleinste"),
+    ("Highest", "Größte"),
+    ("Set to", "Einstellen auf"),
+    ("and", "und"),
+    ("at the output", "am Abtrieb"),
+    // classroom
+    ("Student:", "Schüler:"),
+    ("Target ratio:", "Zielverhältnis:"),
+    ("Time:", "Zeit:"),
+    ("Recomputations:", "Neuberechnungen:"),
+    ("Best:", "Beste:"),
+    ("off", "daneben"),
+    ("Student", "Schüler"),
+    ("Start exercise", "Übung starten"),
+    ("Finish exercise", "Übung beenden"),
+    ("Closest:", "Am nächsten:"),
+    ("Copy summary", "Zusammenfassung kopieren"),
+    // steppers
+    ("solved", "berechnet"),
+    ("Belt pulley", "Riemenscheibe"),
+    ("Motor steps/rev", "Motorschritte/U"),
+    ("Microstepping", "Mikroschritte"),
+    ("Pulley teeth", "Zähne Riemenscheibe"),
+    ("Belt pitch", "Riementeilung"),
+    ("Lead", "Steigung"),
+    ("Steps/mm", "Schritte/mm"),
+    ("Use gear pair ratio", "Übersetzung des Radpaars übernehmen"),
+    ("travel per output revolution", "Weg je Abtriebsumdrehung"),
+    ("per microstep", "je Mikroschritt"),
+    ("Motor steps/rev isn't a whole number.", "Motorschritte/U sind keine ganze Zahl."),
+    ("Microstepping isn't a whole number.", "Mikroschritte sind keine ganze Zahl."),
+    ("Pulley teeth aren't a whole number.", "Die Zähne der Riemenscheibe sind keine ganze Zahl."),
+    // history and pulleys
+    ("Keep after restart", "Nach Neustart behalten"),
+    ("Clear", "Leeren"),
+    ("Gear pairs you stay with for a moment show up here.", "Radpaare, bei denen Sie kurz verweilen, erscheinen hier."),
+    ("ratio", "Übersetzung"),
+    ("given ratio", "vorgegebene Übersetzung"),
+    ("center distance", "Achsabstand"),
+    ("in", "Antrieb"),
+    ("out", "Abtrieb"),
+    ("Driver pulley diameter", "Durchmesser treibende Scheibe"),
+    ("Driven pulley diameter", "Durchmesser getriebene Scheibe"),
+    ("Pulley ratio", "Übersetzung der Scheiben"),
+    ("Driver Pulley", "Treibende Scheibe"),
+    ("Driven Pulley", "Getriebene Scheibe"),
+    ("Diameters in mm.", "Durchmesser in mm."),
+    // datasets
+    ("First harmonic", "Erste Oberschwingung"),
+    ("Play", "Abspielen"),
+    ("outside of the audible range", "außerhalb des Hörbereichs"),
+    ("built without the \"audio\" feature", "ohne das Feature \"audio\" gebaut"),
+    ("Best error for the given ratio", "Bester Fehler für die vorgegebene Übersetzung"),
+    ("may not be empty", "darf nicht leer sein"),
+    ("must be a whole number from", "muss eine ganze Zahl sein von"),
+    ("must be a number from", "muss eine Zahl sein von"),
+    ("duplicate", "doppelt:"),
+    ("Delete row", "Zeile löschen"),
+    ("Add row", "Zeile hinzufügen"),
+    ("Invalid cells, those rows are ignored:", "Ungültige Zellen, diese Zeilen werden ignoriert:"),
+    ("Gear inventory", "Räderbestand"),
+    ("Motors", "Motoren"),
+    ("Gearbox catalog", "Getriebekatalog"),
+    ("Name", "Name"),
+    ("Count", "Anzahl"),
+    ("Free speed (rpm)", "Leerlaufdrehzahl (U/min)"),
+    ("Stall torque (Nm)", "Blockiermoment (Nm)"),
+    ("kV (rpm/V)", "kV (U/min/V)"),
+    ("Series", "Baureihe"),
+    ("Efficiency (%)", "Wirkungsgrad (%)"),
+    // tires, settings and quick entry
+    ("mm around", "mm Umfang"),
+    ("Expected a size like 205/55R16.", "Erwartet eine Größe wie 205/55R16."),
+    ("Expected a size like 26x2.1.", "Erwartet eine Größe wie 26x2.1."),
+    ("Expected a size like 25-622.", "Erwartet eine Größe wie 25-622."),
+    ("Enter a size like 205/55R16, 26x2.1, 25-622 or a circumference in mm.", "Größe wie 205/55R16, 26x2.1, 25-622 oder Umfang in mm eingeben."),
+    ("Actual ratio is", "Die tatsächliche Übersetzung weicht um"),
+    ("off the given ratio.", "von der vorgegebenen ab."),
+    ("px per step", "px pro Schritt"),
+    ("New", "Neu"),
+    ("Can't lock", "Nicht fixierbar:"),
+    ("Expected a number after \"ratio\".", "Nach \"ratio\" wird eine Zahl erwartet."),
+    ("Expected a number after \":\".", "Nach \":\" wird eine Zahl erwartet."),
+    ("Tooth counts must be whole numbers.", "Zähnezahlen müssen ganze Zahlen sein."),
+    ("Expected a tooth count after \"to\".", "Nach \"to\" wird eine Zähnezahl erwartet."),
+    ("Which gear has", "Welches Rad hat"),
+    ("teeth? Say pinion or wheel.", "Zähne? pinion oder wheel angeben."),
+    ("Don't know what to do with", "Unklar, was"),
+    ("after", "nach"),
+    ("Don't understand", "Nicht verstanden:"),
+    ("Type e.g. \"13 to 40\" or \"3:1 with 15 tooth pinion\".", "Z. B. \"13 to 40\" oder \"3:1 with 15 tooth pinion\" eingeben."),
+    // app
+    ("13 to 40, 3:1 with 15 tooth pinion, ratio 2.6 lock left", "13 to 40, 3:1 with 15 tooth pinion, ratio 2.6 lock left"),
+    ("The teeth need a center distance of", "Die Zähne brauchen einen Achsabstand von"),
+    ("Trains of two pairs within", "Getriebe aus zwei Paaren innerhalb von"),
+    ("of the given ratio.", "der vorgegebenen Übersetzung."),
+    ("only available in the browser", "nur im Browser verfügbar"),
+];
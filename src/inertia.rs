@@ -0,0 +1,164 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * Mass and rotational inertia of the gears. Every gear is taken as a solid disc of its pitch
+ * diameter and the face width, a ring gear as a tube from the pitch circle to a rim four
+ * modules further out. Printed gears with less than full infill weigh less than this.
+ *
+ * Seen from the input shaft, an inertia that turns i times slower counts 1 / i², so the
+ * inertia of the train at the input is the sum of every gear's inertia over the square of
+ * its ratio to the input.
+ *
+ * Servo drives want the load inertia at the motor close to the motor's own, somewhere up
+ * to 10:1 depending on how stiff the coupling is. The load turns at the output, so the
+ * reduction that reaches a target ratio k is i = √(J_load / (k · J_motor)).
+ */
+
+pub struct Body {
+    // kg
+    pub mass: f32,
+    // kg m²
+    pub inertia: f32,
+}
+
+impl Body {
+    // a tube, or a disc with an inner diameter of 0. densities in g/cm³, lengths in mm.
+    pub fn tube(density: f32, inner: f32, outer: f32, width: f32) -> Body {
+        let (ri, ro) = (inner / 2.0, outer / 2.0);
+        // mm³ times g/cm³ is mg, 1e-6 kg
+        let mass = std::f32::consts::PI * (ro * ro - ri * ri) * width * density * 1e-6;
+        // kg mm² to kg m²
+        let inertia = mass * (ro * ro + ri * ri) / 2.0 * 1e-6;
+        Body { mass, inertia }
+    }
+}
+
+pub struct Matching {
+    // kg cm², like the data sheets
+    load: f32,
+    motor: f32,
+    // load over motor inertia
+    target: f32,
+}
+
+impl Matching {
+    pub fn new() -> Matching {
+        Matching { load: 10.0, motor: 1.0, target: 3.0 }
+    }
+
+    // the reduction that reaches the target, with the gears' own inertia at the input
+    fn suggested(&self, gears: f32) -> Option<f32> {
+        let room = self.target * self.motor - gears;
+        (room > 0.0 && self.load > 0.0).then(|| (self.load / room).sqrt())
+    }
+
+    // gears is the inertia of the gears at the input, in kg m². returns a ratio to use.
+    pub fn ui(&mut self, ui: &mut egui::Ui, ratio: f32, gears: f32) -> Option<f32> {
+        let gears = gears * 1e4;
+        egui::Grid::new("inertia_matching").show(ui, |ui| {
+            ui.label(tr("Load inertia"));
+            ui.add(egui::DragValue::new(&mut self.load).speed(0.1).clamp_range(0.0..=1e6).suffix(" kg cm²"));
+            ui.end_row();
+            ui.label(tr("Motor inertia"));
+            ui.add(egui::DragValue::new(&mut self.motor).speed(0.01).clamp_range(0.0001..=1e5).suffix(" kg cm²"));
+            ui.end_row();
+            ui.label(tr("Target ratio"));
+            ui.add(egui::DragValue::new(&mut self.target).speed(0.05).clamp_range(0.1..=20.0).suffix(" : 1"));
+            ui.end_row();
+        });
+
+        let at_motor = reflected(self.load, ratio) + gears;
+        let matching = at_motor / self.motor;
+        ui.label(format!("{} {:.4} kg cm²", tr("Load at the motor:"), at_motor));
+        let text = format!("{} {:.2} : 1", tr("Load to motor inertia:"), matching);
+        if matching > 10.0 {
+            ui.colored_label(ui.visuals().warn_fg_color, text);
+        } else {
+            ui.label(text);
+        }
+
+        let mut apply = None;
+        match self.suggested(gears) {
+            Some(i) => {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} {:.3}", tr("Reduction for the target:"), i));
+                    if ui.button(tr("Use")).clicked() {
+                        apply = Some(i);
+                    }
+                });
+            }
+            None => {
+                ui.label(tr("The gears alone exceed the target."));
+            }
+        }
+        apply
+    }
+}
+
+// an inertia turning `ratio` times slower than the input, as seen from the input
+pub fn reflected(inertia: f32, ratio: f32) -> f32 {
+    inertia / (ratio * ratio)
+}
+
+// kg m² as kg cm², the unit servo data sheets use
+pub fn kg_cm2(inertia: f32) -> String {
+    format!("{:.4} kg cm²", inertia * 1e4)
+}
+
+// a table of the gears and their ratio to the input, with the sum at the input shaft.
+// returns the sum in kg m².
+pub fn train_ui(ui: &mut egui::Ui, gears: &[(String, Body, f32)]) -> f32 {
+    egui::Grid::new("inertia").striped(true).show(ui, |ui| {
+        for h in ["", "Mass", "Inertia", "At the input"] {
+            ui.label(egui::RichText::new(tr(h)).strong());
+        }
+        ui.end_row();
+        for (name, body, ratio) in gears {
+            ui.label(name.as_str());
+            ui.label(format!("{:.1} g", body.mass * 1000.0));
+            ui.label(kg_cm2(body.inertia));
+            ui.label(kg_cm2(reflected(body.inertia, *ratio)));
+            ui.end_row();
+        }
+    });
+    let total: f32 = gears.iter().map(|(_, b, r)| reflected(b.inertia, *r)).sum();
+    ui.label(egui::RichText::new(format!("{} {}", tr("Total at the input shaft:"), kg_cm2(total))).strong());
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steel_disc() {
+        // 100 mm across and 10 mm wide, 78.5 cm³ of steel
+        let disc = Body::tube(7.85, 0.0, 100.0, 10.0);
+        assert!((disc.mass - 0.6165).abs() < 1e-4, "{}", disc.mass);
+        assert!((disc.inertia - disc.mass * 0.05 * 0.05 / 2.0).abs() < 1e-9);
+        // a tube has its mass further out
+        let tube = Body::tube(7.85, 80.0, 100.0, 10.0);
+        assert!(tube.mass < disc.mass && tube.inertia / tube.mass > disc.inertia / disc.mass);
+    }
+
+    #[test]
+    fn reflected_inertia() {
+        assert_eq!(reflected(10.0, 2.0), 2.5);
+        assert_eq!(reflected(10.0, 1.0), 10.0);
+    }
+
+    #[test]
+    fn suggested_reduction() {
+        let m = Matching::new();
+        // the load at the motor plus the gears lands on the target
+        for gears in [0.0, 1.5] {
+            let i = m.suggested(gears).unwrap();
+            assert!((reflected(m.load, i) + gears - m.target * m.motor).abs() < 1e-4);
+        }
+        // the gears alone are already over the target
+        assert_eq!(m.suggested(3.0), None);
+        assert_eq!(m.suggested(4.0), None);
+    }
+}
@@ -0,0 +1,201 @@
+use eframe::egui;
+
+use crate::band::Band;
+use crate::i18n::tr;
+use crate::dataset::Dataset;
+use crate::search::describe;
+use crate::train::{Stage, Train};
+use crate::worker::{self, Job, Progress};
+
+/*
+ * Change gears for thread cutting on a lathe. Per spindle revolution the carriage has to
+ * move one thread pitch, so the leadscrew turns thread pitch / leadscrew pitch times and
+ * the reduction from spindle to leadscrew is leadscrew pitch / thread pitch.
+ *
+ * Only the gears in the inventory can be used, as often as they are there. Metric threads
+ * on an imperial leadscrew (and the other way round) need the factor 127 (= 5 * 25.4), so
+ * they only work out with a 127 tooth gear in the set.
+ */
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Pitch {
+    Metric,
+    Tpi,
+}
+
+impl Pitch {
+    // pitch in mm
+    fn mm(self, value: f64) -> f64 {
+        match self {
+            Pitch::Metric => value,
+            Pitch::Tpi => 25.4 / value,
+        }
+    }
+}
+
+fn pitch_ui(ui: &mut egui::Ui, kind: &mut Pitch, value: &mut f64) {
+    ui.horizontal(|ui| {
+        let suffix = if *kind == Pitch::Metric { " mm" } else { " TPI" };
+        ui.add(egui::DragValue::new(value).speed(0.01).clamp_range(0.01..=200.0).max_decimals(4).suffix(suffix));
+        ui.radio_value(kind, Pitch::Metric, tr("metric"));
+        ui.radio_value(kind, Pitch::Tpi, "TPI");
+    });
+}
+
+// what a search needs from the lathe, so it can run on the worker
+struct Query {
+    // (teeth, count) of the gears in the inventory
+    gears: Vec<(u32, u32)>,
+    target: f64,
+    compound: bool,
+    top_n: usize,
+}
+
+struct Setup {
+    train: Train,
+    ratio: f64,
+    // relative error of the cut pitch in percent
+    error: f64,
+}
+
+// searches all single pairs and, if enabled, compound trains A/B × C/D
+fn search(query: &Query, progress: &Progress) -> Option<Vec<Setup>> {
+    let gears = &query.gears;
+    let available = |used: &[u32]| {
+        gears.iter().all(|&(t, n)| used.iter().filter(|&&u| u == t).count() as u32 <= n)
+    };
+
+    let target = query.target;
+    let mut results = Vec::new();
+    let mut add = |stages: &[Stage]| {
+        let train = Train::new(stages);
+        let ratio = stages.iter().map(|s| s.driven as f64 / s.driver as f64).product::<f64>();
+        results.push(Setup { train, ratio, error: (target / ratio - 1.0) * 100.0 });
+    };
+    for (i, &(a, _)) in gears.iter().enumerate() {
+        if !progress.report(i as f32 / gears.len() as f32) {
+            return None;
+        }
+        for &(b, _) in gears {
+            if available(&[a, b]) {
+                add(&[Stage::new(a, b)]);
+            }
+            if !query.compound {
+                continue;
+            }
+            for &(c, _) in gears {
+                for &(d, _) in gears {
+                    if available(&[a, b, c, d]) {
+                        add(&[Stage::new(a, b), Stage::new(c, d)]);
+                    }
+                }
+            }
+        }
+    }
+    // fewer gears win ties, they are quicker to set up
+    results.sort_by(|x, y| {
+        x.error.abs().partial_cmp(&y.error.abs()).unwrap()
+            .then(x.train.stages.len().cmp(&y.train.stages.len()))
+    });
+    results.dedup_by(|x, y| x.train == y.train);
+    results.truncate(query.top_n);
+    Some(results)
+}
+
+pub struct Lathe {
+    leadscrew: f64,
+    leadscrew_kind: Pitch,
+    thread: f64,
+    thread_kind: Pitch,
+    compound: bool,
+    top_n: usize,
+    results: Vec<Setup>,
+    job: Option<Job<Vec<Setup>>>,
+}
+
+impl Lathe {
+    pub fn new() -> Lathe {
+        Lathe {
+            leadscrew: 8.0,
+            leadscrew_kind: Pitch::Tpi,
+            thread: 1.5,
+            thread_kind: Pitch::Metric,
+            compound: true,
+            top_n: 10,
+            results: Vec::new(),
+            job: None,
+        }
+    }
+
+    // spindle to leadscrew reduction
+    fn target(&self) -> f64 {
+        self.leadscrew_kind.mm(self.leadscrew) / self.thread_kind.mm(self.thread)
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.target() as f32
+    }
+
+    fn query(&self, inventory: &Dataset) -> Query {
+        let mut gears: Vec<(u32, u32)> = Vec::new();
+        for r in inventory.valid_rows() {
+            let teeth = inventory.real(r, "Teeth") as u32;
+            let count = inventory.real(r, "Count") as u32;
+            match gears.iter_mut().find(|g| g.0 == teeth) {
+                Some(g) => g.1 += count,
+                None => gears.push((teeth, count)),
+            }
+        }
+        Query { gears, target: self.target(), compound: self.compound, top_n: self.top_n }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, inventory: &Dataset, band: &Band) {
+        egui::Grid::new("lathe_inputs").show(ui, |ui| {
+            ui.label(tr("Leadscrew"));
+            pitch_ui(ui, &mut self.leadscrew_kind, &mut self.leadscrew);
+            ui.end_row();
+            ui.label(tr("Thread"));
+            pitch_ui(ui, &mut self.thread_kind, &mut self.thread);
+            ui.end_row();
+            ui.label(tr("Results"));
+            ui.add(egui::DragValue::new(&mut self.top_n).clamp_range(1..=50));
+            ui.end_row();
+        });
+        ui.checkbox(&mut self.compound, tr("Compound trains (two stages)"));
+        ui.label(format!("{} {:.6}", tr("Spindle to leadscrew reduction:"), self.target()));
+        if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Search change gears"))).clicked() {
+            let query = self.query(inventory);
+            self.job = Some(Job::spawn(ui.ctx(), move |p| search(&query, p)));
+        }
+        if let Some(r) = worker::job_ui(ui, &mut self.job) {
+            self.results = r;
+        }
+        ui.label(tr("Gears come from the gear inventory in Tools → Datasets."));
+
+        if self.results.is_empty() {
+            return;
+        }
+        ui.separator();
+        if !self.results.iter().any(|s| band.shows(s.error / 100.0)) {
+            band.empty_note(ui);
+            return;
+        }
+        egui::Grid::new("lathe_results").striped(true).show(ui, |ui| {
+            for h in ["Gears (driven/driver)", "Reduction", "Pitch error"] {
+                ui.label(egui::RichText::new(tr(h)).strong());
+            }
+            ui.end_row();
+            for s in self.results.iter().filter(|s| band.shows(s.error / 100.0)) {
+                let mut text = describe(&s.train);
+                if s.train.stages.iter().any(|g| g.driver == 127 || g.driven == 127) {
+                    text += "  (127T)";
+                }
+                ui.label(text);
+                ui.label(format!("{:.6}", s.ratio));
+                ui.label(format!("{:+.4} %", s.error));
+                band.mark(ui, s.error / 100.0);
+                ui.end_row();
+            }
+        });
+    }
+}
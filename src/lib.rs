@@ -1,4 +1,62 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+mod accuracy;
 mod app;
+mod approx;
+mod audio;
+mod band;
+mod belt;
+mod bicycle;
+mod classroom;
+mod clock;
+mod compare;
+#[cfg(not(target_arch = "wasm32"))]
+mod config;
+mod constraint;
+mod conversions;
+mod cvt;
+mod cycloidal;
+mod dataset;
+mod differential;
+mod favorites;
+mod feedback;
+mod gear_train;
+mod gearbox;
+mod geometry;
+mod history;
+mod i18n;
+mod inertia;
+mod lathe;
+mod matching;
+mod mesh_tone;
+mod motion_works;
+mod motor;
+mod planetary;
+mod plot;
+mod presets;
+mod project;
+mod pulley;
+mod quick_entry;
+mod ratio_model;
+mod rc;
+mod report;
+mod reratio;
+mod schematic;
+mod search;
+mod settings;
+mod sidereal;
+mod spinner;
+mod stepper;
+mod strength;
+mod sweep;
+mod tire;
+mod train;
+mod url_state;
+mod vehicle;
+#[cfg(target_arch = "wasm32")]
+mod web;
+mod worker;
 pub use app::RitzelApp;
+pub use spinner::{NumberSpinner, Sensitivity, SpinnerValue};
+#[cfg(target_arch = "wasm32")]
+pub use web::WebHandle;
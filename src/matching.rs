@@ -0,0 +1,147 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+use crate::train::{self, Stage, Train};
+
+/*
+ * Two trains that should run at the same speed (e.g. the left and right side of a tracked
+ * robot) but don't. The solver looks for the smallest single gear swap in either train
+ * that brings both ratios together within a tolerance.
+ */
+
+// replacing one gear of one train
+#[derive(Debug, Clone, Copy)]
+struct Swap {
+    train: usize,
+    stage: usize,
+    driven: bool,
+    from: u32,
+    to: u32,
+    // relative mismatch of the two trains after the swap
+    error: f32,
+}
+
+pub struct TrainMatch {
+    trains: [Train; 2],
+    // acceptable relative mismatch in percent
+    tolerance: f32,
+}
+
+// relative difference of two ratios
+fn mismatch(a: f32, b: f32) -> f32 {
+    (a - b).abs() / b
+}
+
+impl TrainMatch {
+    pub fn new() -> TrainMatch {
+        TrainMatch {
+            trains: [
+                Train::new(&[Stage::new(12, 36), Stage::new(15, 45)]),
+                Train::new(&[Stage::new(12, 36), Stage::new(14, 45)]),
+            ],
+            tolerance: 0.5,
+        }
+    }
+
+    // the swaps that equalize the trains within the tolerance, smallest change first
+    fn solve(&self) -> Vec<Swap> {
+        let mut swaps = Vec::new();
+        for t in 0..2 {
+            let own = self.trains[t].ratio();
+            let other = self.trains[1 - t].ratio();
+            for (si, s) in self.trains[t].stages.iter().enumerate() {
+                // the driven gear scales the ratio, the driver inverts it
+                for driven in [false, true] {
+                    let from = if driven { s.driven } else { s.driver };
+                    let exact = if driven {
+                        from as f32 * other / own
+                    } else {
+                        from as f32 * own / other
+                    };
+                    let to = exact.round().max(1.0) as u32;
+                    if to == from {
+                        continue;
+                    }
+                    let mut changed = self.trains[t].clone();
+                    if driven {
+                        changed.stages[si].driven = to;
+                    } else {
+                        changed.stages[si].driver = to;
+                    }
+                    let error = mismatch(changed.ratio(), other);
+                    if error * 100.0 <= self.tolerance {
+                        swaps.push(Swap { train: t, stage: si, driven, from, to, error });
+                    }
+                }
+            }
+        }
+        swaps.sort_by(|a, b| {
+            a.from.abs_diff(a.to).cmp(&b.from.abs_diff(b.to))
+                .then(a.error.total_cmp(&b.error))
+        });
+        swaps
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.trains[0].ratio()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_top(|ui| {
+            for (i, name) in ["Train A", "Train B"].iter().enumerate() {
+                ui.vertical(|ui| {
+                    ui.label(egui::RichText::new(tr(name)).strong());
+                    train::train_editor(ui, &mut self.trains[i], name);
+                });
+            }
+        });
+        ui.separator();
+
+        let ra = self.trains[0].ratio();
+        let rb = self.trains[1].ratio();
+        ui.label(format!("{} {:.3} %", tr("Mismatch:"), mismatch(ra, rb) * 100.0));
+        ui.horizontal(|ui| {
+            ui.label(tr("Tolerance:"));
+            ui.add(egui::DragValue::new(&mut self.tolerance).speed(0.01).clamp_range(0.0..=10.0).suffix(" %"));
+        });
+
+        if mismatch(ra, rb) * 100.0 <= self.tolerance {
+            ui.label(tr("The trains already match."));
+            return;
+        }
+        let swaps = self.solve();
+        if swaps.is_empty() {
+            ui.label(tr("No single gear swap matches the trains within the tolerance."));
+            return;
+        }
+
+        let mut apply = None;
+        egui::Grid::new("swaps").striped(true).show(ui, |ui| {
+            ui.label(egui::RichText::new(tr("Swap")).strong());
+            ui.label(egui::RichText::new(tr("Remaining error")).strong());
+            ui.end_row();
+            for (i, s) in swaps.iter().enumerate() {
+                ui.label(format!("{}, {} {}, {}: {} T → {} T",
+                    if s.train == 0 { "A" } else { "B" },
+                    tr("stage"),
+                    s.stage + 1,
+                    tr(if s.driven { "driven" } else { "driver" }),
+                    s.from, s.to));
+                ui.label(format!("{:.3} %", s.error * 100.0));
+                if ui.small_button(tr("Apply")).clicked() {
+                    apply = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = apply {
+            let s = swaps[i];
+            let stage = &mut self.trains[s.train].stages[s.stage];
+            if s.driven {
+                stage.driven = s.to;
+            } else {
+                stage.driver = s.to;
+            }
+        }
+    }
+}
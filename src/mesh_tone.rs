@@ -0,0 +1,50 @@
+use eframe::egui;
+
+use crate::audio::Audio;
+use crate::i18n::tr;
+
+/*
+ * Gear whine sits at the mesh frequency, the rate at which teeth engage:
+ * teeth × rpm / 60, the same for both gears of a pair. Playing it together with its first
+ * harmonic gives a rough idea where it sits relative to the motor noise.
+ */
+
+pub struct MeshTone {
+    // speed of the input gear
+    rpm: f32,
+    harmonic: bool,
+}
+
+impl MeshTone {
+    pub fn new() -> MeshTone {
+        MeshTone {
+            rpm: 3000.0,
+            harmonic: true,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, input_teeth: u32, audio: &mut Audio) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Input speed"));
+            ui.add(egui::DragValue::new(&mut self.rpm).speed(10.0).clamp_range(1.0..=100000.0).suffix(" rpm"));
+        });
+        let freq = input_teeth as f32 * self.rpm / 60.0;
+        ui.label(format!("{}: {:.1} Hz", tr("Mesh frequency"), freq));
+        ui.checkbox(&mut self.harmonic, format!("{} ({:.1} Hz)", tr("First harmonic"), 2.0 * freq));
+
+        let audible = (20.0..=20000.0).contains(&freq);
+        let play = ui.add_enabled(Audio::available() && audible, egui::Button::new(tr("Play")))
+            .on_disabled_hover_text(tr(if Audio::available() {
+                "outside of the audible range"
+            } else {
+                "built without the \"audio\" feature"
+            }));
+        if play.clicked() {
+            let mut waves = vec![(freq, 0.2)];
+            if self.harmonic {
+                waves.push((2.0 * freq, 0.1));
+            }
+            audio.tone(&waves, 1500);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use eframe::egui;
+
+use crate::bicycle::parse_teeth;
+use crate::i18n::tr;
+use crate::worker::{self, Job, Progress};
+
+/*
+ * The motion works of a clock turn the minute hand into the hour hand, 12:1 in two stages:
+ * the cannon pinion drives the minute wheel, the pinion on the minute wheel drives the hour
+ * wheel. Cannon pinion and hour wheel sit on the same axis, so with the same module both
+ * stages need the same tooth sum.
+ *
+ * Only exact solutions count, a clock can't be a little off per turn.
+ */
+
+#[derive(Debug, Clone, Copy)]
+struct Works {
+    cannon_pinion: u32,
+    minute_wheel: u32,
+    hour_pinion: u32,
+    hour_wheel: u32,
+}
+
+impl Works {
+    fn total_teeth(&self) -> u32 {
+        self.cannon_pinion + self.minute_wheel + self.hour_pinion + self.hour_wheel
+    }
+}
+
+// what a search needs, so it can run on the worker
+struct Query {
+    pinions: (u32, u32),
+    wheels: (u32, u32),
+    // None means all
+    cutters: Option<Vec<u32>>,
+    same_module: bool,
+}
+
+// every exact 12:1 motion works in the ranges, fewest teeth first
+fn search(query: &Query, progress: &Progress) -> Option<Vec<Works>> {
+    let cut = |t: &u32| query.cutters.as_ref().map_or(true, |c| c.contains(t));
+    let pinions: Vec<u32> = (query.pinions.0..=query.pinions.1).filter(cut).collect();
+    let wheels: Vec<u32> = (query.wheels.0..=query.wheels.1).filter(cut).collect();
+
+    let mut results = Vec::new();
+    for (i, &a) in pinions.iter().enumerate() {
+        if !progress.report(i as f32 / pinions.len() as f32) {
+            return None;
+        }
+        for &b in &wheels {
+            for &c in &pinions {
+                // b/a * d/c = 12
+                let num = 12 * a * c;
+                if num % b != 0 {
+                    continue;
+                }
+                let d = num / b;
+                if !wheels.contains(&d) || (query.same_module && a + b != c + d) {
+                    continue;
+                }
+                results.push(Works { cannon_pinion: a, minute_wheel: b, hour_pinion: c, hour_wheel: d });
+            }
+        }
+    }
+    results.sort_by_key(|w| w.total_teeth());
+    Some(results)
+}
+
+pub struct MotionWorks {
+    pinion_min: u32,
+    pinion_max: u32,
+    wheel_min: u32,
+    wheel_max: u32,
+    // tooth counts there are cutters for, None means all
+    cutters: Option<Vec<u32>>,
+    cutters_str: String,
+    same_module: bool,
+    results: Vec<Works>,
+    job: Option<Job<Vec<Works>>>,
+}
+
+impl MotionWorks {
+    pub fn new() -> MotionWorks {
+        MotionWorks {
+            pinion_min: 6,
+            pinion_max: 16,
+            wheel_min: 20,
+            wheel_max: 96,
+            cutters: None,
+            cutters_str: String::new(),
+            same_module: true,
+            results: Vec::new(),
+            job: None,
+        }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        12.0
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        let invalid = !self.cutters_str.trim().is_empty() && parse_teeth(&self.cutters_str).is_none();
+        egui::Grid::new("motion_works_inputs").show(ui, |ui| {
+            ui.label(tr("Pinions"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.pinion_min).clamp_range(4..=self.pinion_max));
+                ui.label(tr("to"));
+                ui.add(egui::DragValue::new(&mut self.pinion_max).clamp_range(self.pinion_min..=40));
+            });
+            ui.end_row();
+            ui.label(tr("Wheels"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.wheel_min).clamp_range(6..=self.wheel_max));
+                ui.label(tr("to"));
+                ui.add(egui::DragValue::new(&mut self.wheel_max).clamp_range(self.wheel_min..=300));
+            });
+            ui.end_row();
+            ui.label(tr("Cutters"));
+            let mut te = egui::TextEdit::singleline(&mut self.cutters_str)
+                .desired_width(240.0)
+                .hint_text(tr("e.g. 8, 10, 12, 30, 32, 36 (empty: all)"));
+            if invalid {
+                te = te.text_color(ui.visuals().error_fg_color);
+            }
+            // keeps the last valid list while typing
+            if ui.add(te).changed() {
+                if self.cutters_str.trim().is_empty() {
+                    self.cutters = None;
+                } else if let Some(c) = parse_teeth(&self.cutters_str) {
+                    self.cutters = Some(c);
+                }
+            }
+            ui.end_row();
+        });
+        if invalid {
+            ui.colored_label(ui.visuals().error_fg_color, tr("Cutters have to be whole tooth counts, searching with the last valid list."));
+        }
+        ui.checkbox(&mut self.same_module, tr("Same module for both stages (equal tooth sums)"));
+        if ui.add_enabled(self.job.is_none(), egui::Button::new(format!("{} 12:1", tr("Search")))).clicked() {
+            let query = Query {
+                pinions: (self.pinion_min, self.pinion_max),
+                wheels: (self.wheel_min, self.wheel_max),
+                cutters: self.cutters.clone(),
+                same_module: self.same_module,
+            };
+            self.job = Some(Job::spawn(ui.ctx(), move |p| search(&query, p)));
+        }
+        if let Some(r) = worker::job_ui(ui, &mut self.job) {
+            self.results = r;
+        }
+
+        ui.separator();
+        ui.label(format!("{} {}", tr("Exact solutions:"), self.results.len()));
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            egui::Grid::new("motion_works_results").striped(true).show(ui, |ui| {
+                for h in ["Cannon pinion", "Minute wheel", "Hour pinion", "Hour wheel", "Total teeth"] {
+                    ui.label(egui::RichText::new(tr(h)).strong());
+                }
+                ui.end_row();
+                for w in &self.results {
+                    ui.label(format!("{}", w.cannon_pinion));
+                    ui.label(format!("{}", w.minute_wheel));
+                    ui.label(format!("{}", w.hour_pinion));
+                    ui.label(format!("{}", w.hour_wheel));
+                    ui.label(format!("{}", w.total_teeth()));
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
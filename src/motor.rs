@@ -0,0 +1,88 @@
+use eframe::egui;
+
+use crate::dataset::Dataset;
+use crate::i18n::tr;
+use crate::plot;
+
+/*
+ * What comes out of the gearbox: a motor from the motor dataset behind the current
+ * reduction. Speed divides by the ratio, torque multiplies with it (minus losses).
+ *
+ * Between stall and free speed a DC motor's speed falls linearly with the torque, so the
+ * output runs along a straight line too, and a load torque on it gives the speed.
+ */
+
+pub struct MotorOutput {
+    // key of the selected motor in the dataset
+    motor: String,
+    // gearbox efficiency in percent
+    efficiency: f32,
+    // at the output, in Nm
+    load: f64,
+}
+
+impl MotorOutput {
+    pub fn new() -> MotorOutput {
+        MotorOutput {
+            motor: String::from("NEO"),
+            efficiency: 90.0,
+            load: 1.0,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, motors: &Dataset, ratio: f32) {
+        egui::ComboBox::new("motor_output_motor", tr("Motor"))
+            .selected_text(&self.motor)
+            .show_ui(ui, |ui| {
+                for r in motors.valid_rows() {
+                    let name = motors.text(r, "Name");
+                    ui.selectable_value(&mut self.motor, name.to_string(), name);
+                }
+            });
+        ui.horizontal(|ui| {
+            ui.label(tr("Gearbox efficiency"));
+            ui.add(egui::DragValue::new(&mut self.efficiency).speed(0.5).clamp_range(1.0..=100.0).suffix(" %"));
+        });
+
+        let row = match motors.find(&self.motor) {
+            Some(r) if motors.row_is_valid(r) => r,
+            _ => {
+                ui.label(tr("Select a motor, they can be edited in Tools → Datasets."));
+                return;
+            }
+        };
+        let ratio = ratio as f64;
+        let free_speed = motors.real(row, "Free speed (rpm)");
+        let stall_torque = motors.real(row, "Stall torque (Nm)");
+        let kv = motors.real(row, "kV (rpm/V)");
+
+        ui.separator();
+        egui::Grid::new("motor_output").striped(true).show(ui, |ui| {
+            ui.label(tr("Reduction"));
+            ui.label(format!("{:.3}", ratio));
+            ui.end_row();
+            ui.label(tr("Output free speed"));
+            ui.label(egui::RichText::new(format!("{:.1} rpm", free_speed / ratio)).strong());
+            ui.end_row();
+            ui.label(tr("Output stall torque"));
+            ui.label(egui::RichText::new(format!("{:.2} Nm", stall_torque * ratio * self.efficiency as f64 / 100.0)).strong());
+            ui.end_row();
+            if kv > 0.0 {
+                ui.label(tr("Output speed constant"));
+                ui.label(format!("{:.2} rpm/V", kv / ratio));
+                ui.end_row();
+            }
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(tr("Load torque"));
+            ui.add(egui::DragValue::new(&mut self.load).speed(0.05).clamp_range(0.0..=1e6).suffix(" Nm"));
+        });
+        let out_stall = stall_torque * ratio * self.efficiency as f64 / 100.0;
+        plot::torque_speed(ui, "motor_torque_speed", free_speed / ratio, out_stall, self.load);
+        if self.load >= out_stall {
+            ui.colored_label(ui.visuals().warn_fg_color, tr("The load stalls the motor."));
+        }
+    }
+}
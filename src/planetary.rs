@@ -0,0 +1,295 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+use crate::train::Direction;
+
+/*
+ * Planetary stages: a sun gear in the middle, planets around it on a carrier, and a ring gear
+ * around the planets. With S teeth on the sun and R on the ring the speeds of the three
+ * members always satisfy (Willis)
+ *     S · ω_sun + R · ω_ring = (S + R) · ω_carrier
+ * Holding one member fixed leaves a plain ratio between the other two:
+ *     ring fixed,    sun → carrier:   1 + R / S
+ *     sun fixed,     ring → carrier:  1 + S / R
+ *     carrier fixed, sun → ring:      −R / S, it reverses like an external pair
+ * and driving the other way round gives the inverse, an overdrive. That makes six ways to
+ * use the same gears, in general input over output is −k_out / k_in with k_sun = S,
+ * k_ring = R and k_carrier = −(S + R).
+ * Stacked stages drive the input of the next stage with the output of the one before, so
+ * their ratios multiply like the stages of a spur gear train.
+ *
+ * Not every set of tooth counts can be built:
+ * - the planets mesh with sun and ring at once, so R = S + 2 P for P teeth on a planet, and
+ *   R − S has to be even
+ * - n planets sit at equal angles only if (S + R) / n is whole, otherwise their teeth don't
+ *   line up with sun and ring at every position
+ * - neighbouring planets must not touch, their centers are (S + P) · sin(π / n) modules
+ *   apart and their tip circles P + 2 modules across. A single planet has no neighbour.
+ * When they don't work out, the counts a few teeth around often do with about the same ratio.
+ */
+
+// how far the search for buildable stages goes from the entered teeth
+const NEARBY_SUN: u32 = 4;
+const NEARBY_RING: u32 = 8;
+const NEARBY_RESULTS: usize = 5;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Member {
+    Sun,
+    Ring,
+    Carrier,
+}
+
+// input and output, the third member is held
+const CONFIGURATIONS: [(Member, Member); 6] = [
+    (Member::Sun, Member::Carrier),
+    (Member::Carrier, Member::Sun),
+    (Member::Ring, Member::Carrier),
+    (Member::Carrier, Member::Ring),
+    (Member::Sun, Member::Ring),
+    (Member::Ring, Member::Sun),
+];
+
+impl Member {
+    fn name(self) -> &'static str {
+        match self {
+            Member::Sun => "Sun",
+            Member::Ring => "Ring",
+            Member::Carrier => "Carrier",
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Stage {
+    pub sun: u32,
+    pub ring: u32,
+    // number of planets on the carrier
+    pub planets: u32,
+    pub input: Member,
+    pub output: Member,
+}
+
+impl Stage {
+    pub fn new(sun: u32, ring: u32) -> Stage {
+        Stage { sun, ring, planets: 3, input: Member::Sun, output: Member::Carrier }
+    }
+
+    // teeth on each planet, so it meshes with sun and ring
+    pub fn planet(&self) -> u32 {
+        self.ring.saturating_sub(self.sun) / 2
+    }
+
+    // why this stage can't be assembled, empty if it can
+    pub fn problems(&self) -> Vec<&'static str> {
+        let mut problems = Vec::new();
+        if self.ring <= self.sun {
+            problems.push("The ring needs more teeth than the sun.");
+        }
+        if (self.ring + self.sun) % 2 != 0 {
+            problems.push("Ring minus sun teeth have to be even for whole planet teeth.");
+        }
+        if (self.sun + self.ring) % self.planets != 0 {
+            problems.push("Sun plus ring teeth have to divide by the number of planets.");
+        }
+        let planet = self.planet();
+        let spacing = (self.sun + planet) as f64 * (std::f64::consts::PI / self.planets as f64).sin();
+        if self.planets >= 2 && spacing <= (planet + 2) as f64 {
+            problems.push("The planets are too big to fit next to each other.");
+        }
+        problems
+    }
+
+    // buildable stages with a few teeth more or less, closest ratio first
+    fn nearby(&self) -> Vec<Stage> {
+        let ratio = self.signed_ratio();
+        let mut found: Vec<Stage> = (self.sun.saturating_sub(NEARBY_SUN).max(6)..=self.sun + NEARBY_SUN)
+            .flat_map(|sun| (self.ring.saturating_sub(NEARBY_RING)..=self.ring + NEARBY_RING).map(move |ring| (sun, ring)))
+            .map(|(sun, ring)| Stage { sun, ring, ..*self })
+            .filter(|s| s.problems().is_empty())
+            .collect();
+        found.sort_by(|a, b| (a.signed_ratio() - ratio).abs().total_cmp(&(b.signed_ratio() - ratio).abs()));
+        found.truncate(NEARBY_RESULTS);
+        found
+    }
+
+    // the member that is neither input nor output
+    pub fn held(&self) -> Member {
+        match (self.input, self.output) {
+            (Member::Sun, Member::Ring) | (Member::Ring, Member::Sun) => Member::Carrier,
+            (Member::Sun, _) | (_, Member::Sun) => Member::Ring,
+            _ => Member::Sun,
+        }
+    }
+
+    // e.g. "Sun → Carrier, Ring held"
+    fn configuration_text(&self) -> String {
+        format!("{} → {}, {} {}", tr(self.input.name()), tr(self.output.name()), tr(self.held().name()), tr("held"))
+    }
+
+    // the factor of the member's speed in the Willis equation, moved to one side
+    fn coefficient(&self, member: Member) -> f64 {
+        match member {
+            Member::Sun => self.sun as f64,
+            Member::Ring => self.ring as f64,
+            Member::Carrier => -((self.sun + self.ring) as f64),
+        }
+    }
+
+    // input over output speed, negative when the output turns the other way
+    pub fn signed_ratio(&self) -> f64 {
+        -self.coefficient(self.output) / self.coefficient(self.input)
+    }
+
+    pub fn direction(&self) -> Direction {
+        if self.signed_ratio() < 0.0 { Direction::Reversed } else { Direction::Same }
+    }
+}
+
+pub struct Planetary {
+    stages: Vec<Stage>,
+}
+
+impl Planetary {
+    pub fn new() -> Planetary {
+        Planetary { stages: vec![Stage::new(12, 48)] }
+    }
+
+    fn signed_ratio(&self) -> f64 {
+        self.stages.iter().map(Stage::signed_ratio).product()
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.signed_ratio().abs() as f32
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        let mut delete = None;
+        let count = self.stages.len();
+        egui::Grid::new("planetary").striped(true).show(ui, |ui| {
+            for h in ["Stage", "Sun", "Ring", "Planet", "Planets", "Configuration", "Ratio", ""] {
+                ui.label(egui::RichText::new(tr(h)).strong());
+            }
+            ui.end_row();
+
+            let mut direction = Direction::Same;
+            for (i, s) in self.stages.iter_mut().enumerate() {
+                ui.label(format!("{}", i + 1));
+                ui.add(egui::DragValue::new(&mut s.sun).clamp_range(6..=1000));
+                ui.add(egui::DragValue::new(&mut s.ring).clamp_range(12..=3000));
+                if (s.ring + s.sun) % 2 == 0 {
+                    ui.label(s.planet().to_string());
+                } else {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("{:.1}", (s.ring as f32 - s.sun as f32) / 2.0));
+                }
+                ui.add(egui::DragValue::new(&mut s.planets).clamp_range(1..=12));
+                egui::ComboBox::from_id_source(("planetary_configuration", i))
+                    .selected_text(s.configuration_text())
+                    .show_ui(ui, |ui| {
+                        for (input, output) in CONFIGURATIONS {
+                            let option = Stage { input, output, ..*s };
+                            let text = format!("{}  ({:.3})", option.configuration_text(), option.signed_ratio());
+                            if ui.selectable_label((s.input, s.output) == (input, output), text).clicked() {
+                                *s = option;
+                            }
+                        }
+                    });
+                ui.label(format!("{:.3}", s.signed_ratio().abs()));
+                // of this stage's output
+                direction = direction.then(s.direction());
+                direction.show(ui);
+                if ui.add_enabled(count > 1, egui::Button::new("🗑").small()).on_hover_text(tr("Delete stage")).clicked() {
+                    delete = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = delete {
+            self.stages.remove(i);
+        }
+        if ui.button(tr("Add planetary stage")).clicked() {
+            let last = self.stages.last().copied().unwrap_or(Stage::new(12, 48));
+            self.stages.push(last);
+        }
+        let mut replace = None;
+        for (i, s) in self.stages.iter().enumerate() {
+            let problems = s.problems();
+            for p in &problems {
+                ui.colored_label(ui.visuals().error_fg_color, format!("{} {}: {}", tr("Stage"), i + 1, tr(p)));
+            }
+            if problems.is_empty() {
+                continue;
+            }
+            egui::CollapsingHeader::new(format!("{} {}", tr("Nearby tooth counts for stage"), i + 1))
+                .id_source(("planetary_nearby", i))
+                .show(ui, |ui| {
+                    let nearby = s.nearby();
+                    if nearby.is_empty() {
+                        ui.label(tr("Nothing buildable close by."));
+                    }
+                    for n in nearby {
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "{} {}, {} {}, {} {}: {:.3}",
+                                tr("Sun"), n.sun, tr("Ring"), n.ring, tr("Planet"), n.planet(), n.signed_ratio().abs()
+                            ));
+                            if ui.button(tr("Use")).clicked() {
+                                replace = Some((i, n));
+                            }
+                        });
+                    }
+                });
+        }
+        if let Some((i, n)) = replace {
+            self.stages[i] = n;
+        }
+
+        let total = self.signed_ratio();
+        ui.label(egui::RichText::new(format!("{} {:.4}", tr("Total ratio:"), total.abs())).strong());
+        ui.horizontal(|ui| {
+            let direction = if total < 0.0 { Direction::Reversed } else { Direction::Same };
+            ui.label(format!("{} {}", tr("Output turns"), tr(direction.name())));
+            direction.show(ui);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ratio(input: Member, output: Member) -> f64 {
+        Stage { input, output, ..Stage::new(12, 48) }.signed_ratio()
+    }
+
+    #[test]
+    fn willis_ratios() {
+        assert!((ratio(Member::Sun, Member::Carrier) - 5.0).abs() < 1e-12);
+        assert!((ratio(Member::Ring, Member::Carrier) - 1.25).abs() < 1e-12);
+        assert!((ratio(Member::Sun, Member::Ring) + 4.0).abs() < 1e-12);
+        // driven the other way round, an overdrive
+        assert!((ratio(Member::Carrier, Member::Sun) - 0.2).abs() < 1e-12);
+        assert_eq!(Stage { input: Member::Sun, output: Member::Ring, ..Stage::new(12, 48) }.held(), Member::Carrier);
+    }
+
+    #[test]
+    fn stacked_stages_multiply() {
+        let p = Planetary { stages: vec![Stage::new(12, 48), Stage::new(12, 48)] };
+        assert!((p.ratio() - 25.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn single_planet_has_no_neighbour() {
+        let stage = Stage { planets: 1, ..Stage::new(12, 48) };
+        assert!(stage.problems().is_empty());
+    }
+
+    #[test]
+    fn crowded_planets() {
+        // 4 planets of 30 teeth around a 10 tooth sun don't fit
+        let stage = Stage { planets: 4, ..Stage::new(10, 70) };
+        assert!(stage.problems().contains(&"The planets are too big to fit next to each other."));
+        let stage = Stage { planets: 3, ..Stage::new(12, 48) };
+        assert!(stage.problems().is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * Charts, drawn with egui_plot when built with the "plot" feature. Without it, the
+ * numbers behind the chart are shown as text instead.
+ *
+ * Ratios are plotted on a log scale: with even steps between the gears, the points lie on
+ * a straight line.
+ */
+
+// a named list of ratios, one line in the chart
+pub struct Series {
+    pub name: String,
+    pub ratios: Vec<f64>,
+}
+
+// the step from each ratio to the next, in percent
+fn steps(ratios: &[f64]) -> Vec<f64> {
+    ratios.windows(2).map(|w| (w[0] / w[1] - 1.0) * 100.0).collect()
+}
+
+#[cfg(feature = "plot")]
+pub fn ratio_steps(ui: &mut egui::Ui, id: &str, series: &[Series]) {
+    use egui_plot::{Legend, Line, Plot, PlotPoints, Points};
+
+    Plot::new(id)
+        .height(180.0)
+        .allow_scroll(false)
+        .legend(Legend::default())
+        .x_axis_label(tr("gear"))
+        .y_axis_label(tr("ratio (log)"))
+        .y_axis_formatter(|y, _, _| format!("{:.2}", 10f64.powf(y)))
+        .label_formatter(|_, p| format!("{} {:.0}: {:.3}", tr("gear"), p.x, 10f64.powf(p.y)))
+        .show(ui, |plot_ui| {
+            for s in series {
+                let points: Vec<[f64; 2]> = s.ratios.iter().enumerate()
+                    .map(|(i, r)| [(i + 1) as f64, r.log10()])
+                    .collect();
+                plot_ui.line(Line::new(PlotPoints::from(points.clone())).name(&s.name));
+                plot_ui.points(Points::new(PlotPoints::from(points)).radius(3.0_f32).name(&s.name));
+            }
+        });
+    steps_label(ui, series);
+}
+
+#[cfg(not(feature = "plot"))]
+pub fn ratio_steps(ui: &mut egui::Ui, _id: &str, series: &[Series]) {
+    steps_label(ui, series);
+    ui.weak(tr("Built without the \"plot\" feature, no chart."));
+}
+
+fn steps_label(ui: &mut egui::Ui, series: &[Series]) {
+    for s in series {
+        let steps: Vec<String> = steps(&s.ratios).iter().map(|x| format!("{:.1} %", x)).collect();
+        ui.label(format!("{} {}: {}", s.name, tr("steps"), steps.join(", ")));
+    }
+}
+
+// the straight torque-speed line of a DC motor at the output, from stall to free speed,
+// with the speed at the load torque marked. torques in Nm, speeds in rpm.
+pub fn load_speed(free_speed: f64, stall_torque: f64, load: f64) -> f64 {
+    if stall_torque <= 0.0 { 0.0 } else { free_speed * (1.0 - load / stall_torque).max(0.0) }
+}
+
+#[cfg(feature = "plot")]
+pub fn torque_speed(ui: &mut egui::Ui, id: &str, free_speed: f64, stall_torque: f64, load: f64) {
+    use egui_plot::{HLine, Line, Plot, PlotPoints, Points};
+
+    let at_load = load_speed(free_speed, stall_torque, load);
+    Plot::new(id)
+        .height(200.0)
+        .allow_scroll(false)
+        .include_x(0.0)
+        .include_y(0.0)
+        .x_axis_label(format!("{} Nm", tr("torque")))
+        .y_axis_label(format!("{} rpm", tr("speed")))
+        .label_formatter(|_, p| format!("{:.2} Nm: {:.1} rpm", p.x, p.y))
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new(PlotPoints::from(vec![[0.0, free_speed], [stall_torque, 0.0]])).name(tr("output")));
+            plot_ui.hline(HLine::new(at_load).name(tr("at the load")));
+            plot_ui.points(Points::new(PlotPoints::from(vec![[load, at_load]])).radius(4.0_f32).name(tr("load")));
+        });
+    ui.label(format!("{} {:.2} Nm: {:.1} rpm", tr("At"), load, at_load));
+}
+
+#[cfg(not(feature = "plot"))]
+pub fn torque_speed(ui: &mut egui::Ui, _id: &str, free_speed: f64, stall_torque: f64, load: f64) {
+    ui.label(format!("{} {:.2} Nm: {:.1} rpm", tr("At"), load, load_speed(free_speed, stall_torque, load)));
+    ui.weak(tr("Built without the \"plot\" feature, no chart."));
+}
+
+// engine speed over road speed for every gear, one line each from standstill to the
+// redline. speeds are km/h per rpm.
+#[cfg(feature = "plot")]
+pub fn gearing_chart(ui: &mut egui::Ui, id: &str, speeds: &[f64], redline: f64) {
+    use egui_plot::{HLine, Legend, Line, Plot, PlotPoints};
+
+    Plot::new(id)
+        .height(220.0)
+        .allow_scroll(false)
+        .legend(Legend::default())
+        .include_x(0.0)
+        .include_y(0.0)
+        .x_axis_label("km/h")
+        .y_axis_label("rpm")
+        .label_formatter(|name, p| format!("{} {:.0} km/h {} {:.0} rpm", name, p.x, tr("at"), p.y))
+        .show(ui, |plot_ui| {
+            for (i, v) in speeds.iter().enumerate() {
+                plot_ui.line(Line::new(PlotPoints::from(vec![[0.0, 0.0], [v * redline, redline]])).name(format!("{} {}", tr("gear"), i + 1)));
+            }
+            plot_ui.hline(HLine::new(redline).name(tr("redline")));
+        });
+}
+
+#[cfg(not(feature = "plot"))]
+pub fn gearing_chart(ui: &mut egui::Ui, _id: &str, _speeds: &[f64], _redline: f64) {
+    ui.weak(tr("Built without the \"plot\" feature, no chart."));
+}
+
+// relative error in percent over tooth count. The absolute error is plotted, the sign
+// only says whether the ratio ends up too high or too low.
+#[cfg(feature = "plot")]
+pub fn error_vs_teeth(ui: &mut egui::Ui, id: &str, errors: &[(u32, f64)]) {
+    use egui_plot::{Line, Plot, PlotPoints, Points};
+
+    let points: Vec<[f64; 2]> = errors.iter().map(|(t, e)| [*t as f64, e.abs()]).collect();
+    Plot::new(id)
+        .height(200.0)
+        .allow_scroll(false)
+        .include_y(0.0)
+        .x_axis_label(tr("pinion teeth"))
+        .y_axis_label(format!("{} %", tr("error")))
+        .label_formatter(|_, p| format!("{:.0} T: {:.4} %", p.x, p.y))
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new(PlotPoints::from(points.clone())));
+            plot_ui.points(Points::new(PlotPoints::from(points)).radius(2.0_f32));
+        });
+}
+
+#[cfg(not(feature = "plot"))]
+pub fn error_vs_teeth(ui: &mut egui::Ui, id: &str, errors: &[(u32, f64)]) {
+    ui.weak(tr("Built without the \"plot\" feature, no chart."));
+    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+        egui::Grid::new(id).striped(true).show(ui, |ui| {
+            for (t, e) in errors {
+                ui.label(format!("{} T", t));
+                ui.label(format!("{:+.4} %", e));
+                ui.end_row();
+            }
+        });
+    });
+}
@@ -0,0 +1,93 @@
+use crate::dataset::{Dataset, CATALOG_FIELDS};
+
+/*
+ * Reductions of off-the-shelf gearboxes, so nobody has to retype 5.23:1 by hand.
+ * The ratios are the actual ones, not the nominal ones printed on the box. Efficiencies are
+ * typical catalog values at rated load, worm boxes lose more the higher their ratio.
+ *
+ * These are only the defaults of the gearbox catalog, which is a dataset and can be extended
+ * with the boxes on the shelf in Tools → Datasets.
+ */
+
+pub struct Preset {
+    pub name: &'static str,
+    pub ratio: f32,
+    // percent
+    pub efficiency: f32,
+}
+
+pub struct PresetGroup {
+    pub name: &'static str,
+    pub presets: &'static [Preset],
+}
+
+const fn p(name: &'static str, ratio: f32, efficiency: f32) -> Preset {
+    Preset { name, ratio, efficiency }
+}
+
+pub const CATALOG: &[PresetGroup] = &[
+    PresetGroup { name: "VersaPlanetary", presets: &[
+        p("VersaPlanetary 3:1", 3.0, 95.0),
+        p("VersaPlanetary 4:1", 4.0, 95.0),
+        p("VersaPlanetary 5:1", 5.0, 95.0),
+        p("VersaPlanetary 7:1", 7.0, 95.0),
+        p("VersaPlanetary 9:1", 9.0, 95.0),
+        p("VersaPlanetary 10:1", 10.0, 95.0),
+    ]},
+    PresetGroup { name: "MAXPlanetary", presets: &[
+        p("MAXPlanetary 3:1", 3.0, 95.0),
+        p("MAXPlanetary 4:1", 4.0, 95.0),
+        p("MAXPlanetary 5:1", 5.0, 95.0),
+        p("MAXPlanetary 7:1", 7.0, 95.0),
+        p("MAXPlanetary 9:1", 9.0, 95.0),
+    ]},
+    PresetGroup { name: "UltraPlanetary", presets: &[
+        p("UltraPlanetary 3:1 cartridge", 2.89, 95.0),
+        p("UltraPlanetary 4:1 cartridge", 3.61, 95.0),
+        p("UltraPlanetary 5:1 cartridge", 5.23, 95.0),
+    ]},
+    PresetGroup { name: "Sport gearboxes", presets: &[
+        p("Toughbox Mini 8.45:1", 8.45, 92.0),
+        p("Toughbox Mini 10.71:1", 10.71, 92.0),
+        p("Toughbox Mini 12.75:1", 12.75, 92.0),
+        p("SDS MK4 L1", 8.14, 92.0),
+        p("SDS MK4 L2", 6.75, 92.0),
+        p("SDS MK4 L3", 6.12, 92.0),
+        p("SDS MK4i L1", 8.14, 92.0),
+        p("SDS MK4i L2", 6.75, 92.0),
+        p("SDS MK4i L3", 6.12, 92.0),
+    ]},
+    PresetGroup { name: "Planetary gearheads", presets: &[
+        p("Planetary gearhead 3:1", 3.0, 97.0),
+        p("Planetary gearhead 5:1", 5.0, 97.0),
+        p("Planetary gearhead 10:1", 10.0, 97.0),
+        p("Planetary gearhead 25:1", 25.0, 94.0),
+        p("Planetary gearhead 50:1", 50.0, 94.0),
+        p("Planetary gearhead 100:1", 100.0, 94.0),
+    ]},
+    PresetGroup { name: "Worm gearboxes", presets: &[
+        p("Worm gearbox 7.5:1", 7.5, 87.0),
+        p("Worm gearbox 15:1", 15.0, 82.0),
+        p("Worm gearbox 30:1", 30.0, 72.0),
+        p("Worm gearbox 50:1", 50.0, 62.0),
+        p("Worm gearbox 100:1", 100.0, 50.0),
+    ]},
+    PresetGroup { name: "Harmonic drives", presets: &[
+        p("Harmonic drive 30:1", 30.0, 80.0),
+        p("Harmonic drive 50:1", 50.0, 80.0),
+        p("Harmonic drive 80:1", 80.0, 75.0),
+        p("Harmonic drive 100:1", 100.0, 75.0),
+        p("Harmonic drive 120:1", 120.0, 70.0),
+        p("Harmonic drive 160:1", 160.0, 65.0),
+    ]},
+];
+
+pub fn default_catalog() -> Dataset {
+    let mut data = Dataset::new("Gearbox catalog", CATALOG_FIELDS, &[]);
+    data.rows = CATALOG.iter()
+        .flat_map(|g| g.presets.iter().map(move |p| {
+            vec![p.name.to_string(), g.name.to_string(), p.ratio.to_string(), p.efficiency.to_string()]
+        }))
+        .collect();
+    data
+}
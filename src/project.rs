@@ -0,0 +1,255 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * A project keeps the gear pair of the active calculation together with reference files,
+ * like a photo of the donor gearbox or a screenshot of a datasheet. The project file is text
+ * with one "key=value" line each, the attachments are stored in it as base64:
+ *     gear-ratio-project=1
+ *     pair=l=11&r=72&lock=ratio
+ *     attachment=donor.jpg;/9j/4AAQSkZJRgABAQ...
+ * The pair is written like the URL fragment. Lines that aren't known are skipped.
+ *
+ * PNG and JPEG images are shown in the side panel and put into the HTML report, other files
+ * are only listed. Files dropped on the window become attachments, the native app also adds
+ * them by path. The browser has no file system, so there projects can't be saved or opened.
+ */
+
+#[cfg(not(target_arch = "wasm32"))]
+const HEADER: &str = "gear-ratio-project=1";
+// width of the images in the side panel
+const THUMBNAIL_WIDTH: f32 = 220.0;
+
+pub struct Attachment {
+    pub name: String,
+    pub bytes: Vec<u8>,
+    // None until the image was decoded, Some(None) if it isn't one
+    texture: Option<Option<egui::TextureHandle>>,
+}
+
+impl Attachment {
+    pub fn new(name: String, bytes: Vec<u8>) -> Attachment {
+        Attachment { name, bytes, texture: None }
+    }
+
+    // the type of the images that can be shown, from the first bytes
+    pub fn image_mime(&self) -> Option<&'static str> {
+        if self.bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some("image/png")
+        } else if self.bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+            Some("image/jpeg")
+        } else {
+            None
+        }
+    }
+
+    fn texture(&mut self, ctx: &egui::Context) -> Option<&egui::TextureHandle> {
+        if self.texture.is_none() {
+            let decoded = self.image_mime().and_then(|_| image::load_from_memory(&self.bytes).ok());
+            self.texture = Some(decoded.map(|image| {
+                let rgba = image.to_rgba8();
+                let size = [rgba.width() as usize, rgba.height() as usize];
+                let pixels = egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw());
+                ctx.load_texture(&self.name, pixels, egui::TextureOptions::default())
+            }));
+        }
+        self.texture.as_ref().and_then(|t| t.as_ref())
+    }
+}
+
+pub struct Project {
+    pub attachments: Vec<Attachment>,
+    #[cfg(not(target_arch = "wasm32"))]
+    path: String,
+    // file to attach
+    #[cfg(not(target_arch = "wasm32"))]
+    attach_path: String,
+    status: String,
+}
+
+impl Project {
+    pub fn new() -> Project {
+        Project {
+            attachments: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            path: String::from("gears.project"),
+            #[cfg(not(target_arch = "wasm32"))]
+            attach_path: String::new(),
+            status: String::new(),
+        }
+    }
+
+    // pair is the URL fragment of the active calculation
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn to_text(&self, pair: &str) -> String {
+        let mut lines = vec![HEADER.to_string(), format!("pair={}", pair)];
+        lines.extend(self.attachments.iter().map(|a| format!("attachment={};{}", a.name, base64::encode(&a.bytes))));
+        lines.join("\n")
+    }
+
+    // replaces the attachments, returns the pair
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_text(&mut self, text: &str) -> Result<String, String> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err(String::from("not a gear ratio project"));
+        }
+        let mut pair = String::new();
+        let mut attachments = Vec::new();
+        for (key, value) in lines.filter_map(|l| l.split_once('=')) {
+            match key {
+                "pair" => pair = value.to_string(),
+                "attachment" => {
+                    // the name can't have a ; in it, see attach()
+                    let (name, data) = value.split_once(';').ok_or_else(|| format!("attachment without data: {}", value))?;
+                    let bytes = base64::decode(data.trim()).map_err(|e| format!("attachment {}: {}", name, e))?;
+                    attachments.push(Attachment::new(name.to_string(), bytes));
+                }
+                _ => {}
+            }
+        }
+        self.attachments = attachments;
+        Ok(pair)
+    }
+
+    pub fn attach(&mut self, name: &str, bytes: Vec<u8>) {
+        // keeps the line format of the project file intact
+        let name = name.replace([';', '\n', '\r'], "_");
+        self.status = format!("{} {}", tr("Attached"), name);
+        self.attachments.push(Attachment::new(name, bytes));
+    }
+
+    // files dropped on the window. returns true if there were any.
+    pub fn take_dropped(&mut self, ctx: &egui::Context) -> bool {
+        let dropped = ctx.input_mut(|i| std::mem::take(&mut i.raw.dropped_files));
+        let any = !dropped.is_empty();
+        for file in dropped {
+            match (file.bytes, file.path) {
+                (Some(bytes), _) => self.attach(&file.name, bytes.to_vec()),
+                #[cfg(not(target_arch = "wasm32"))]
+                (None, Some(path)) => self.attach_file(&path.to_string_lossy()),
+                _ => {}
+            }
+        }
+        any
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn attach_file(&mut self, path: &str) {
+        match std::fs::read(path) {
+            Ok(bytes) => {
+                let name = std::path::Path::new(path).file_name().map_or(path.into(), |n| n.to_string_lossy());
+                self.attach(&name, bytes);
+            }
+            Err(e) => self.status = format!("{} {}: {}", tr("Can't read"), path, e),
+        }
+    }
+
+    // save and open, native only. returns the pair of an opened project.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn file_ui(&mut self, ui: &mut egui::Ui, pair: &str) -> Option<String> {
+        let mut opened = None;
+        ui.add(egui::TextEdit::singleline(&mut self.path).desired_width(THUMBNAIL_WIDTH));
+        ui.horizontal(|ui| {
+            if ui.button(tr("Save project")).clicked() {
+                self.status = match std::fs::write(&self.path, self.to_text(pair)) {
+                    Ok(()) => format!("{} {}", tr("Saved"), self.path),
+                    Err(e) => format!("{} {}", tr("Saving failed:"), e),
+                };
+            }
+            if ui.button(tr("Open project")).clicked() {
+                let loaded = std::fs::read_to_string(&self.path).map_err(|e| e.to_string()).and_then(|t| self.load_text(&t));
+                match loaded {
+                    Ok(p) => {
+                        self.status = format!("{} {}", tr("Opened"), self.path);
+                        opened = Some(p);
+                    }
+                    Err(e) => self.status = format!("{} {}", tr("Opening failed:"), e),
+                }
+            }
+        });
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.attach_path).hint_text(tr("File to attach")).desired_width(THUMBNAIL_WIDTH - 60.0));
+            if ui.add_enabled(!self.attach_path.trim().is_empty(), egui::Button::new(tr("Attach"))).clicked() {
+                let path = self.attach_path.trim().to_string();
+                self.attach_file(&path);
+                self.attach_path.clear();
+            }
+        });
+        opened
+    }
+
+    // the side panel. returns the pair of an opened project.
+    pub fn ui(&mut self, ui: &mut egui::Ui, pair: &str) -> Option<String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let opened = self.file_ui(ui, pair);
+        #[cfg(target_arch = "wasm32")]
+        let opened = {
+            let _ = pair;
+            None
+        };
+        ui.weak(tr("Drop images or files on the window to attach them."));
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+        ui.separator();
+
+        let mut remove = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, a) in self.attachments.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(&a.name).strong());
+                    ui.weak(format!("{:.1} kB", a.bytes.len() as f32 / 1000.0));
+                    if ui.small_button("🗑").on_hover_text(tr("Remove attachment")).clicked() {
+                        remove = Some(i);
+                    }
+                });
+                if let Some(texture) = a.texture(ui.ctx()) {
+                    ui.add(egui::Image::new(texture).max_width(THUMBNAIL_WIDTH));
+                }
+                ui.add_space(4.0);
+            }
+        });
+        if let Some(i) = remove {
+            self.attachments.remove(i);
+        }
+        opened
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attachments_round_trip() {
+        let mut project = Project::new();
+        project.attach("donor;box.png", vec![0x89, b'P', b'N', b'G', 0, 255, b'\n']);
+        project.attach("notes.txt", b"48 teeth\n".to_vec());
+        let text = project.to_text("l=11&r=72&lock=ratio");
+
+        let mut loaded = Project::new();
+        assert_eq!(loaded.load_text(&text).unwrap(), "l=11&r=72&lock=ratio");
+        let files: Vec<(&str, &[u8])> = loaded.attachments.iter().map(|a| (a.name.as_str(), a.bytes.as_slice())).collect();
+        assert_eq!(files, [("donor_box.png", &[0x89, b'P', b'N', b'G', 0, 255, b'\n'][..]), ("notes.txt", &b"48 teeth\n"[..])]);
+    }
+
+    #[test]
+    fn not_a_project() {
+        let mut project = Project::new();
+        project.attach("kept.txt", Vec::new());
+        assert!(project.load_text("l=11&r=72").is_err());
+        assert!(project.load_text("gear-ratio-project=1\nattachment=bad;!!!").is_err());
+        assert_eq!(project.attachments.len(), 1);
+    }
+
+    #[test]
+    fn image_types() {
+        let png = Attachment::new(String::from("a"), b"\x89PNG\r\n\x1a\n....".to_vec());
+        let jpeg = Attachment::new(String::from("b"), vec![0xff, 0xd8, 0xff, 0xe0]);
+        let text = Attachment::new(String::from("c"), b"hello".to_vec());
+        assert_eq!((png.image_mime(), jpeg.image_mime(), text.image_mime()), (Some("image/png"), Some("image/jpeg"), None));
+    }
+}
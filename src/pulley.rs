@@ -0,0 +1,116 @@
+use eframe::egui;
+
+use crate::app::{columns_layout, lock_shortcut, lock_toggle};
+use crate::constraint::{ConstraintGraph, Quantity, Relation};
+use crate::i18n::tr;
+use crate::settings::Settings;
+
+/*
+ * V-belt and friction drives: same as the gear pair, but the sides are pulley diameters
+ * instead of tooth counts. Since diameters aren't quantized, there is no rounding and
+ * the given ratio is always the actual ratio.
+ */
+
+pub struct PulleyDrive {
+    // pitch diameters in mm
+    left: f32,
+    right: f32,
+    ratio: f32,
+    graph: ConstraintGraph,
+}
+
+// for screen readers
+fn name(column: Quantity) -> &'static str {
+    tr(match column {
+        Quantity::Left => "Driver pulley diameter",
+        Quantity::Right => "Driven pulley diameter",
+        _ => "Pulley ratio",
+    })
+}
+
+impl PulleyDrive {
+    pub fn new() -> PulleyDrive {
+        PulleyDrive {
+            left: 50.0,
+            right: 100.0,
+            ratio: 2.0,
+            graph: ConstraintGraph::new(&[Relation::Teeth], &[Quantity::Ratio]),
+        }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    // recomputes the value that is not fixed and not changed
+    fn recompute_from(&mut self, column: Quantity) {
+        for (q, _) in self.graph.plan(&[column]) {
+            match q {
+                Quantity::Left => self.left = self.right / self.ratio,
+                Quantity::Right => self.right = self.left * self.ratio,
+                _ => self.ratio = self.right / self.left,
+            }
+        }
+    }
+
+    fn lock_ui(&mut self, ui: &mut egui::Ui, column: Quantity) {
+        if lock_toggle(ui, self.graph.is_locked(column), name(column)) {
+            self.graph.lock_one_of(column, &[Quantity::Left, Quantity::Ratio, Quantity::Right]);
+        }
+    }
+
+    fn diameter_column(&mut self, ui: &mut egui::Ui, column: Quantity, settings: &Settings) {
+        ui.vertical(|ui| {
+            ui.label(egui::RichText::new(
+                tr(if column == Quantity::Left { "Driver Pulley" } else { "Driven Pulley" })
+            ).strong());
+
+            let value = match column {
+                Quantity::Left => &mut self.left,
+                _            => &mut self.right,
+            };
+            let changed = ui.add(settings.spinner(value)
+                .interactive(!self.graph.is_locked(column))
+                .step(1.0)
+                .fine_step(0.1)
+                .range(1.0..=10000.0)
+                .precision(1)
+                .label(name(column))
+                .id_source(("pulley", column as i32))).changed();
+            if changed {
+                self.recompute_from(column);
+            }
+            self.lock_ui(ui, column);
+        });
+    }
+
+    fn ratio_column(&mut self, ui: &mut egui::Ui, settings: &Settings) {
+        ui.vertical(|ui| {
+            ui.label(egui::RichText::new(tr("Ratio")).strong());
+            let changed = ui.add(settings.spinner(&mut self.ratio)
+                .interactive(!self.graph.is_locked(Quantity::Ratio))
+                .step(0.1)
+                .fine_step(0.01)
+                .range(0.1..=100.0)
+                .precision(2)
+                .label(name(Quantity::Ratio))
+                .id_source(("pulley", Quantity::Ratio as i32))).changed();
+            if changed {
+                self.recompute_from(Quantity::Ratio);
+            }
+            self.lock_ui(ui, Quantity::Ratio);
+        });
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, settings: &Settings) {
+        if let Some(q) = lock_shortcut(ui) {
+            self.graph.lock_one_of(q, &[Quantity::Left, Quantity::Ratio, Quantity::Right]);
+        }
+        ui.with_layout(columns_layout(ui), |ui| {
+            self.diameter_column(ui, Quantity::Left, settings);
+            self.ratio_column(ui, settings);
+            self.diameter_column(ui, Quantity::Right, settings);
+        });
+        ui.label(tr("Diameters in mm."));
+    }
+}
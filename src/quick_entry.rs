@@ -0,0 +1,180 @@
+use crate::constraint::Quantity;
+use crate::i18n::tr;
+
+/*
+ * Quick entry for the gear pair: short phrases like "13 to 40", "3:1 with 15 tooth pinion"
+ * or "ratio 2.6 lock left" get parsed into what the user wants to set. Applying it is up to
+ * the app, this only finds out the intent and which of the teeth and the ratio follows from
+ * it: what was typed is kept whatever is locked, and only the one that wasn't typed gets
+ * solved. The lock only decides when the intent leaves a choice.
+ */
+
+#[derive(PartialEq, Debug, Default)]
+pub struct Intent {
+    pub left: Option<u32>,
+    pub right: Option<u32>,
+    pub ratio: Option<f64>,
+    pub lock: Option<Quantity>,
+}
+
+impl Intent {
+    // the quantity of ratio = right / left to solve for, None if there's nothing to solve
+    pub fn solve_for(&self, locked: Quantity) -> Option<Quantity> {
+        match (self.left.is_some(), self.ratio.is_some(), self.right.is_some()) {
+            (true, _, true) => Some(Quantity::Ratio),
+            (true, true, false) => Some(Quantity::Right),
+            (false, true, true) => Some(Quantity::Left),
+            // a gear alone keeps a locked ratio, otherwise the ratio follows the teeth
+            (true, false, false) if locked == Quantity::Ratio => Some(Quantity::Right),
+            (false, false, true) if locked == Quantity::Ratio => Some(Quantity::Left),
+            (true, false, false) | (false, false, true) => Some(Quantity::Ratio),
+            // a ratio alone keeps a locked gear
+            (false, true, false) if locked == Quantity::Right => Some(Quantity::Left),
+            (false, true, false) => Some(Quantity::Right),
+            (false, false, false) => None,
+        }
+    }
+}
+
+// words that don't change the meaning
+const FILLER: &[&str] = &["with", "a", "an", "the", "on", "and", "of", "is", "=", "gear", "set"];
+
+fn side(word: &str) -> Option<Quantity> {
+    match word {
+        "left" | "input" | "pinion" | "driver" | "motor" => Some(Quantity::Left),
+        "right" | "output" | "wheel" | "driven" | "spur" => Some(Quantity::Right),
+        _ => None,
+    }
+}
+
+fn tokens(text: &str) -> Vec<String> {
+    let text = text.to_lowercase().replace(':', " : ").replace(',', " ");
+    let mut out = Vec::new();
+    for w in text.split_whitespace() {
+        // "15t" is 15 teeth
+        match w.strip_suffix('t') {
+            Some(n) if n.parse::<u32>().is_ok() => {
+                out.push(n.to_string());
+                out.push(String::from("teeth"));
+            }
+            _ => out.push(w.to_string()),
+        }
+    }
+    out.retain(|w| !FILLER.contains(&w.as_str()));
+    out
+}
+
+pub fn parse(text: &str) -> Result<Intent, String> {
+    let t = tokens(text);
+    let word = |i: usize| t.get(i).map(|s| s.as_str()).unwrap_or("");
+    let number = |i: usize| word(i).parse::<f64>().ok().filter(|x| *x > 0.0);
+    let teeth = |i: usize| word(i).parse::<u32>().ok().filter(|x| *x > 0);
+
+    let mut intent = Intent::default();
+    let mut i = 0;
+    while i < t.len() {
+        if word(i) == "lock" {
+            intent.lock = match word(i + 1) {
+                "ratio" => Some(Quantity::Ratio),
+                w => Some(side(w).ok_or_else(|| format!("{} \"{}\".", tr("Can't lock"), w))?),
+            };
+            i += 2;
+        } else if word(i) == "ratio" {
+            intent.ratio = Some(number(i + 1).ok_or_else(|| tr("Expected a number after \"ratio\"."))?);
+            i += 2;
+        } else if let Some(x) = number(i) {
+            match word(i + 1) {
+                // 3:1 is a reduction of 3
+                ":" => {
+                    let y = number(i + 2).ok_or_else(|| tr("Expected a number after \":\"."))?;
+                    intent.ratio = Some(x / y);
+                    i += 3;
+                }
+                "to" => {
+                    intent.left = Some(teeth(i).ok_or_else(|| tr("Tooth counts must be whole numbers."))?);
+                    intent.right = Some(teeth(i + 2).ok_or_else(|| tr("Expected a tooth count after \"to\"."))?);
+                    i += 3;
+                }
+                "tooth" | "teeth" => {
+                    let n = teeth(i).ok_or_else(|| tr("Tooth counts must be whole numbers."))?;
+                    match side(word(i + 2)) {
+                        Some(Quantity::Left) => intent.left = Some(n),
+                        Some(_) => intent.right = Some(n),
+                        None => return Err(format!("{} {} {}", tr("Which gear has"), n, tr("teeth? Say pinion or wheel."))),
+                    }
+                    i += 3;
+                }
+                _ if t.len() == 1 => {
+                    intent.ratio = Some(x);
+                    i += 1;
+                }
+                w => return Err(format!("{} \"{}\" {} {}.", tr("Don't know what to do with"), w, tr("after"), x)),
+            }
+        } else {
+            return Err(format!("{} \"{}\".", tr("Don't understand"), word(i)));
+        }
+    }
+    if intent == Intent::default() {
+        return Err(String::from(tr("Type e.g. \"13 to 40\" or \"3:1 with 15 tooth pinion\".")));
+    }
+    Ok(intent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratio_only() {
+        let i = parse("ratio 2.6").unwrap();
+        assert_eq!(i.ratio, Some(2.6));
+        assert_eq!((i.left, i.right, i.lock), (None, None, None));
+        assert_eq!(parse("3:1").unwrap().ratio, Some(3.0));
+        assert_eq!(parse("2.5").unwrap().ratio, Some(2.5));
+    }
+
+    #[test]
+    fn ratio_with_pinion() {
+        let i = parse("3:1 with 15 tooth pinion").unwrap();
+        assert_eq!((i.left, i.ratio, i.right), (Some(15), Some(3.0), None));
+        assert_eq!(parse("3:1 15t input").unwrap().left, Some(15));
+    }
+
+    #[test]
+    fn ratio_with_gear() {
+        let i = parse("2:1 with 40 teeth wheel lock right").unwrap();
+        assert_eq!((i.left, i.ratio, i.right), (None, Some(2.0), Some(40)));
+        assert_eq!(i.lock, Some(Quantity::Right));
+        let i = parse("13 to 40").unwrap();
+        assert_eq!((i.left, i.right), (Some(13), Some(40)));
+    }
+
+    #[test]
+    fn bad_input() {
+        assert!(parse("").is_err());
+        assert!(parse("banana").is_err());
+        assert!(parse("ratio").is_err());
+        assert!(parse("ratio -2").is_err());
+        assert!(parse("3:").is_err());
+        assert!(parse("13.5 to 40").is_err());
+        assert!(parse("15 teeth").is_err());
+        assert!(parse("lock banana").is_err());
+    }
+
+    #[test]
+    fn solves_what_was_not_typed() {
+        let i = parse("3:1 with 15 tooth pinion").unwrap();
+        for locked in [Quantity::Left, Quantity::Ratio, Quantity::Right] {
+            assert_eq!(i.solve_for(locked), Some(Quantity::Right));
+        }
+        let i = parse("2:1 with 40 teeth wheel").unwrap();
+        for locked in [Quantity::Left, Quantity::Ratio, Quantity::Right] {
+            assert_eq!(i.solve_for(locked), Some(Quantity::Left));
+        }
+        assert_eq!(parse("13 to 40").unwrap().solve_for(Quantity::Ratio), Some(Quantity::Ratio));
+        let ratio = parse("ratio 2").unwrap();
+        assert_eq!(ratio.solve_for(Quantity::Right), Some(Quantity::Left));
+        assert_eq!(ratio.solve_for(Quantity::Left), Some(Quantity::Right));
+        assert_eq!(parse("lock left").unwrap().solve_for(Quantity::Left), None);
+    }
+}
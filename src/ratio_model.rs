@@ -0,0 +1,190 @@
+use eframe::egui;
+
+use crate::band::Band;
+use crate::i18n::tr;
+
+/*
+ * The given ratio is what the user asked for, the actual ratio is what the whole tooth
+ * counts give. Rounding makes them diverge, and the policy decides what happens then:
+ * - Tolerate: nothing, the actual ratio is just shown
+ * - Warn: a warning shows up when the divergence leaves the tolerance band
+ * - Snap: the given ratio follows the actual ratio, so they never diverge, unless the given
+ *   ratio is locked
+ *
+ * How the exact tooth count is rounded to whole teeth is up to the user too, rounding down or
+ * up keeps the error on one side, and the smallest error picks whichever of the two is closer.
+ *
+ * Zero teeth or a ratio that isn't a positive number are refused, the model keeps its last
+ * good values and reports the problem, so nothing derived from it becomes infinite.
+ *
+ * The gear pair owns one, the gear train checks its total against one made from its target,
+ * and the comparison and the reports take the error of the active pair from it.
+ */
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Divergence {
+    Tolerate,
+    Warn,
+    Snap,
+}
+
+impl Divergence {
+    pub const ALL: [Divergence; 3] = [Divergence::Tolerate, Divergence::Warn, Divergence::Snap];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Divergence::Tolerate => "Tolerate",
+            Divergence::Warn => "Warn",
+            Divergence::Snap => "Snap",
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Rounding {
+    Nearest,
+    Down,
+    Up,
+    SmallestError,
+}
+
+impl Rounding {
+    pub const ALL: [Rounding; 4] = [Rounding::Nearest, Rounding::Down, Rounding::Up, Rounding::SmallestError];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Rounding::Nearest => "Round to nearest",
+            Rounding::Down => "Round down",
+            Rounding::Up => "Round up",
+            Rounding::SmallestError => "Smallest ratio error",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RatioModel {
+    pub given: f64,
+    pub actual: f64,
+    pub policy: Divergence,
+    pub rounding: Rounding,
+    // acceptable divergence, for the Warn policy and the searches
+    pub band: Band,
+    // why the last values were refused
+    pub problem: Option<&'static str>,
+}
+
+impl RatioModel {
+    pub fn new(ratio: f64) -> RatioModel {
+        RatioModel {
+            given: ratio,
+            actual: ratio,
+            policy: Divergence::Warn,
+            rounding: Rounding::Nearest,
+            band: Band::new(),
+            problem: None,
+        }
+    }
+
+    // a target with an acceptable error of ± percent, which warns when a ratio is outside
+    pub fn target(ratio: f64, percent: f64) -> RatioModel {
+        let mut model = RatioModel::new(ratio);
+        model.band.percent = percent;
+        model
+    }
+
+    pub fn set_given(&mut self, ratio: f64) {
+        if ratio.is_finite() && ratio > 0.0 {
+            self.given = ratio;
+            self.problem = None;
+        } else {
+            self.problem = Some("The ratio has to be a positive number.");
+        }
+    }
+
+    // the ratio of the teeth, which the given ratio snaps to if the policy says so and it
+    // isn't locked
+    pub fn set_actual(&mut self, left_teeth: u32, right_teeth: u32, given_locked: bool) {
+        if left_teeth == 0 || right_teeth == 0 {
+            self.problem = Some("A gear needs at least one tooth.");
+            return;
+        }
+        self.problem = None;
+        self.actual = right_teeth as f64 / left_teeth as f64;
+        if self.policy == Divergence::Snap && !given_locked {
+            self.set_given(self.actual);
+        }
+    }
+
+    // whole teeth for an exact tooth count, ratio_of gives the ratio a count would make
+    pub fn round_teeth(&self, exact: f64, ratio_of: impl Fn(f64) -> f64) -> u32 {
+        let teeth = match self.rounding {
+            Rounding::Nearest => exact.round(),
+            Rounding::Down => exact.floor(),
+            Rounding::Up => exact.ceil(),
+            Rounding::SmallestError => {
+                let (down, up) = (exact.floor().max(1.0), exact.ceil().max(1.0));
+                if self.error_of(ratio_of(down)).abs() <= self.error_of(ratio_of(up)).abs() { down } else { up }
+            }
+        };
+        teeth.max(1.0) as u32
+    }
+
+    // how far a ratio is off the given ratio, in percent
+    pub fn error_of(&self, ratio: f64) -> f64 {
+        (ratio / self.given - 1.0) * 100.0
+    }
+
+    pub fn error(&self) -> f64 {
+        self.error_of(self.actual)
+    }
+
+    // whether a ratio is too far off to be accepted silently
+    pub fn diverges(&self, ratio: f64) -> bool {
+        self.policy == Divergence::Warn && !self.band.contains(self.error_of(ratio) / 100.0)
+    }
+
+    pub fn warning(&self) -> Option<String> {
+        if self.diverges(self.actual) {
+            Some(format!("{} {:+.2} % {}", tr("Actual ratio is"), self.error(), tr("off the given ratio.")))
+        } else {
+            None
+        }
+    }
+
+    pub fn policy_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("divergence_policy")
+                .selected_text(tr(self.policy.name()))
+                .show_ui(ui, |ui| {
+                    for p in Divergence::ALL {
+                        ui.selectable_value(&mut self.policy, p, tr(p.name()));
+                    }
+                });
+            if self.policy == Divergence::Warn {
+                ui.add(egui::DragValue::new(&mut self.band.percent).speed(0.05).clamp_range(0.0..=100.0).suffix(" %"));
+            }
+        });
+        egui::ComboBox::from_id_source("rounding")
+            .selected_text(tr(self.rounding.name()))
+            .show_ui(ui, |ui| {
+                for r in Rounding::ALL {
+                    ui.selectable_value(&mut self.rounding, r, tr(r.name()));
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_leaves_a_locked_given_ratio() {
+        let mut model = RatioModel::new(1.5);
+        model.policy = Divergence::Snap;
+        model.set_actual(10, 16, true);
+        assert_eq!(model.given, 1.5);
+        model.set_actual(10, 16, false);
+        assert_eq!(model.given, 1.6);
+    }
+}
@@ -0,0 +1,75 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * RC cars: the pinion on the motor drives the spur gear, which drives the wheels through
+ * the internal ratio of the transmission. The pinion/spur pair is the regular gear pair,
+ * this adds the vehicle around it.
+ */
+
+pub struct RcCar {
+    // internal transmission ratio, e.g. 2.6 for many 1/10 buggies
+    internal: f32,
+    // tire diameter in mm
+    tire: f32,
+    kv: f32,
+    cells: u32,
+    // nominal voltage of one cell
+    cell_voltage: f32,
+}
+
+impl RcCar {
+    pub fn new() -> RcCar {
+        RcCar {
+            internal: 2.6,
+            tire: 83.0,
+            kv: 4000.0,
+            cells: 2,
+            cell_voltage: 3.7,
+        }
+    }
+
+    pub fn final_drive(&self, spur_ratio: f32) -> f32 {
+        spur_ratio * self.internal
+    }
+
+    // theoretical top speed in km/h, without load or losses
+    fn top_speed(&self, spur_ratio: f32) -> f32 {
+        let motor_rpm = self.kv * self.cells as f32 * self.cell_voltage;
+        let wheel_rpm = motor_rpm / self.final_drive(spur_ratio);
+        wheel_rpm * std::f32::consts::PI * self.tire * 60.0 / 1_000_000.0
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, spur_ratio: f32) {
+        egui::Grid::new("rc_inputs").show(ui, |ui| {
+            ui.label(tr("Internal ratio"));
+            ui.add(egui::DragValue::new(&mut self.internal).speed(0.01).clamp_range(0.1..=20.0));
+            ui.end_row();
+            ui.label(tr("Tire diameter"));
+            ui.add(egui::DragValue::new(&mut self.tire).speed(0.5).clamp_range(10.0..=500.0).suffix(" mm"));
+            ui.end_row();
+            ui.label(tr("Motor"));
+            ui.add(egui::DragValue::new(&mut self.kv).speed(10.0).clamp_range(100.0..=100000.0).suffix(" kV"));
+            ui.end_row();
+            ui.label(tr("Battery"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.cells).clamp_range(1..=12).suffix(" S"));
+                ui.label("×");
+                ui.add(egui::DragValue::new(&mut self.cell_voltage).speed(0.01).clamp_range(1.0..=5.0).suffix(" V"));
+            });
+            ui.end_row();
+        });
+        ui.separator();
+
+        egui::Grid::new("rc_results").striped(true).show(ui, |ui| {
+            ui.label(tr("Final drive ratio"));
+            ui.label(egui::RichText::new(format!("{:.2}", self.final_drive(spur_ratio))).strong());
+            ui.end_row();
+            ui.label(tr("Top speed"));
+            ui.label(egui::RichText::new(format!("{:.1} km/h", self.top_speed(spur_ratio))).strong());
+            ui.end_row();
+        });
+        ui.label(tr("Pinion is the input gear, spur the output gear."));
+    }
+}
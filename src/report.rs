@@ -0,0 +1,309 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+use crate::project::Attachment;
+use crate::ratio_model::RatioModel;
+use crate::search::{self, describe, Candidate};
+
+/*
+ * Reports of a design, to keep the calculation next to the project that uses it. The
+ * Markdown report has the inputs, what follows from them and, if wanted, the results of
+ * the train search.
+ *
+ * The PDF report is a sheet for the shop: the numbers and a drawing of the pitch circles.
+ * It needs the "pdf" feature and a file system, so there's none in the browser.
+ *
+ * The HTML report is a single file with the tables and the images attached to the project,
+ * embedded as data URLs so it can be mailed on its own.
+ */
+
+// everything a report says about the gear pair
+pub struct Design {
+    pub name: String,
+    pub left: u32,
+    pub right: u32,
+    pub ratio: RatioModel,
+    // in mm
+    pub module: f32,
+    pub center_distance: f32,
+    // in rpm
+    pub input_speed: f32,
+    pub output_speed: f32,
+}
+
+impl Design {
+    // the actual ratio as a reduced fraction
+    pub fn fraction(&self) -> String {
+        let g = search::gcd(self.left, self.right).max(1);
+        format!("{}/{}", self.right / g, self.left / g)
+    }
+
+}
+
+fn inputs(d: &Design) -> Vec<(&'static str, String)> {
+    vec![
+        ("Input gear", format!("{} T", d.left)),
+        ("Output gear", format!("{} T", d.right)),
+        ("Given ratio", format!("{:.4}", d.ratio.given)),
+        ("Module", format!("{} mm", d.module)),
+        ("Input speed", format!("{} rpm", d.input_speed)),
+    ]
+}
+
+fn derived(d: &Design) -> Vec<(&'static str, String)> {
+    vec![
+        ("Actual ratio", format!("{} = {:.4}", d.fraction(), d.ratio.actual)),
+        ("Ratio error", format!("{:+.3} %", d.ratio.error())),
+        ("Center distance", format!("{:.2} mm", d.center_distance)),
+        ("Output speed", format!("{:.1} rpm", d.output_speed)),
+    ]
+}
+
+fn table(s: &mut String, rows: &[(&str, String)]) {
+    *s += "| Quantity | Value |\n|---|---|\n";
+    for (q, v) in rows {
+        *s += &format!("| {} | {} |\n", q, v);
+    }
+    *s += "\n";
+}
+
+pub fn markdown(design: &Design, search: Option<&[Vec<Candidate>]>) -> String {
+    let d = design;
+    let mut s = format!("# {}\n\n## Inputs\n\n", d.name);
+    table(&mut s, &inputs(d));
+    s += "## Derived values\n\n";
+    table(&mut s, &derived(d));
+    if let Some(groups) = search {
+        s += "## Train search\n\n| Stages | Train (driven/driver) | Ratio | Error | Total teeth |\n|---|---|---|---|---|\n";
+        for (i, group) in groups.iter().enumerate() {
+            for c in group {
+                s += &format!("| {} | {} | {:.6} | {:.4} % | {} |\n",
+                    i + 1, describe(&c.train), c.ratio, c.error * 100.0, c.total_teeth());
+            }
+        }
+        s += "\n";
+    }
+    s
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn html_table(s: &mut String, rows: &[(&str, String)]) {
+    *s += "<table>\n<tr><th>Quantity</th><th>Value</th></tr>\n";
+    for (q, v) in rows {
+        *s += &format!("<tr><td>{}</td><td>{}</td></tr>\n", escape(q), escape(v));
+    }
+    *s += "</table>\n";
+}
+
+// attachments that aren't PNG or JPEG images are only listed by name
+pub fn html(design: &Design, search: Option<&[Vec<Candidate>]>, attachments: &[Attachment]) -> String {
+    let d = design;
+    let name = escape(&d.name);
+    let mut s = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+        <style>table {{ border-collapse: collapse; }} td, th {{ border: 1px solid #999; padding: 2px 8px; }} img {{ max-width: 100%; }}</style>\n\
+        </head>\n<body>\n<h1>{}</h1>\n<h2>Inputs</h2>\n", name, name);
+    html_table(&mut s, &inputs(d));
+    s += "<h2>Derived values</h2>\n";
+    html_table(&mut s, &derived(d));
+    if let Some(groups) = search {
+        s += "<h2>Train search</h2>\n<table>\n<tr><th>Stages</th><th>Train (driven/driver)</th><th>Ratio</th><th>Error</th><th>Total teeth</th></tr>\n";
+        for (i, group) in groups.iter().enumerate() {
+            for c in group {
+                s += &format!("<tr><td>{}</td><td>{}</td><td>{:.6}</td><td>{:.4} %</td><td>{}</td></tr>\n",
+                    i + 1, escape(&describe(&c.train)), c.ratio, c.error * 100.0, c.total_teeth());
+            }
+        }
+        s += "</table>\n";
+    }
+    if !attachments.is_empty() {
+        s += "<h2>Attachments</h2>\n";
+        for a in attachments {
+            let name = escape(&a.name);
+            match a.image_mime() {
+                Some(mime) => s += &format!("<figure>\n<img src=\"data:{};base64,{}\" alt=\"{}\">\n<figcaption>{}</figcaption>\n</figure>\n",
+                    mime, base64::encode(&a.bytes), name, name),
+                None => s += &format!("<p>{} ({} bytes)</p>\n", name, a.bytes.len()),
+            }
+        }
+    }
+    s += "</body>\n</html>\n";
+    s
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn pdf_available() -> bool {
+    cfg!(feature = "pdf")
+}
+
+// an A4 page with the numbers and the pitch circles of the pair
+#[cfg(all(feature = "pdf", not(target_arch = "wasm32")))]
+fn save_pdf(design: &Design, path: &str) -> Result<(), String> {
+    use printpdf::{BuiltinFont, Line, Mm, PdfDocument, Point};
+
+    let d = design;
+    let (doc, page, layer) = PdfDocument::new(d.name.as_str(), Mm(210.0), Mm(297.0), "report");
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| e.to_string())?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(|e| e.to_string())?;
+    let layer = doc.get_page(page).get_layer(layer);
+
+    layer.use_text(d.name.as_str(), 18.0, Mm(20.0), Mm(270.0), &bold);
+    let mut y = 255.0;
+    for (title, rows) in [("Inputs", inputs(d)), ("Derived values", derived(d))] {
+        layer.use_text(title, 13.0, Mm(20.0), Mm(y), &bold);
+        y -= 7.0;
+        for (q, v) in rows {
+            layer.use_text(q, 11.0, Mm(25.0), Mm(y), &font);
+            layer.use_text(v, 11.0, Mm(80.0), Mm(y), &font);
+            y -= 6.0;
+        }
+        y -= 5.0;
+    }
+
+    // pitch circles touching each other, scaled to fit below the numbers
+    let (r1, r2) = (d.module * d.left as f32 / 2.0, d.module * d.right as f32 / 2.0);
+    let scale = (170.0 / (2.0 * (r1 + r2))).min((y - 40.0) / (2.0 * r1.max(r2)));
+    let cy = 30.0 + r1.max(r2) * scale;
+    let cx1 = 105.0 - (r1 + r2) * scale / 2.0;
+    let cx2 = cx1 + (r1 + r2) * scale;
+    layer.set_outline_thickness(0.5);
+    for (cx, r, teeth) in [(cx1, r1, d.left), (cx2, r2, d.right)] {
+        let points = printpdf::utils::calculate_points_for_circle(Mm(r * scale), Mm(cx), Mm(cy));
+        layer.add_line(Line { points, is_closed: true });
+        let cross = |dx: f32, dy: f32| Line {
+            points: vec![(Point::new(Mm(cx - dx), Mm(cy - dy)), false), (Point::new(Mm(cx + dx), Mm(cy + dy)), false)],
+            is_closed: false,
+        };
+        layer.add_line(cross(2.0, 0.0));
+        layer.add_line(cross(0.0, 2.0));
+        layer.use_text(format!("{} T, d = {:.2} mm", teeth, 2.0 * r), 10.0, Mm(cx - 15.0), Mm(cy - r * scale - 8.0), &font);
+    }
+    if scale != 1.0 {
+        layer.use_text(format!("scale {:.2} : 1", scale), 9.0, Mm(20.0), Mm(15.0), &font);
+    }
+
+    let bytes = doc.save_to_bytes().map_err(|e| e.to_string())?;
+    std::fs::write(path, bytes).map_err(|e| e.to_string())
+}
+
+#[cfg(all(not(feature = "pdf"), not(target_arch = "wasm32")))]
+fn save_pdf(_design: &Design, _path: &str) -> Result<(), String> {
+    Err(String::from("built without the \"pdf\" feature"))
+}
+
+pub struct Report {
+    #[cfg(not(target_arch = "wasm32"))]
+    path: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    pdf_path: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    html_path: String,
+    include_search: bool,
+    status: String,
+}
+
+impl Report {
+    pub fn new() -> Report {
+        Report {
+            #[cfg(not(target_arch = "wasm32"))]
+            path: String::from("gears.md"),
+            #[cfg(not(target_arch = "wasm32"))]
+            pdf_path: String::from("gears.pdf"),
+            #[cfg(not(target_arch = "wasm32"))]
+            html_path: String::from("gears.html"),
+            include_search: true,
+            status: String::new(),
+        }
+    }
+
+    // search holds the results of the train search, empty if it didn't run. the attachments
+    // of the project only go into the HTML report.
+    pub fn ui(&mut self, ui: &mut egui::Ui, design: &Design, search: &[Vec<Candidate>], attachments: &[Attachment]) {
+        ui.add_enabled(!search.is_empty(), egui::Checkbox::new(&mut self.include_search, tr("Include the train search results")))
+            .on_disabled_hover_text(tr("run a train search first"));
+        let search = (self.include_search && !search.is_empty()).then_some(search);
+        let text = markdown(design, search);
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            ui.monospace(&text);
+        });
+        ui.separator();
+        ui.horizontal(|ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.add(egui::TextEdit::singleline(&mut self.path).desired_width(160.0));
+                if ui.button(tr("Save")).clicked() {
+                    self.status = match std::fs::write(&self.path, &text) {
+                        Ok(()) => format!("{} {}", tr("Saved"), self.path),
+                        Err(e) => format!("{} {}", tr("Saving failed:"), e),
+                    };
+                }
+            }
+            // no file system in the browser
+            if ui.button(tr("Copy")).clicked() {
+                ui.output_mut(|o| o.copied_text = text.clone());
+                self.status = tr("Copied to clipboard.").to_string();
+            }
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.pdf_path).desired_width(160.0));
+            if ui.add_enabled(pdf_available(), egui::Button::new(tr("Save PDF")))
+                .on_disabled_hover_text(tr("built without the \"pdf\" feature"))
+                .clicked()
+            {
+                self.status = match save_pdf(design, &self.pdf_path) {
+                    Ok(()) => format!("{} {}", tr("Saved"), self.pdf_path),
+                    Err(e) => format!("{} {}", tr("Saving failed:"), e),
+                };
+            }
+        });
+        ui.horizontal(|ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.add(egui::TextEdit::singleline(&mut self.html_path).desired_width(160.0));
+                if ui.button(tr("Save HTML")).clicked() {
+                    self.status = match std::fs::write(&self.html_path, html(design, search, attachments)) {
+                        Ok(()) => format!("{} {}", tr("Saved"), self.html_path),
+                        Err(e) => format!("{} {}", tr("Saving failed:"), e),
+                    };
+                }
+            }
+            if ui.button(tr("Copy HTML")).clicked() {
+                ui.output_mut(|o| o.copied_text = html(design, search, attachments));
+                self.status = tr("Copied to clipboard.").to_string();
+            }
+            ui.weak(format!("{} {}", tr("Attachments:"), attachments.len()));
+        });
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_embeds_images() {
+        let design = Design {
+            name: String::from("<Lathe> & co"),
+            left: 20,
+            right: 40,
+            ratio: RatioModel::target(2.0, 0.5),
+            module: 1.0,
+            center_distance: 30.0,
+            input_speed: 100.0,
+            output_speed: 50.0,
+        };
+        let png = Attachment::new(String::from("a.png"), b"\x89PNG\r\n\x1a\nxx".to_vec());
+        let notes = Attachment::new(String::from("notes.txt"), b"abc".to_vec());
+        let page = html(&design, None, &[png, notes]);
+        assert!(page.contains("<h1>&lt;Lathe&gt; &amp; co</h1>"));
+        assert!(page.contains(&format!("src=\"data:image/png;base64,{}\"", base64::encode(b"\x89PNG\r\n\x1a\nxx"))));
+        assert!(page.contains("<p>notes.txt (3 bytes)</p>"));
+        assert!(!page.contains("Train search"));
+    }
+}
@@ -0,0 +1,135 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * When a component of a drive is replaced (a bigger tire, a sprocket that isn't available
+ * any more), the output speed changes. This walks through the original setup and the
+ * changed parts and solves for the gear that restores the original output speed.
+ */
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Solve {
+    Driver,
+    Driven,
+}
+
+pub struct Reratio {
+    orig_driver: u32,
+    orig_driven: u32,
+    // diameter of the wheel (or drum, roller, ...) on the output shaft in mm
+    orig_wheel: f32,
+    new_wheel: f32,
+    // the gear that stays, can differ from the original if that one isn't available
+    kept_teeth: u32,
+    solve: Solve,
+}
+
+impl Reratio {
+    pub fn new() -> Reratio {
+        Reratio {
+            orig_driver: 15,
+            orig_driven: 45,
+            orig_wheel: 600.0,
+            new_wheel: 650.0,
+            kept_teeth: 15,
+            solve: Solve::Driven,
+        }
+    }
+
+    // output speed per input revolution, in mm
+    fn travel(driver: u32, driven: u32, wheel: f32) -> f32 {
+        wheel * std::f32::consts::PI * driver as f32 / driven as f32
+    }
+
+    // the solved gear, rounded to whole teeth
+    fn solved_teeth(&self) -> u32 {
+        self.exact_teeth().round().max(1.0) as u32
+    }
+
+    fn exact_teeth(&self) -> f32 {
+        // keep wheel * driver / driven constant
+        let k = self.orig_wheel * self.orig_driver as f32 / self.orig_driven as f32;
+        match self.solve {
+            Solve::Driven => self.new_wheel * self.kept_teeth as f32 / k,
+            Solve::Driver => k * self.kept_teeth as f32 / self.new_wheel,
+        }
+    }
+
+    fn pair(&self, solved: u32) -> (u32, u32) {
+        match self.solve {
+            Solve::Driven => (self.kept_teeth, solved),
+            Solve::Driver => (solved, self.kept_teeth),
+        }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        let (driver, driven) = self.pair(self.solved_teeth());
+        driven as f32 / driver as f32
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(egui::RichText::new(format!("1. {}", tr("Original setup"))).strong());
+        egui::Grid::new("reratio_orig").show(ui, |ui| {
+            ui.label(tr("Driver"));
+            ui.add(egui::DragValue::new(&mut self.orig_driver).clamp_range(1..=1000).suffix(" T"));
+            ui.end_row();
+            ui.label(tr("Driven"));
+            ui.add(egui::DragValue::new(&mut self.orig_driven).clamp_range(1..=1000).suffix(" T"));
+            ui.end_row();
+            ui.label(tr("Wheel diameter"));
+            ui.add(egui::DragValue::new(&mut self.orig_wheel).speed(1.0).clamp_range(1.0..=10000.0).suffix(" mm"));
+            ui.end_row();
+        });
+
+        ui.separator();
+        ui.label(egui::RichText::new(format!("2. {}", tr("What changed"))).strong());
+        egui::Grid::new("reratio_new").show(ui, |ui| {
+            ui.label(tr("New wheel diameter"));
+            ui.add(egui::DragValue::new(&mut self.new_wheel).speed(1.0).clamp_range(1.0..=10000.0).suffix(" mm"));
+            ui.end_row();
+            ui.label(tr("Gear to replace"));
+            ui.horizontal(|ui| {
+                let old = self.solve;
+                ui.radio_value(&mut self.solve, Solve::Driver, tr("Driver"));
+                ui.radio_value(&mut self.solve, Solve::Driven, tr("Driven"));
+                if old != self.solve {
+                    // start out with the gear that is kept from the original setup
+                    self.kept_teeth = match self.solve {
+                        Solve::Driven => self.orig_driver,
+                        Solve::Driver => self.orig_driven,
+                    };
+                }
+            });
+            ui.end_row();
+            ui.label(tr(match self.solve {
+                Solve::Driven => "Available driver",
+                Solve::Driver => "Available driven",
+            }));
+            ui.add(egui::DragValue::new(&mut self.kept_teeth).clamp_range(1..=1000).suffix(" T"));
+            ui.end_row();
+        });
+
+        ui.separator();
+        ui.label(egui::RichText::new(format!("3. {}", tr("Replacement"))).strong());
+        let exact = self.exact_teeth();
+        let orig = Reratio::travel(self.orig_driver, self.orig_driven, self.orig_wheel);
+        egui::Grid::new("reratio_result").striped(true).show(ui, |ui| {
+            ui.label(tr("Exact"));
+            ui.label(format!("{:.2} T", exact));
+            ui.end_row();
+            // both neighbours, the user may prefer erring to one side
+            for teeth in [exact.floor().max(1.0) as u32, exact.ceil().max(1.0) as u32] {
+                let (driver, driven) = self.pair(teeth);
+                let travel = Reratio::travel(driver, driven, self.new_wheel);
+                let label = egui::RichText::new(format!("{} T", teeth));
+                ui.label(if teeth == self.solved_teeth() { label.strong() } else { label });
+                ui.label(format!("{:+.2} % {}", (travel / orig - 1.0) * 100.0, tr("output speed")));
+                ui.end_row();
+                if exact.floor() == exact.ceil() {
+                    break;
+                }
+            }
+        });
+    }
+}
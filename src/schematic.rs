@@ -0,0 +1,119 @@
+use eframe::egui;
+
+use crate::gear_train::Element;
+use crate::i18n::tr;
+
+/*
+ * A schematic side view of a gear train. All shafts sit on one line, every gear is a circle
+ * with a diameter proportional to its teeth, so meshing gears touch at the mesh point. The
+ * driven gear of a stage and the driver of the next one share a shaft and are drawn on top
+ * of each other. Idlers have no tooth count of their own, they get the size of the smaller
+ * gear of their stage. Gearboxes are boxes with the input and output shaft on the line.
+ */
+
+const HEIGHT: f32 = 220.0;
+// gearbox length in teeth
+const BOX_LENGTH: f32 = 24.0;
+
+enum Part {
+    Gear { x: f32, teeth: u32, idler: bool },
+    // the touching point of two gears
+    Mesh { x: f32 },
+    // stage ratio, written above the stage
+    Ratio { x: f32, ratio: f32 },
+    Gearbox { x: f32, name: String, ratio: f32 },
+}
+
+fn radius(teeth: u32) -> f32 {
+    teeth as f32 / 2.0
+}
+
+// the parts along the shaft line, in teeth, and the width they take
+fn layout(elements: &[Element]) -> (Vec<Part>, f32) {
+    let mut parts = Vec::new();
+    // position of the current shaft and the radius of the widest thing on the left of it
+    let mut x = 0.0;
+    let mut left = 0.0_f32;
+    for e in elements {
+        match e {
+            Element::Gears(s, _) => {
+                let start = x;
+                left = left.max(radius(s.driver) - x);
+                parts.push(Part::Gear { x, teeth: s.driver, idler: false });
+                let mut r = radius(s.driver);
+                let idler = s.driver.min(s.driven);
+                for _ in 0..s.idlers {
+                    parts.push(Part::Mesh { x: x + r });
+                    x += r + radius(idler);
+                    r = radius(idler);
+                    parts.push(Part::Gear { x, teeth: idler, idler: true });
+                }
+                parts.push(Part::Mesh { x: x + r });
+                x += r + radius(s.driven);
+                parts.push(Part::Gear { x, teeth: s.driven, idler: false });
+                parts.push(Part::Ratio { x: (start + x) / 2.0, ratio: s.ratio() });
+            }
+            Element::Gearbox { name, ratio, .. } => {
+                parts.push(Part::Gearbox { x, name: name.clone(), ratio: *ratio });
+                x += BOX_LENGTH;
+            }
+        }
+    }
+    // the right edge: the last gear or the end of the last gearbox
+    let right = parts.iter().map(|p| match p {
+        Part::Gear { x, teeth, .. } => x + radius(*teeth),
+        Part::Gearbox { x, .. } => x + BOX_LENGTH,
+        _ => 0.0,
+    }).fold(0.0_f32, f32::max);
+    for p in &mut parts {
+        match p {
+            Part::Gear { x, .. } | Part::Mesh { x } | Part::Ratio { x, .. } | Part::Gearbox { x, .. } => *x += left,
+        }
+    }
+    (parts, left + right)
+}
+
+pub fn train_schematic(ui: &mut egui::Ui, elements: &[Element]) {
+    let (parts, width) = layout(elements);
+    let largest = parts.iter().map(|p| match p {
+        Part::Gear { teeth, .. } => radius(*teeth),
+        _ => 0.0,
+    }).fold(BOX_LENGTH / 2.0, f32::max);
+
+    let (response, painter) = ui.allocate_painter(egui::vec2(ui.available_width(), HEIGHT), egui::Sense::hover());
+    let rect = response.rect.shrink(4.0);
+    // room for the ratios above
+    let scale = (rect.width() / width.max(1.0)).min((rect.height() - 40.0) / (2.0 * largest));
+    let line = rect.center().y + 10.0;
+    let at = |x: f32| egui::pos2(rect.left() + x * scale, line);
+
+    let visuals = ui.visuals();
+    let stroke = egui::Stroke::new(1.5_f32, visuals.text_color());
+    let weak = egui::Stroke::new(1.0_f32, visuals.weak_text_color());
+    let font = egui::FontId::proportional(12.0);
+
+    painter.line_segment([egui::pos2(rect.left(), line), egui::pos2(rect.right(), line)], weak);
+    for p in &parts {
+        match p {
+            Part::Gear { x, teeth, idler } => {
+                painter.circle_stroke(at(*x), radius(*teeth) * scale, if *idler { weak } else { stroke });
+                painter.circle_filled(at(*x), 2.5, visuals.text_color());
+                let label = if *idler { tr("idler").to_string() } else { format!("{}", teeth) };
+                painter.text(at(*x) - egui::vec2(0.0, radius(*teeth) * scale * 0.5), egui::Align2::CENTER_CENTER, label, font.clone(), visuals.text_color());
+            }
+            Part::Mesh { x } => {
+                painter.circle_filled(at(*x), 3.0, visuals.warn_fg_color);
+            }
+            Part::Ratio { x, ratio } => {
+                painter.text(egui::pos2(at(*x).x, rect.top()), egui::Align2::CENTER_TOP, format!("{:.3}", ratio), font.clone(), visuals.strong_text_color());
+            }
+            Part::Gearbox { x, name, ratio } => {
+                let size = egui::vec2(BOX_LENGTH * scale, BOX_LENGTH * scale);
+                let r = egui::Rect::from_min_size(at(*x) - egui::vec2(0.0, size.y / 2.0), size);
+                painter.rect_stroke(r, 2.0, stroke);
+                painter.text(r.center(), egui::Align2::CENTER_CENTER, name, font.clone(), visuals.text_color());
+                painter.text(egui::pos2(r.center().x, rect.top()), egui::Align2::CENTER_TOP, format!("{:.3}", ratio), font.clone(), visuals.strong_text_color());
+            }
+        }
+    }
+}
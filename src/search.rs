@@ -0,0 +1,552 @@
+use eframe::egui;
+
+use crate::band::Band;
+use crate::i18n::tr;
+use crate::train::{Stage, Train};
+use crate::worker::{self, Job, Progress};
+
+/*
+ * Searching for gear trains that approximate a target ratio.
+ * A single pair can only hit ratios of small fractions, more stages get closer but cost
+ * space and efficiency. The search keeps the best trains for every stage count separately,
+ * so the results can be compared side by side.
+ *
+ * Instead of enumerating every combination of gears, all distinct single stage ratios are
+ * sorted once. For a train of n stages, the first n - 1 stages are enumerated and the last
+ * one is looked up by binary search for the ratio that is still missing.
+ */
+
+pub const MAX_STAGES: usize = 3;
+
+// what makes one train better than another. Apart from Error, trains inside the tolerance
+// are ranked by size and the ones outside by error after them.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Objective {
+    Error,
+    TotalTeeth,
+    LargestGear,
+    CenterDistance,
+}
+
+impl Objective {
+    pub const ALL: [Objective; 4] = [Objective::Error, Objective::TotalTeeth, Objective::LargestGear, Objective::CenterDistance];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Objective::Error => "Smallest error",
+            Objective::TotalTeeth => "Fewest teeth",
+            Objective::LargestGear => "Smallest largest gear",
+            Objective::CenterDistance => "Shortest center distance",
+        }
+    }
+
+    // the column showing the size
+    pub fn measure(self) -> &'static str {
+        match self {
+            Objective::Error | Objective::TotalTeeth => "Total teeth",
+            Objective::LargestGear => "Largest gear",
+            Objective::CenterDistance => "Widest stage",
+        }
+    }
+
+    // size of a train, in teeth. The center distance of a stage is half its tooth sum
+    // times the module, so the widest stage is the one with the most teeth.
+    pub fn size(self, stages: &[Stage]) -> u32 {
+        match self {
+            Objective::Error | Objective::TotalTeeth => stages.iter().map(|s| s.driver + s.driven).sum(),
+            Objective::LargestGear => stages.iter().map(|s| s.driver.max(s.driven)).max().unwrap_or(0),
+            Objective::CenterDistance => stages.iter().map(|s| s.driver + s.driven).max().unwrap_or(0),
+        }
+    }
+}
+
+// a housing that is already there limits the center distance of every stage
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CenterLimit {
+    Free,
+    Fixed,
+    AtMost,
+}
+
+impl CenterLimit {
+    pub const ALL: [CenterLimit; 3] = [CenterLimit::Free, CenterLimit::Fixed, CenterLimit::AtMost];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CenterLimit::Free => "Any",
+            CenterLimit::Fixed => "Fixed",
+            CenterLimit::AtMost => "At most",
+        }
+    }
+}
+
+// how far off a fixed center distance may be, in mm
+const CENTER_SLACK: f64 = 0.01;
+
+#[derive(Debug, Clone)]
+pub struct SearchParams {
+    pub target: f64,
+    pub min_teeth: u32,
+    pub max_teeth: u32,
+    // a separate range for the driven gears, like the pinions of a clock
+    pub driven_teeth: Option<(u32, u32)>,
+    pub max_stages: usize,
+    // how many trains are kept per stage count
+    pub top_n: usize,
+    pub objective: Objective,
+    // relative error that is good enough when optimizing for size
+    pub tolerance: f64,
+    pub center_limit: CenterLimit,
+    // in mm
+    pub center_distance: f64,
+    pub module: f64,
+}
+
+impl SearchParams {
+    // whether a stage fits the center distance limit
+    pub fn fits(&self, driver: u32, driven: u32) -> bool {
+        let a = (driver + driven) as f64 * self.module / 2.0;
+        match self.center_limit {
+            CenterLimit::Free => true,
+            CenterLimit::Fixed => (a - self.center_distance).abs() <= CENTER_SLACK,
+            CenterLimit::AtMost => a <= self.center_distance + CENTER_SLACK,
+        }
+    }
+
+    // the tooth sum a fixed center distance needs, if it is a whole number
+    pub fn tooth_sum(&self) -> Option<u32> {
+        let sum = 2.0 * self.center_distance / self.module;
+        ((sum - sum.round()).abs() * self.module / 2.0 <= CENTER_SLACK).then_some(sum.round() as u32)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub train: Train,
+    pub ratio: f64,
+    // relative error to the target
+    pub error: f64,
+}
+
+impl Candidate {
+    pub fn total_teeth(&self) -> u32 {
+        self.train.stages.iter().map(|s| s.driver + s.driven).sum()
+    }
+}
+
+pub fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+// all distinct stage ratios in the tooth ranges that `fits` allows, smallest gears for each
+// ratio, sorted by ratio
+fn stage_table(driver_teeth: (u32, u32), driven_teeth: (u32, u32), fits: impl Fn(u32, u32) -> bool) -> Vec<(f64, Stage)> {
+    let mut table: Vec<(f64, Stage)> = Vec::new();
+    for driver in driver_teeth.0..=driver_teeth.1 {
+        for driven in driven_teeth.0..=driven_teeth.1 {
+            if !fits(driver, driven) {
+                continue;
+            }
+            // a reduced fraction that can be scaled into the range is already there,
+            // with smaller gears
+            let g = gcd(driver, driven);
+            if g > 1 && driver / g >= driver_teeth.0 && driven / g >= driven_teeth.0 && fits(driver / g, driven / g) {
+                continue;
+            }
+            table.push((driven as f64 / driver as f64, Stage::new(driver, driven)));
+        }
+    }
+    table.sort_by(|a, b| a.0.total_cmp(&b.0));
+    table
+}
+
+// the best candidates seen so far, by error and size as the objective says
+struct TopN {
+    n: usize,
+    objective: Objective,
+    tolerance: f64,
+    items: Vec<Candidate>,
+}
+
+impl TopN {
+    fn new(params: &SearchParams) -> TopN {
+        TopN {
+            n: params.top_n,
+            objective: params.objective,
+            tolerance: params.tolerance,
+            items: Vec::with_capacity(params.top_n + 1),
+        }
+    }
+
+    // smaller is better
+    fn key(&self, stages: &[Stage], error: f64) -> (bool, f64, f64) {
+        let size = self.objective.size(stages) as f64;
+        if self.objective == Objective::Error || error > self.tolerance {
+            (self.objective != Objective::Error, error, size)
+        } else {
+            (false, size, error)
+        }
+    }
+
+    fn worst_key(&self) -> (bool, f64, f64) {
+        match self.items.get(self.n - 1) {
+            Some(c) => self.key(&c.train.stages, c.error),
+            None => (true, f64::INFINITY, f64::INFINITY),
+        }
+    }
+
+    fn offer(&mut self, stages: &[Stage], ratio: f64, target: f64) {
+        let error = (ratio - target).abs() / target;
+        if self.key(stages, error) > self.worst_key() {
+            return;
+        }
+        self.insert(Candidate { train: Train::new(stages), ratio, error });
+    }
+
+    fn insert(&mut self, c: Candidate) {
+        let key = self.key(&c.train.stages, c.error);
+        let pos = self.items.iter()
+            .position(|i| key < self.key(&i.train.stages, i.error))
+            .unwrap_or(self.items.len());
+        self.items.insert(pos, c);
+        self.items.truncate(self.n);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn merge(mut self, other: TopN) -> TopN {
+        for c in other.items {
+            if self.key(&c.train.stages, c.error) <= self.worst_key() {
+                self.insert(c);
+            }
+        }
+        self
+    }
+}
+
+// runs `row` for every row of the stage table and merges the best trains. The rows are
+// spread over all cores, in the browser they run one after the other. `progress` gets the
+// number of finished rows and returns false to cancel.
+#[cfg(not(target_arch = "wasm32"))]
+fn scan(rows: usize, params: &SearchParams, progress: impl Fn(usize) -> bool + Sync, row: impl Fn(usize, &mut TopN) + Sync) -> Option<TopN> {
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let finished = AtomicUsize::new(0);
+    (0..rows).into_par_iter()
+        .try_fold(|| TopN::new(params), |mut top, i| {
+            row(i, &mut top);
+            progress(finished.fetch_add(1, Ordering::Relaxed) + 1).then_some(top)
+        })
+        .try_reduce(|| TopN::new(params), |a, b| Some(a.merge(b)))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn scan(rows: usize, params: &SearchParams, progress: impl Fn(usize) -> bool + Sync, row: impl Fn(usize, &mut TopN) + Sync) -> Option<TopN> {
+    let mut top = TopN::new(params);
+    for i in 0..rows {
+        row(i, &mut top);
+        if !progress(i + 1) {
+            return None;
+        }
+    }
+    Some(top)
+}
+
+// index of the entries around the ratio in the sorted table, and all within the relative
+// tolerance of it
+fn neighbours(table: &[(f64, Stage)], ratio: f64, tolerance: f64) -> std::ops::Range<usize> {
+    let i = table.partition_point(|e| e.0 < ratio);
+    let lo = table.partition_point(|e| e.0 < ratio * (1.0 - tolerance));
+    let hi = table.partition_point(|e| e.0 <= ratio * (1.0 + tolerance));
+    lo.min(i.saturating_sub(1))..hi.max((i + 1).min(table.len()))
+}
+
+// best trains for every stage count from 1 to max_stages, None if cancelled
+pub fn search(params: &SearchParams, progress: &Progress) -> Option<Vec<Vec<Candidate>>> {
+    let driver_teeth = (params.min_teeth, params.max_teeth);
+    let table = stage_table(driver_teeth, params.driven_teeth.unwrap_or(driver_teeth), |a, b| params.fits(a, b));
+    let target = params.target;
+    let mut results = Vec::new();
+    if table.is_empty() || target <= 0.0 {
+        return Some(results);
+    }
+
+    let max_stages = params.max_stages.min(MAX_STAGES);
+    // only trains near the best ratio matter when optimizing for error
+    let tolerance = if params.objective == Objective::Error { 0.0 } else { params.tolerance };
+    for stages in 1..=max_stages {
+        let part = progress.part(stages - 1, max_stages);
+        let rows = |done: usize| part.report(done as f32 / table.len() as f32);
+        let top = match stages {
+            1 => {
+                let mut top = TopN::new(params);
+                for (r, s) in &table {
+                    top.offer(&[*s], *r, target);
+                }
+                top
+            }
+            2 => scan(table.len(), params, rows, |i, top| {
+                let (r1, s1) = table[i];
+                for j in neighbours(&table, target / r1, tolerance) {
+                    // the same pair in the other order
+                    if j < i {
+                        continue;
+                    }
+                    let (r2, s2) = table[j];
+                    top.offer(&[s1, s2], r1 * r2, target);
+                }
+            })?,
+            _ => scan(table.len(), params, rows, |i, top| {
+                let (r1, s1) = table[i];
+                for (j, (r2, s2)) in table.iter().enumerate().skip(i) {
+                    for k in neighbours(&table, target / (r1 * r2), tolerance) {
+                        if k < j {
+                            continue;
+                        }
+                        let (r3, s3) = table[k];
+                        top.offer(&[s1, *s2, s3], r1 * r2 * r3, target);
+                    }
+                }
+            })?,
+        };
+        results.push(top.items);
+    }
+    Some(results)
+}
+
+// two stage trains within the relative tolerance of the target, fewest teeth first
+pub fn compound_pairs(target: f64, min_teeth: u32, max_teeth: u32, tolerance: f64, limit: usize, progress: &Progress) -> Option<Vec<Candidate>> {
+    let table = stage_table((min_teeth, max_teeth), (min_teeth, max_teeth), |_, _| true);
+    let mut found = Vec::new();
+    for (i, (r1, s1)) in table.iter().enumerate() {
+        if i % 256 == 0 && !progress.report(i as f32 / table.len() as f32) {
+            return None;
+        }
+        let lo = table.partition_point(|e| e.0 * r1 < target * (1.0 - tolerance) - 1e-12);
+        let hi = table.partition_point(|e| e.0 * r1 <= target * (1.0 + tolerance) + 1e-12);
+        for (r2, s2) in &table[lo.max(i)..hi.max(i)] {
+            let ratio = r1 * r2;
+            found.push(Candidate { train: Train::new(&[*s1, *s2]), ratio, error: (ratio - target).abs() / target });
+        }
+    }
+    found.sort_by(|a, b| a.total_teeth().cmp(&b.total_teeth()).then(a.error.total_cmp(&b.error)));
+    found.truncate(limit);
+    Some(found)
+}
+
+pub fn describe(train: &Train) -> String {
+    train.stages.iter()
+        .map(|s| format!("{}/{}", s.driven, s.driver))
+        .collect::<Vec<_>>()
+        .join(" × ")
+}
+
+pub struct TrainSearch {
+    params: SearchParams,
+    results: Vec<Vec<Candidate>>,
+    job: Option<Job<Vec<Vec<Candidate>>>>,
+}
+
+impl TrainSearch {
+    pub fn new() -> TrainSearch {
+        TrainSearch {
+            params: SearchParams {
+                target: 7.5,
+                min_teeth: 10,
+                max_teeth: 60,
+                driven_teeth: None,
+                max_stages: 2,
+                top_n: 5,
+                objective: Objective::Error,
+                tolerance: 0.0,
+                center_limit: CenterLimit::Free,
+                center_distance: 50.0,
+                module: 1.0,
+            },
+            results: Vec::new(),
+            job: None,
+        }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.params.target as f32
+    }
+
+    // of the last search, one list per stage count
+    pub fn results(&self) -> &[Vec<Candidate>] {
+        &self.results
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, band: &Band) {
+        let p = &mut self.params;
+        egui::Grid::new("search_params").show(ui, |ui| {
+            ui.label(tr("Target ratio"));
+            ui.add(egui::DragValue::new(&mut p.target).speed(0.01).clamp_range(0.001..=10000.0).max_decimals(6));
+            ui.end_row();
+            ui.label(tr("Teeth per gear"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut p.min_teeth).clamp_range(4..=p.max_teeth));
+                ui.label(tr("to"));
+                ui.add(egui::DragValue::new(&mut p.max_teeth).clamp_range(p.min_teeth..=200));
+            });
+            ui.end_row();
+            ui.label(tr("Stages"));
+            ui.add(egui::Slider::new(&mut p.max_stages, 1..=MAX_STAGES).text(tr("at most")));
+            ui.end_row();
+            ui.label(tr("Results per stage count"));
+            ui.add(egui::DragValue::new(&mut p.top_n).clamp_range(1..=50));
+            ui.end_row();
+            ui.label(tr("Center distance"));
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("search_center_limit")
+                    .selected_text(tr(p.center_limit.name()))
+                    .show_ui(ui, |ui| {
+                        for c in CenterLimit::ALL {
+                            ui.selectable_value(&mut p.center_limit, c, tr(c.name()));
+                        }
+                    });
+                if p.center_limit != CenterLimit::Free {
+                    ui.add(egui::DragValue::new(&mut p.center_distance).speed(0.1).clamp_range(1.0..=10000.0).max_decimals(3).suffix(" mm"));
+                    ui.label(tr("module"));
+                    ui.add(egui::DragValue::new(&mut p.module).speed(0.01).clamp_range(0.05..=50.0).max_decimals(3).suffix(" mm"));
+                }
+            });
+            ui.end_row();
+            ui.label(tr("Optimize for"));
+            egui::ComboBox::from_id_source("search_objective")
+                .selected_text(tr(p.objective.name()))
+                .show_ui(ui, |ui| {
+                    for o in Objective::ALL {
+                        ui.selectable_value(&mut p.objective, o, tr(o.name()));
+                    }
+                });
+            ui.end_row();
+        });
+        if p.center_limit == CenterLimit::Fixed {
+            match p.tooth_sum() {
+                Some(sum) => ui.label(format!("{} {}.", tr("Teeth of every stage together:"), sum)),
+                None => ui.colored_label(ui.visuals().warn_fg_color, tr("No whole tooth sum gives this center distance with this module.")),
+            };
+        }
+        if p.objective != Objective::Error {
+            ui.label(format!("{} ±{} % ({}).", tr("Among the trains within"), band.percent, tr("Tolerance menu")));
+        }
+        if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Search"))).clicked() {
+            self.params.tolerance = band.percent / 100.0;
+            let params = self.params.clone();
+            self.job = Some(Job::spawn(ui.ctx(), move |p| search(&params, p)));
+        }
+        if let Some(r) = worker::job_ui(ui, &mut self.job) {
+            self.results = r;
+        }
+
+        if self.results.is_empty() {
+            return;
+        }
+        ui.separator();
+
+        let objective = self.params.objective;
+        // the overview: what does another stage buy?
+        egui::Grid::new("search_summary").striped(true).show(ui, |ui| {
+            ui.label(egui::RichText::new(tr("Stages")).strong());
+            ui.label(egui::RichText::new(tr("Best train")).strong());
+            ui.label(egui::RichText::new(tr("Error")).strong());
+            ui.label(egui::RichText::new(tr(objective.measure())).strong());
+            ui.end_row();
+            for (i, group) in self.results.iter().enumerate() {
+                ui.label(format!("{}", i + 1));
+                match group.first() {
+                    Some(c) => {
+                        ui.label(describe(&c.train));
+                        ui.label(format!("{:.4} %", c.error * 100.0));
+                        ui.label(format!("{}", objective.size(&c.train.stages)));
+                        band.mark(ui, c.error);
+                    }
+                    None => {
+                        ui.label("-");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, group) in self.results.iter().enumerate() {
+                egui::CollapsingHeader::new(format!("{} {}", tr("Best trains with stages:"), i + 1))
+                    .id_source(("search_group", i))
+                    .show(ui, |ui| {
+                        if !group.iter().any(|c| band.shows(c.error)) {
+                            band.empty_note(ui);
+                        }
+                        egui::Grid::new(("search_results", i)).striped(true).show(ui, |ui| {
+                            for c in group.iter().filter(|c| band.shows(c.error)) {
+                                ui.label(describe(&c.train));
+                                ui.label(format!("{:.6}", c.ratio));
+                                ui.label(format!("{:.4} %", c.error * 100.0));
+                                ui.label(format!("{} T", objective.size(&c.train.stages)));
+                                band.mark(ui, c.error);
+                                ui.end_row();
+                            }
+                        });
+                    });
+            }
+        });
+    }
+}
+
+// compound trains for the gear pair, when whole teeth can't hit the given ratio
+pub struct CompoundSearch {
+    min_teeth: u32,
+    max_teeth: u32,
+    results: Vec<Candidate>,
+    // the ratio the results are for
+    searched: f64,
+    job: Option<Job<(f64, Vec<Candidate>)>>,
+}
+
+impl CompoundSearch {
+    pub fn new() -> CompoundSearch {
+        CompoundSearch {
+            min_teeth: 10,
+            max_teeth: 80,
+            results: Vec::new(),
+            searched: 0.0,
+            job: None,
+        }
+    }
+
+    // finds the trains within the band, a band of 0 for exact hits only
+    pub fn ui(&mut self, ui: &mut egui::Ui, target: f64, band: &Band) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Teeth per gear"));
+            ui.add(egui::DragValue::new(&mut self.min_teeth).clamp_range(4..=self.max_teeth));
+            ui.label(tr("to"));
+            ui.add(egui::DragValue::new(&mut self.max_teeth).clamp_range(self.min_teeth..=200));
+            if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Search compound pairs"))).clicked() {
+                let (min, max, tolerance) = (self.min_teeth, self.max_teeth, band.percent / 100.0);
+                self.job = Some(Job::spawn(ui.ctx(), move |p| {
+                    compound_pairs(target, min, max, tolerance, 20, p).map(|found| (target, found))
+                }));
+            }
+        });
+        if let Some((searched, results)) = worker::job_ui(ui, &mut self.job) {
+            self.searched = searched;
+            self.results = results;
+        }
+        if self.searched != target {
+            return;
+        }
+        if self.results.is_empty() {
+            band.empty_note(ui);
+        }
+        egui::Grid::new("compound_results").striped(true).show(ui, |ui| {
+            for c in &self.results {
+                ui.label(describe(&c.train));
+                ui.label(format!("{:.6}", c.ratio));
+                ui.label(if c.error < 1e-12 { tr("exact").to_string() } else { format!("{:.4} %", c.error * 100.0) });
+                ui.label(format!("{} T", c.total_teeth()));
+                ui.end_row();
+            }
+        });
+    }
+}
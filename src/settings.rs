@@ -0,0 +1,443 @@
+use eframe::egui;
+
+use crate::constraint::Quantity;
+use crate::feedback::Feedback;
+use crate::i18n::{tr, Language};
+use crate::spinner::{self, NumberSpinner, Sensitivity, SpinnerValue};
+use crate::strength::{Material, DEFAULT_MATERIALS};
+
+/*
+ * User settings, saved with the app state. They are stored as "key=value" lines, keys that
+ * aren't known anymore are skipped and missing ones keep their default.
+ *
+ * Lengths are always calculated in mm, the units only change what is shown and typed.
+ * Imperial gears are specified by diametral pitch, teeth per inch of pitch diameter,
+ * instead of the module.
+ *
+ * The UI language and whether numbers are written with a decimal comma start out from the
+ * system language.
+ *
+ * The materials are one "material=name;allowable stress;density" line each. A
+ * save with any of them replaces the whole table, one without keeps the defaults.
+ */
+
+// languages that write 1,5
+const COMMA_LANGUAGES: [&str; 16] = ["bg", "cs", "da", "de", "es", "fi", "fr", "it", "nb", "nl", "pl", "pt", "ru", "sv", "tr", "uk"];
+
+#[cfg(not(target_arch = "wasm32"))]
+fn system_language() -> Option<String> {
+    ["LC_ALL", "LC_NUMERIC", "LANG"].iter().find_map(|v| std::env::var(v).ok().filter(|l| !l.is_empty()))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn system_language() -> Option<String> {
+    web_sys::window()?.navigator().language()
+}
+
+fn system_decimal_comma() -> bool {
+    system_language().is_some_and(|l| COMMA_LANGUAGES.contains(&l.get(..2).unwrap_or("")))
+}
+
+fn system_ui_language() -> Language {
+    system_language().and_then(|l| Language::from_key(l.get(..2)?)).unwrap_or(Language::English)
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Units {
+    Metric,
+    Imperial,
+}
+
+impl Units {
+    pub fn name(self) -> &'static str {
+        match self {
+            Units::Metric => "Metric (module, mm)",
+            Units::Imperial => "Imperial (diametral pitch, in)",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Units::Metric => "metric",
+            Units::Imperial => "imperial",
+        }
+    }
+
+    pub fn pitch_name(self) -> &'static str {
+        match self {
+            Units::Metric => "Module",
+            Units::Imperial => "Diametral pitch",
+        }
+    }
+
+    pub fn length_suffix(self) -> &'static str {
+        match self {
+            Units::Metric => " mm",
+            Units::Imperial => " in",
+        }
+    }
+
+    // a quantity of the gear pair as shown
+    pub fn show(self, q: Quantity, value: f32) -> f32 {
+        match (self, q) {
+            (Units::Imperial, Quantity::Module) => 25.4 / value,
+            (Units::Imperial, Quantity::CenterDistance) => value / 25.4,
+            _ => value,
+        }
+    }
+
+    // the other way round
+    pub fn store(self, q: Quantity, shown: f32) -> f32 {
+        match (self, q) {
+            (Units::Imperial, Quantity::Module) => 25.4 / shown,
+            (Units::Imperial, Quantity::CenterDistance) => shown * 25.4,
+            _ => shown,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Theme {
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    const ALL: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::System => "Follow system",
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Theme::System => "system",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+
+    // whether to use the dark visuals, None leaves the choice to eframe
+    pub fn dark(self, system: Option<eframe::Theme>) -> Option<bool> {
+        match self {
+            Theme::System => system.map(|t| t == eframe::Theme::Dark),
+            Theme::Light => Some(false),
+            Theme::Dark => Some(true),
+        }
+    }
+}
+
+pub struct Settings {
+    // spinner steps of the gear pair columns
+    pub teeth_step: u32,
+    pub ratio_step: f64,
+    pub sensitivity: Sensitivity,
+    pub min_teeth: u32,
+    pub max_teeth: u32,
+    // preview values shown above and below the spinner values
+    pub preview: usize,
+    // decimals of the actual ratio
+    pub decimals: usize,
+    pub decimal_comma: bool,
+    pub units: Units,
+    pub language: Language,
+    pub theme: Theme,
+    // what new calculations start with
+    pub default_teeth: u32,
+    pub default_ratio: f64,
+    // for the strength check
+    pub materials: Vec<Material>,
+    // click sounds and vibration
+    pub feedback: Feedback,
+}
+
+fn default_materials() -> Vec<Material> {
+    DEFAULT_MATERIALS.iter().map(|&(name, allowable, density)| Material { name: name.to_string(), allowable, density }).collect()
+}
+
+impl Settings {
+    pub fn new() -> Settings {
+        Settings {
+            teeth_step: 1,
+            ratio_step: 0.1,
+            sensitivity: Sensitivity::default(),
+            min_teeth: 1,
+            max_teeth: 100000,
+            preview: 2,
+            decimals: 3,
+            decimal_comma: system_decimal_comma(),
+            units: Units::Metric,
+            language: system_ui_language(),
+            theme: Theme::System,
+            default_teeth: 10,
+            default_ratio: 1.5,
+            materials: default_materials(),
+            feedback: Feedback::new(),
+        }
+    }
+
+    // a spinner with the preview rows and sensitivity set up
+    pub fn spinner<'a, T: SpinnerValue>(&self, value: &'a mut T) -> NumberSpinner<'a, T> {
+        NumberSpinner::new(value).preview(self.preview).sensitivity(self.sensitivity).decimal_comma(self.decimal_comma)
+    }
+
+    // a number with the chosen decimal separator
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        spinner::format_value(value, decimals, self.decimal_comma)
+    }
+
+    pub fn to_text(&self) -> String {
+        [
+            format!("teeth_step={}", self.teeth_step),
+            format!("ratio_step={}", self.ratio_step),
+            format!("drag_detent={}", self.sensitivity.drag),
+            format!("scroll_detent={}", self.sensitivity.scroll),
+            format!("invert_scroll={}", self.sensitivity.invert_scroll),
+            format!("min_teeth={}", self.min_teeth),
+            format!("max_teeth={}", self.max_teeth),
+            format!("preview={}", self.preview),
+            format!("decimals={}", self.decimals),
+            format!("decimal_comma={}", self.decimal_comma),
+            format!("units={}", self.units.key()),
+            format!("language={}", self.language.key()),
+            format!("theme={}", self.theme.key()),
+            format!("default_teeth={}", self.default_teeth),
+            format!("default_ratio={}", self.default_ratio),
+            format!("sound={}", self.feedback.sound),
+            format!("haptics={}", self.feedback.haptics),
+        ].into_iter()
+            .chain(self.materials.iter().map(|m| format!("material={};{};{}", m.name, m.allowable, m.density)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // only the lines that differ from the defaults, so changed defaults still apply to what
+    // the user didn't change. the materials are all saved or none.
+    pub fn changed_text(&self, defaults: &Settings) -> String {
+        let (text, default) = (self.to_text(), defaults.to_text());
+        let materials = |t: &str| t.lines().filter(|l| l.starts_with("material=")).map(String::from).collect::<Vec<_>>();
+        let same_materials = materials(&text) == materials(&default);
+        text.lines()
+            .filter(|l| if l.starts_with("material=") { !same_materials } else { !default.lines().any(|d| d == *l) })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn load_text(&mut self, text: &str) {
+        fn set<T: std::str::FromStr>(field: &mut T, value: &str) {
+            if let Ok(v) = value.parse() {
+                *field = v;
+            }
+        }
+        let mut materials = Vec::new();
+        for (key, value) in text.lines().filter_map(|l| l.split_once('=')) {
+            let value = value.trim();
+            match key.trim() {
+                "teeth_step" => set(&mut self.teeth_step, value),
+                "ratio_step" => set(&mut self.ratio_step, value),
+                "drag_detent" => set(&mut self.sensitivity.drag, value),
+                "scroll_detent" => set(&mut self.sensitivity.scroll, value),
+                "invert_scroll" => set(&mut self.sensitivity.invert_scroll, value),
+                "min_teeth" => set(&mut self.min_teeth, value),
+                "max_teeth" => set(&mut self.max_teeth, value),
+                "preview" => set(&mut self.preview, value),
+                "decimals" => set(&mut self.decimals, value),
+                "decimal_comma" => set(&mut self.decimal_comma, value),
+                "units" => self.units = if value == Units::Imperial.key() { Units::Imperial } else { Units::Metric },
+                "theme" => self.theme = Theme::ALL.into_iter().find(|t| t.key() == value).unwrap_or(self.theme),
+                "language" => self.language = Language::from_key(value).unwrap_or(self.language),
+                "default_teeth" => set(&mut self.default_teeth, value),
+                "default_ratio" => set(&mut self.default_ratio, value),
+                "sound" => set(&mut self.feedback.sound, value),
+                "haptics" => set(&mut self.feedback.haptics, value),
+                "material" => {
+                    let parsed = value.rsplit_once(';').and_then(|(rest, density)| {
+                        let (name, allowable) = rest.rsplit_once(';')?;
+                        Some(Material {
+                            name: name.to_string(),
+                            allowable: allowable.parse().ok()?,
+                            density: density.parse().ok()?,
+                        })
+                    });
+                    materials.extend(parsed);
+                }
+                _ => {}
+            }
+        }
+        if !materials.is_empty() {
+            self.materials = materials;
+        }
+        // the ranges the settings window allows, even from an edited save
+        self.teeth_step = self.teeth_step.clamp(1, 100);
+        if !self.ratio_step.is_finite() {
+            self.ratio_step = 0.1;
+        }
+        self.ratio_step = self.ratio_step.clamp(0.001, 10.0);
+        self.preview = self.preview.min(5);
+        // no gear without teeth
+        self.min_teeth = self.min_teeth.max(1);
+        self.max_teeth = self.max_teeth.max(self.min_teeth);
+        self.default_teeth = self.default_teeth.max(1);
+        if !self.default_ratio.is_finite() || self.default_ratio <= 0.0 {
+            self.default_ratio = 1.5;
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(egui::RichText::new(tr("Spinners")).strong());
+        egui::Grid::new("settings_spinners").show(ui, |ui| {
+            ui.label(tr("Teeth step"));
+            ui.add(egui::DragValue::new(&mut self.teeth_step).clamp_range(1..=100));
+            ui.end_row();
+            ui.label(tr("Ratio step"));
+            ui.add(egui::DragValue::new(&mut self.ratio_step).speed(0.001).clamp_range(0.001..=10.0).max_decimals(3));
+            ui.end_row();
+            ui.label(tr("Dragging"));
+            ui.add(egui::DragValue::new(&mut self.sensitivity.drag).speed(0.5).clamp_range(2.0..=200.0).suffix(format!(" {}", tr("px per step"))));
+            ui.end_row();
+            ui.label(tr("Scrolling"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.sensitivity.scroll).speed(0.5).clamp_range(1.0..=500.0).suffix(format!(" {}", tr("px per step"))));
+                ui.checkbox(&mut self.sensitivity.invert_scroll, tr("Invert"));
+            });
+            ui.end_row();
+            ui.label(tr("Teeth"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.min_teeth).clamp_range(1..=self.max_teeth));
+                ui.label(tr("to"));
+                ui.add(egui::DragValue::new(&mut self.max_teeth).clamp_range(self.min_teeth..=100000));
+            });
+            ui.end_row();
+        });
+        ui.weak(tr("Holding shift takes finer steps, ctrl ten steps at once."));
+        ui.weak(tr("Tab goes from field to field, F1, F2 and F3 move the lock."));
+
+        ui.separator();
+        ui.label(egui::RichText::new(tr("Display")).strong());
+        egui::Grid::new("settings_display").show(ui, |ui| {
+            ui.label(tr("Preview rows"));
+            ui.add(egui::DragValue::new(&mut self.preview).clamp_range(0..=5));
+            ui.end_row();
+            ui.label(tr("Ratio decimals"));
+            ui.add(egui::DragValue::new(&mut self.decimals).clamp_range(0..=8));
+            ui.end_row();
+            ui.label(tr("Decimal separator"));
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.decimal_comma, false, tr("Point (1.5)"));
+                ui.radio_value(&mut self.decimal_comma, true, tr("Comma (1,5)"));
+            });
+            ui.end_row();
+            ui.label(tr("Units"));
+            egui::ComboBox::from_id_source("settings_units")
+                .selected_text(tr(self.units.name()))
+                .show_ui(ui, |ui| {
+                    for u in [Units::Metric, Units::Imperial] {
+                        ui.selectable_value(&mut self.units, u, tr(u.name()));
+                    }
+                });
+            ui.end_row();
+            ui.label(tr("Theme"));
+            ui.horizontal(|ui| {
+                for t in Theme::ALL {
+                    ui.radio_value(&mut self.theme, t, tr(t.name()));
+                }
+            });
+            ui.end_row();
+            ui.label(tr("Language"));
+            egui::ComboBox::from_id_source("settings_language")
+                .selected_text(self.language.name())
+                .show_ui(ui, |ui| {
+                    for l in Language::ALL {
+                        ui.selectable_value(&mut self.language, l, l.name());
+                    }
+                });
+            ui.end_row();
+        });
+
+        ui.separator();
+        ui.label(egui::RichText::new(tr("New calculations")).strong());
+        egui::Grid::new("settings_defaults").show(ui, |ui| {
+            ui.label(tr("Input gear"));
+            ui.add(egui::DragValue::new(&mut self.default_teeth).clamp_range(1..=100000).suffix(format!(" {}", tr("teeth"))));
+            ui.end_row();
+            ui.label(tr("Ratio"));
+            ui.add(egui::DragValue::new(&mut self.default_ratio).speed(0.01).clamp_range(0.01..=1000.0));
+            ui.end_row();
+        });
+
+        ui.separator();
+        ui.label(egui::RichText::new(tr("Materials")).strong());
+        let mut remove = None;
+        egui::Grid::new("settings_materials").show(ui, |ui| {
+            for (i, m) in self.materials.iter_mut().enumerate() {
+                ui.add(egui::TextEdit::singleline(&mut m.name).desired_width(100.0));
+                ui.add(egui::DragValue::new(&mut m.allowable).speed(0.5).clamp_range(0.1..=5000.0).suffix(" MPa"))
+                    .on_hover_text(tr("Allowable bending stress"));
+                ui.add(egui::DragValue::new(&mut m.density).speed(0.01).clamp_range(0.01..=25.0).suffix(" g/cm³"))
+                    .on_hover_text(tr("Density"));
+                if ui.small_button("🗑").on_hover_text(tr("Delete material")).clicked() {
+                    remove = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = remove {
+            self.materials.remove(i);
+        }
+        ui.horizontal(|ui| {
+            if ui.button(tr("Add material")).clicked() {
+                self.materials.push(Material { name: String::from(tr("New")), allowable: 50.0, density: 1.0 });
+            }
+            if ui.button(tr("Reset materials")).clicked() {
+                self.materials = default_materials();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edited_steps_are_clamped() {
+        let mut settings = Settings::new();
+        settings.load_text("teeth_step=0\nratio_step=NaN");
+        assert_eq!((settings.teeth_step, settings.ratio_step), (1, 0.1));
+        settings.load_text("teeth_step=5000\nratio_step=-3");
+        assert_eq!((settings.teeth_step, settings.ratio_step), (100, 0.001));
+    }
+
+    #[test]
+    fn only_changes_are_saved() {
+        let defaults = Settings::new();
+        let mut settings = Settings::new();
+        assert_eq!(settings.changed_text(&defaults), "");
+        settings.decimals = 5;
+        assert_eq!(settings.changed_text(&defaults), "decimals=5");
+        settings.materials.pop();
+        assert_eq!(settings.changed_text(&defaults).lines().filter(|l| l.starts_with("material=")).count(), 4);
+    }
+
+    #[test]
+    fn feedback_is_saved() {
+        let mut settings = Settings::new();
+        settings.feedback.sound = true;
+        settings.feedback.haptics = true;
+        let mut loaded = Settings::new();
+        loaded.load_text(&settings.to_text());
+        assert!(loaded.feedback.sound && loaded.feedback.haptics);
+    }
+
+    #[test]
+    fn edited_preview_is_clamped() {
+        let mut settings = Settings::new();
+        settings.load_text("preview=1000");
+        assert_eq!(settings.preview, 5);
+    }
+}
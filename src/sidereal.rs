@@ -0,0 +1,148 @@
+use eframe::egui;
+
+use crate::band::Band;
+use crate::bicycle::parse_teeth;
+use crate::i18n::tr;
+use crate::search::{self, describe, Candidate, CenterLimit, Objective, SearchParams};
+use crate::worker::{self, Job, Progress};
+
+/*
+ * Telescope drives: the polar axis has to turn once per sidereal day, the motor runs at a
+ * fixed speed. Most mounts end in a worm drive, so for every worm wheel on the list the
+ * rest of the reduction is searched as a gear train in front of the worm.
+ */
+
+// length of a sidereal day in s
+const SIDEREAL_DAY: f64 = 86164.0905;
+
+struct Drive {
+    worm_wheel: u32,
+    train: Candidate,
+    // relative error of the whole drive
+    error: f64,
+}
+
+// the best train in front of every worm wheel, for every stage count
+fn drives(params: &SearchParams, wheels: &[u32], starts: u32, target: f64, progress: &Progress) -> Option<Vec<Drive>> {
+    let mut results = Vec::new();
+    for (i, &wheel) in wheels.iter().enumerate() {
+        let worm = wheel as f64 / starts as f64;
+        let mut params = params.clone();
+        params.target = target / worm;
+        for c in search::search(&params, &progress.part(i, wheels.len()))?.into_iter().filter_map(|g| g.into_iter().next()) {
+            let error = (c.ratio * worm - target) / target;
+            results.push(Drive { worm_wheel: wheel, train: c, error });
+        }
+    }
+    results.sort_by(|a, b| a.error.abs().total_cmp(&b.error.abs()));
+    Some(results)
+}
+
+pub struct Sidereal {
+    motor_rpm: f64,
+    worm_wheels_str: String,
+    worm_starts: u32,
+    params: SearchParams,
+    results: Vec<Drive>,
+    job: Option<Job<Vec<Drive>>>,
+}
+
+impl Sidereal {
+    pub fn new() -> Sidereal {
+        Sidereal {
+            motor_rpm: 1.0,
+            worm_wheels_str: String::from("100, 144, 180, 200, 250, 288, 359, 360"),
+            worm_starts: 1,
+            params: SearchParams {
+                target: 1.0,
+                min_teeth: 10,
+                max_teeth: 100,
+                driven_teeth: None,
+                max_stages: 2,
+                top_n: 1,
+                objective: Objective::Error,
+                tolerance: 0.0,
+                center_limit: CenterLimit::Free,
+                center_distance: 0.0,
+                module: 1.0,
+            },
+            results: Vec::new(),
+            job: None,
+        }
+    }
+
+    // motor turns per turn of the polar axis
+    fn target(&self) -> f64 {
+        self.motor_rpm * SIDEREAL_DAY / 60.0
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.target() as f32
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, band: &Band) {
+        let p = &mut self.params;
+        egui::Grid::new("sidereal_inputs").show(ui, |ui| {
+            ui.label(tr("Motor speed"));
+            ui.add(egui::DragValue::new(&mut self.motor_rpm).speed(0.1).clamp_range(0.001..=100000.0).max_decimals(4).suffix(" rpm"));
+            ui.end_row();
+            ui.label(tr("Worm wheels"));
+            let invalid = parse_teeth(&self.worm_wheels_str).is_none();
+            let mut te = egui::TextEdit::singleline(&mut self.worm_wheels_str).desired_width(240.0);
+            if invalid {
+                te = te.text_color(ui.visuals().error_fg_color);
+            }
+            ui.add(te);
+            ui.end_row();
+            ui.label(tr("Worm starts"));
+            ui.add(egui::DragValue::new(&mut self.worm_starts).clamp_range(1..=8));
+            ui.end_row();
+            ui.label(tr("Teeth per gear"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut p.min_teeth).clamp_range(4..=p.max_teeth));
+                ui.label(tr("to"));
+                ui.add(egui::DragValue::new(&mut p.max_teeth).clamp_range(p.min_teeth..=200));
+            });
+            ui.end_row();
+            ui.label(tr("Stages before the worm"));
+            ui.add(egui::Slider::new(&mut p.max_stages, 1..=search::MAX_STAGES).text(tr("at most")));
+            ui.end_row();
+        });
+        ui.label(format!("{} {:.4} ({})", tr("Total reduction"), self.target(), tr("one turn per sidereal day")));
+        if ui.add_enabled(self.job.is_none(), egui::Button::new(tr("Search drives"))).clicked() {
+            let params = self.params.clone();
+            let wheels = parse_teeth(&self.worm_wheels_str).unwrap_or_default();
+            let (starts, target) = (self.worm_starts, self.target());
+            self.job = Some(Job::spawn(ui.ctx(), move |p| drives(&params, &wheels, starts, target, p)));
+        }
+        if let Some(r) = worker::job_ui(ui, &mut self.job) {
+            self.results = r;
+        }
+
+        if self.results.is_empty() {
+            return;
+        }
+        ui.separator();
+        if !self.results.iter().any(|d| band.shows(d.error)) {
+            band.empty_note(ui);
+            return;
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("sidereal_results").striped(true).show(ui, |ui| {
+                for h in ["Worm wheel", "Gear train", "Total", "Tracking error"] {
+                    ui.label(egui::RichText::new(tr(h)).strong());
+                }
+                ui.end_row();
+                for d in self.results.iter().filter(|d| band.shows(d.error)) {
+                    ui.label(format!("{}:{}", d.worm_wheel, self.worm_starts));
+                    ui.label(describe(&d.train.train));
+                    ui.label(format!("{:.4}", d.train.ratio * d.worm_wheel as f64 / self.worm_starts as f64));
+                    // a too large reduction makes the axis lag behind the sky
+                    ui.label(format!("{:+.2} s/{}", -d.error * SIDEREAL_DAY, tr("day")));
+                    band.mark(ui, d.error);
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,467 @@
+use std::{fmt::Display, str::FromStr};
+
+use eframe::egui;
+
+use crate::feedback;
+use crate::i18n::tr;
+
+/*
+ * A number field that steps its value when dragged or scrolled over, like a mechanical
+ * counter. The values a step away are shown above and below as weak preview rows.
+ *
+ * It can be used like any other egui widget:
+ * `ui.add(NumberSpinner::new(&mut teeth).step(1).range(6..=200)).changed()`
+ *
+ * The text of the field is kept by the widget, unless a String is given with text(), then
+ * the caller can change it too. Decimal commas are always accepted when typing, shown are
+ * they only when asked for.
+ *
+ * With a label, screen readers get a name for the field and its buttons, and hear the new
+ * value whenever it changes.
+ *
+ * Only the text field takes the keyboard focus, so tabbing goes from field to field. The
+ * arrow keys do what the buttons and preview values do.
+ */
+
+// clickable, but left out when tabbing
+const UNFOCUSABLE_CLICK: egui::Sense = egui::Sense { click: true, drag: false, focusable: false };
+
+pub trait Bounded {
+    const MIN: Self;
+    const MAX: Self;
+}
+
+// stepping stays within the range. Integers can't over- or underflow doing it, floats
+// don't get stuck a rounding error away from the minimum.
+pub trait Step: Bounded + PartialOrd + Copy {
+    const ONE: Self;
+
+    fn step_up(self, step: Self, max: Self) -> Self;
+    fn step_down(self, step: Self, min: Self) -> Self;
+    // a step n times as large
+    fn times(self, n: u8) -> Self;
+}
+
+macro_rules! int_step {
+    ($($t:ty)*) => {$(
+        impl Bounded for $t {
+            const MIN: $t = <$t>::MIN;
+            const MAX: $t = <$t>::MAX;
+        }
+
+        impl Step for $t {
+            const ONE: $t = 1;
+
+            fn step_up(self, step: $t, max: $t) -> $t {
+                self.checked_add(step).map_or(max, |v| v.min(max))
+            }
+
+            fn step_down(self, step: $t, min: $t) -> $t {
+                self.checked_sub(step).map_or(min, |v| v.max(min))
+            }
+
+            fn times(self, n: u8) -> $t {
+                self.saturating_mul(n as $t)
+            }
+        }
+    )*};
+}
+
+macro_rules! float_step {
+    ($($t:ty)*) => {$(
+        impl Bounded for $t {
+            const MIN: $t = <$t>::MIN;
+            const MAX: $t = <$t>::MAX;
+        }
+
+        impl Step for $t {
+            const ONE: $t = 1.0;
+
+            fn step_up(self, step: $t, max: $t) -> $t {
+                (self + step).min(max)
+            }
+
+            fn step_down(self, step: $t, min: $t) -> $t {
+                (self - step).max(min)
+            }
+
+            fn times(self, n: u8) -> $t {
+                self * n as $t
+            }
+        }
+    )*};
+}
+
+int_step!(u8 u16 u32 u64 usize i8 i16 i32 i64 isize);
+float_step!(f32 f64);
+
+pub trait SpinnerValue: Step + Display + FromStr + Send + Sync + 'static {}
+
+impl<T: Step + Display + FromStr + Send + Sync + 'static> SpinnerValue for T {}
+
+// how far the spinners have to be dragged or scrolled for one step. Trackpads send many
+// small scroll deltas, notched mouse wheels few large ones.
+#[derive(Clone, Copy)]
+pub struct Sensitivity {
+    // pixels per step
+    pub drag: f32,
+    pub scroll: f32,
+    pub invert_scroll: bool,
+}
+
+impl Default for Sensitivity {
+    fn default() -> Sensitivity {
+        Sensitivity { drag: 20.0, scroll: 20.0, invert_scroll: false }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct NumberSpinnerState {
+    // scrolled or dragged steps that haven't been taken yet
+    offset: f32,
+    rect_max: egui::Pos2,
+    // drag on the text field by a finger, applied in the next frame
+    touch_delta: f32,
+}
+
+pub struct NumberSpinner<'a, T: SpinnerValue> {
+    value: &'a mut T,
+    text: Option<&'a mut String>,
+    interactive: bool,
+    step: T,
+    // steps with shift and ctrl held
+    fine_step: Option<T>,
+    coarse_step: Option<T>,
+    min_value: T,
+    max_value: T,
+    precision: usize,
+    // preview values above and below the value
+    preview: usize,
+    sensitivity: Sensitivity,
+    decimal_comma: bool,
+    label: Option<String>,
+    id: Option<egui::Id>,
+}
+
+impl<'a, T: SpinnerValue> NumberSpinner<'a, T> {
+    pub fn new(value: &'a mut T) -> Self {
+        NumberSpinner {
+            value,
+            text: None,
+            interactive: true,
+            step: T::ONE,
+            fine_step: None,
+            coarse_step: None,
+            min_value: T::MIN,
+            max_value: T::MAX,
+            precision: 2,
+            preview: 2,
+            sensitivity: Sensitivity::default(),
+            decimal_comma: false,
+            label: None,
+            id: None,
+        }
+    }
+
+    // the text of the field, for callers that set it themselves
+    pub fn text(mut self, text: &'a mut String) -> Self {
+        self.text = Some(text);
+        self
+    }
+
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    pub fn step(mut self, step: T) -> Self {
+        self.step = step;
+        self
+    }
+
+    // the step with shift held, the normal step by default
+    pub fn fine_step(mut self, step: T) -> Self {
+        self.fine_step = Some(step);
+        self
+    }
+
+    // the step with ctrl held, ten normal steps by default
+    pub fn coarse_step(mut self, step: T) -> Self {
+        self.coarse_step = Some(step);
+        self
+    }
+
+    pub fn range(mut self, range: std::ops::RangeInclusive<T>) -> Self {
+        self.min_value = *range.start();
+        self.max_value = *range.end();
+        self
+    }
+
+    // decimals shown, ignored for integers
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn preview(mut self, rows: usize) -> Self {
+        self.preview = rows;
+        self
+    }
+
+    pub fn sensitivity(mut self, sensitivity: Sensitivity) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    // shows 1,5 instead of 1.5
+    pub fn decimal_comma(mut self, comma: bool) -> Self {
+        self.decimal_comma = comma;
+        self
+    }
+
+    // the name screen readers announce
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    // spinners that are shown at different places each frame need a stable id
+    pub fn id_source(mut self, source: impl std::hash::Hash) -> Self {
+        self.id = Some(egui::Id::new(source));
+        self
+    }
+}
+
+impl<'a, T: SpinnerValue> egui::Widget for NumberSpinner<'a, T> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let NumberSpinner { value, text, interactive, min_value, max_value, precision, preview, sensitivity, decimal_comma, label, .. } = self;
+        let name = label.unwrap_or_default();
+        let format = |v: T| format_value(v, precision, decimal_comma);
+        // typed text that can be taken as the value
+        let accept = |text: &str| parse_value::<T>(text).filter(|v| *v >= min_value && *v <= max_value);
+        // used to keep track of dragging and scrolling state
+        let myid = self.id.unwrap_or_else(|| ui.next_auto_id());
+        let mut state: NumberSpinnerState = ui.ctx().data_mut(|d| d.get_temp(myid)).unwrap_or_default();
+        let step = ui.input(|i| {
+            if i.modifiers.shift {
+                self.fine_step.unwrap_or(self.step)
+            } else if i.modifiers.command {
+                self.coarse_step.unwrap_or(self.step.times(10))
+            } else {
+                self.step
+            }
+        });
+
+        // the text kept by the widget, along with the value it was for. It starts over when
+        // the value was changed from outside.
+        let text_id = myid.with("text");
+        let mut own = None;
+        let val_str = match text {
+            Some(t) => t,
+            None => {
+                let kept: Option<(String, T)> = ui.ctx().data_mut(|d| d.get_temp(text_id));
+                own.insert(match kept {
+                    Some((s, v)) if v == *value => s,
+                    _ => format(*value),
+                })
+            }
+        };
+
+        let inner = ui.vertical(|ui| {
+            let mut changed = false;
+            // handle scrolling and dragging.
+            // handling drags needs to be done before adding other ui elements to not steal their
+            // input
+            if interactive {
+                let mut delta = 0.0;
+                let mut urect = ui.min_rect();
+                urect.max = state.rect_max;
+
+                // scrolling, delta is counted in steps
+                ui.input(|i| {
+                    if let Some(pos) = i.pointer.latest_pos() {
+                        if urect.contains(pos){
+                            let mut scroll = i.scroll_delta.y;
+                            // egui turns scrolling with ctrl held into zooming
+                            if i.modifiers.command && scroll == 0.0 {
+                                scroll = i.zoom_delta().ln() * 200.0;
+                            }
+                            if sensitivity.invert_scroll {
+                                scroll = -scroll;
+                            }
+                            delta = scroll / sensitivity.scroll;
+                        }
+                    }
+                });
+
+                // dragging
+                let resp = ui.interact(urect, myid, egui::Sense { click: false, drag: true, focusable: false });
+                if resp.dragged() {
+                    //println!("Dragged by: {:?}", resp.drag_delta());
+                    delta = resp.drag_delta().y / sensitivity.drag;
+                }
+                delta += std::mem::take(&mut state.touch_delta) / sensitivity.drag;
+
+                if delta != 0.0 {
+                    state.offset += delta;
+                    //println!("offset: {}", state.offset);
+                    if state.offset > 1.0 {
+                        state.offset = 0.0;
+                        *value = value.step_up(step, max_value);
+                        changed = true;
+                    } else if state.offset < -1.0 {
+                        state.offset = 0.0;
+                        *value = value.step_down(step, min_value);
+                        changed = true;
+                    }
+                    ui.ctx().data_mut(|d| d.insert_temp(myid, state));
+                    // number changed from scroll/drag, so we need to update the text field
+                    if changed {
+                        *val_str = format(*value);
+                        feedback::note_step(ui.ctx());
+                    }
+                }
+            }
+
+            let te = egui::TextEdit::singleline(val_str)
+                .interactive(interactive)
+                .desired_width(80.0);
+
+            // buttons for touch screens and screen readers, where there is no scrolling
+            let plus = ui.add_enabled(interactive, egui::Button::new("+").small().sense(UNFOCUSABLE_CLICK)).on_hover_text(tr("Increase"));
+            plus.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, format!("{} {}", tr("Increase"), name)));
+            // clicking a preview value jumps there
+            let mut jump = 0;
+            let above = previews(*value, preview, |v| v.step_up(step, max_value));
+            for (n, v) in above.iter().enumerate().rev() {
+                if ghost(ui, v.map(format).unwrap_or_default(), interactive) {
+                    jump = n as i32 + 1;
+                }
+            }
+            let te_response = ui.add(te);
+            let shown = val_str.clone();
+            te_response.widget_info(|| {
+                let mut info = egui::WidgetInfo::labeled(egui::WidgetType::TextEdit, &name);
+                info.current_text_value = Some(shown.clone());
+                info
+            });
+            let below = previews(*value, preview, |v| v.step_down(step, min_value));
+            for (n, v) in below.iter().enumerate() {
+                if ghost(ui, v.map(format).unwrap_or_default(), interactive) {
+                    jump = -(n as i32 + 1);
+                }
+            }
+
+            let minus = ui.add_enabled(interactive, egui::Button::new("-").small().sense(UNFOCUSABLE_CLICK)).on_hover_text(tr("Decrease"));
+            minus.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, format!("{} {}", tr("Decrease"), name)));
+
+            // we need the screen rect of the whole spinner to sense drags / scrolls, but we don't
+            // know it until the other UI elements have been added, so just cache it from last frame
+            if state.rect_max != ui.min_rect().max {
+                state.rect_max = ui.min_rect().max;
+                ui.ctx().data_mut(|d| d.insert_temp(myid, state));
+            }
+
+            // a finger has no other way to spin than dragging, so on touch screens a drag that
+            // starts on the text field spins too instead of selecting text
+            if interactive && te_response.dragged() && ui.input(|i| i.any_touches()) {
+                state.touch_delta += te_response.drag_delta().y;
+                te_response.surrender_focus();
+                ui.ctx().data_mut(|d| d.insert_temp(myid, state));
+            }
+
+            // if enter is pressed and the entered string is no valid number, reset it
+            if te_response.lost_focus() && accept(val_str).is_none() {
+                *val_str = format(*value);
+            }
+            // enter commits the typed value, the field keeps the focus for the arrow keys
+            if te_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                *val_str = format(*value);
+                te_response.request_focus();
+            }
+            if te_response.changed() {
+                if let Some(x) = accept(val_str) {
+                    *value = x;
+                    changed = true;
+                }
+            }
+            // while the text can't be taken, it is outlined and the allowed values are shown
+            if interactive && accept(val_str).is_none() {
+                let color = ui.visuals().error_fg_color;
+                ui.painter().rect_stroke(te_response.rect.expand(1.0), 2.0, egui::Stroke::new(1.5_f32, color));
+                let hint = match (min_value > T::MIN, max_value < T::MAX) {
+                    (true, true) => format!("{} {} – {}", tr("Allowed:"), format(min_value), format(max_value)),
+                    (true, false) => format!("{} {}", tr("At least"), format(min_value)),
+                    (false, true) => format!("{} {}", tr("At most"), format(max_value)),
+                    (false, false) => String::from(tr("Enter a number")),
+                };
+                ui.label(egui::RichText::new(hint).small().color(color));
+            }
+
+            let mut steps = plus.clicked() as i32 - minus.clicked() as i32 + jump;
+            // the arrow keys step while typing, the page keys take ten steps
+            if interactive && te_response.has_focus() {
+                steps += ui.input(|i| {
+                    [(egui::Key::ArrowUp, 1), (egui::Key::ArrowDown, -1), (egui::Key::PageUp, 10), (egui::Key::PageDown, -10)]
+                        .iter()
+                        .filter(|(k, _)| i.key_pressed(*k))
+                        .map(|(_, n)| n)
+                        .sum::<i32>()
+                });
+            }
+            if steps != 0 {
+                for _ in 0..steps.abs() {
+                    *value = if steps > 0 { value.step_up(step, max_value) } else { value.step_down(step, min_value) };
+                }
+                *val_str = format(*value);
+                changed = true;
+                feedback::note_step(ui.ctx());
+            }
+            changed
+        });
+
+        if let Some(s) = own {
+            let kept = (s, *value);
+            ui.ctx().data_mut(|d| d.insert_temp(text_id, kept));
+        }
+        let mut response = inner.response;
+        if inner.inner {
+            response.mark_changed();
+        }
+        // announces the new value
+        response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::DragValue, format!("{} {}", name, format(*value))));
+        response
+    }
+}
+
+// a preview value, returns whether it was clicked. egui's weak text is hard to read on the
+// dark background, so the text color is faded by the same amount in both themes instead.
+fn ghost(ui: &mut egui::Ui, text: String, interactive: bool) -> bool {
+    let color = ui.visuals().text_color().gamma_multiply(0.55);
+    let label = egui::Label::new(egui::RichText::new(text).color(color));
+    if interactive {
+        ui.add(label.sense(UNFOCUSABLE_CLICK)).on_hover_cursor(egui::CursorIcon::PointingHand).clicked()
+    } else {
+        ui.add(label);
+        false
+    }
+}
+
+// the values `rows` steps away, None past the end of the range
+fn previews<T: SpinnerValue>(value: T, rows: usize, step: impl Fn(T) -> T) -> Vec<Option<T>> {
+    let mut last = Some(value);
+    (0..rows).map(|_| {
+        last = last.map(&step).filter(|v| Some(*v) != last);
+        last
+    }).collect()
+}
+
+pub fn format_value<T: Display>(value: T, precision: usize, decimal_comma: bool) -> String {
+    let s = format!("{0:.1$}", value, precision);
+    if decimal_comma { s.replace('.', ",") } else { s }
+}
+
+// accepts a decimal point as well as a decimal comma
+fn parse_value<T: FromStr>(text: &str) -> Option<T> {
+    text.trim().replace(',', ".").parse().ok()
+}
@@ -0,0 +1,146 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * Steps per mm for 3D printers and CNC machines:
+ *     steps/mm = motor steps/rev * microstepping * reduction / travel per output rev
+ * Like the locked column of the gear pair, one of the values is marked and gets solved
+ * from all the others. The travel comes from a belt pulley (teeth * belt pitch) or the
+ * lead of a leadscrew.
+ */
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Quantity {
+    Steps,
+    Microstepping,
+    Reduction,
+    Travel,
+    StepsPerMm,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Drive {
+    Belt,
+    Leadscrew,
+}
+
+pub struct Stepper {
+    steps: f32,
+    microstepping: f32,
+    reduction: f32,
+    drive: Drive,
+    pulley_teeth: f32,
+    belt_pitch: f32,
+    lead: f32,
+    steps_per_mm: f32,
+    solved: Quantity,
+}
+
+impl Stepper {
+    pub fn new() -> Stepper {
+        let mut s = Stepper {
+            steps: 200.0,
+            microstepping: 16.0,
+            reduction: 1.0,
+            drive: Drive::Belt,
+            pulley_teeth: 20.0,
+            belt_pitch: 2.0,
+            lead: 8.0,
+            steps_per_mm: 0.0,
+            solved: Quantity::StepsPerMm,
+        };
+        s.solve();
+        s
+    }
+
+    // mm per revolution of the output shaft
+    fn travel(&self) -> f32 {
+        match self.drive {
+            Drive::Belt => self.pulley_teeth * self.belt_pitch,
+            Drive::Leadscrew => self.lead,
+        }
+    }
+
+    fn set_travel(&mut self, travel: f32) {
+        match self.drive {
+            Drive::Belt => self.pulley_teeth = travel / self.belt_pitch,
+            Drive::Leadscrew => self.lead = travel,
+        }
+    }
+
+    // the gear reduction, which is the ratio of the other modes
+    pub fn ratio(&self) -> f32 {
+        self.reduction
+    }
+
+    fn solve(&mut self) {
+        let motor = self.steps * self.microstepping;
+        match self.solved {
+            Quantity::Steps => self.steps = self.steps_per_mm * self.travel() / (self.microstepping * self.reduction),
+            Quantity::Microstepping => self.microstepping = self.steps_per_mm * self.travel() / (self.steps * self.reduction),
+            Quantity::Reduction => self.reduction = self.steps_per_mm * self.travel() / motor,
+            Quantity::Travel => self.set_travel(motor * self.reduction / self.steps_per_mm),
+            Quantity::StepsPerMm => self.steps_per_mm = motor * self.reduction / self.travel(),
+        }
+    }
+
+    fn row(ui: &mut egui::Ui, solved: &mut Quantity, q: Quantity, label: &str, value: &mut f32, speed: f32, suffix: &str) {
+        ui.label(label);
+        ui.add_enabled(*solved != q, egui::DragValue::new(value)
+            .speed(speed)
+            .clamp_range(0.001..=1_000_000.0)
+            .max_decimals(4)
+            .suffix(suffix));
+        ui.selectable_value(solved, q, tr("solved"));
+        ui.end_row();
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, pair_ratio: f32) {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.drive, Drive::Belt, tr("Belt pulley"));
+            ui.radio_value(&mut self.drive, Drive::Leadscrew, tr("Leadscrew"));
+        });
+
+        let s = &mut self.solved;
+        egui::Grid::new("stepper").show(ui, |ui| {
+            Stepper::row(ui, s, Quantity::Steps, tr("Motor steps/rev"), &mut self.steps, 1.0, "");
+            Stepper::row(ui, s, Quantity::Microstepping, tr("Microstepping"), &mut self.microstepping, 1.0, "");
+            Stepper::row(ui, s, Quantity::Reduction, tr("Reduction"), &mut self.reduction, 0.01, " : 1");
+            match self.drive {
+                Drive::Belt => {
+                    Stepper::row(ui, s, Quantity::Travel, tr("Pulley teeth"), &mut self.pulley_teeth, 0.1, "");
+                    ui.label(tr("Belt pitch"));
+                    ui.add(egui::DragValue::new(&mut self.belt_pitch).speed(0.01).clamp_range(0.1..=50.0).suffix(" mm"));
+                    ui.end_row();
+                }
+                Drive::Leadscrew => {
+                    Stepper::row(ui, s, Quantity::Travel, tr("Lead"), &mut self.lead, 0.01, " mm");
+                }
+            }
+            Stepper::row(ui, s, Quantity::StepsPerMm, tr("Steps/mm"), &mut self.steps_per_mm, 0.1, "");
+        });
+        if ui.button(tr("Use gear pair ratio")).clicked() {
+            self.reduction = pair_ratio;
+            if self.solved == Quantity::Reduction {
+                self.solved = Quantity::StepsPerMm;
+            }
+        }
+        // cheap enough to do every frame, which also covers switching the solved value
+        self.solve();
+
+        ui.separator();
+        ui.label(format!("{:.3} mm {}", self.travel(), tr("travel per output revolution")));
+        ui.label(format!("{:.4} mm {}", 1.0 / self.steps_per_mm, tr("per microstep")));
+        let whole = |x: f32| (x - x.round()).abs() < 1e-3;
+        let problem = match self.solved {
+            Quantity::Steps if !whole(self.steps) => Some("Motor steps/rev isn't a whole number."),
+            Quantity::Microstepping if !whole(self.microstepping) => Some("Microstepping isn't a whole number."),
+            Quantity::Travel if self.drive == Drive::Belt && !whole(self.pulley_teeth) => Some("Pulley teeth aren't a whole number."),
+            _ => None,
+        };
+        if let Some(p) = problem {
+            ui.colored_label(ui.visuals().warn_fg_color, tr(p));
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use eframe::egui;
+
+use crate::geometry::Pair;
+use crate::i18n::tr;
+
+/*
+ * A rough bending strength check of the gear pair with the Lewis formula. The tooth is a
+ * cantilever loaded at the tip by the tangential force of the transmitted torque:
+ *     σ = Kv · Ft / (b · m · Y),    Ft = 2 T / d
+ * with the face width b, the module m and the Lewis form factor Y, which grows with the
+ * number of teeth. The Barth velocity factor Kv = (6.1 + v) / 6.1 adds the impact of the
+ * teeth at the pitch line speed v in m/s. Both gears carry the same force, the smaller one
+ * has the weaker teeth. The safety factor is the allowable stress of the material over σ.
+ *
+ * A helical tooth is loaded like the tooth of a spur gear with more teeth, the virtual
+ * tooth count z / cos³β for the helix angle β, and m is its normal module. The table of Y
+ * starts at 12 teeth, below that the stress is only a lower bound.
+ *
+ * It ignores fatigue, surface wear and load sharing, so it's good for sizing printed and
+ * hobby gears, not for signing off a gearbox.
+ */
+
+pub struct Material {
+    pub name: String,
+    // allowable bending stress in MPa
+    pub allowable: f32,
+    // g/cm³
+    pub density: f32,
+}
+
+// typical allowable bending stresses and densities
+pub const DEFAULT_MATERIALS: [(&str, f32, f32); 5] = [
+    ("Steel", 200.0, 7.85),
+    ("Brass", 60.0, 8.5),
+    ("POM", 30.0, 1.41),
+    ("PETG", 20.0, 1.27),
+    ("PLA", 15.0, 1.24),
+];
+
+// Lewis form factor for 20° full depth teeth, by number of teeth
+const FORM_FACTORS: [(u32, f32); 25] = [
+    (12, 0.245), (13, 0.261), (14, 0.277), (15, 0.290), (16, 0.296), (17, 0.303), (18, 0.309),
+    (19, 0.314), (20, 0.322), (21, 0.328), (22, 0.331), (24, 0.337), (26, 0.346), (28, 0.353),
+    (30, 0.359), (34, 0.371), (38, 0.384), (43, 0.397), (50, 0.409), (60, 0.422), (75, 0.435),
+    (100, 0.447), (150, 0.460), (300, 0.472), (400, 0.480),
+];
+// a rack, and close enough for the teeth of a ring gear
+const RACK_FORM_FACTOR: f32 = 0.485;
+
+// below this many teeth the form factor of 12 teeth is used, which is too optimistic
+pub const MIN_TABLE_TEETH: f32 = 12.0;
+
+// teeth can be the virtual tooth count of a helical gear, which isn't whole
+pub fn form_factor(teeth: f32) -> f32 {
+    match FORM_FACTORS.iter().position(|&(t, _)| t as f32 >= teeth) {
+        Some(0) => FORM_FACTORS[0].1,
+        Some(i) => {
+            let ((t0, y0), (t1, y1)) = (FORM_FACTORS[i - 1], FORM_FACTORS[i]);
+            y0 + (y1 - y0) * (teeth - t0 as f32) / (t1 - t0) as f32
+        }
+        None => RACK_FORM_FACTOR,
+    }
+}
+
+// the spur gear tooth count a helical gear's teeth are shaped like, helix in degrees
+pub fn virtual_teeth(teeth: u32, helix: f32) -> f32 {
+    teeth as f32 / helix.to_radians().cos().powi(3)
+}
+
+pub struct Strength {
+    // in mm
+    pub face_width: f32,
+    // on the input gear, in Nm
+    torque: f32,
+    // of the input and output gear
+    pub materials: [usize; 2],
+}
+
+impl Strength {
+    pub fn new() -> Strength {
+        Strength { face_width: 10.0, torque: 1.0, materials: [0, 0] }
+    }
+
+    // bending stress in MPa of the input (0) or output (1) gear, helix in degrees
+    fn stress(&self, pair: &Pair, helix: f32, input_speed: f32, gear: usize) -> f32 {
+        let m = pair.module;
+        // pitch diameter with the transverse module
+        let d1 = m / helix.to_radians().cos() * pair.left as f32;
+        // N, from Nmm over mm
+        let force = 2.0 * self.torque * 1000.0 / d1;
+        let speed = std::f32::consts::PI * d1 / 1000.0 * input_speed / 60.0;
+        let kv = (6.1 + speed) / 6.1;
+        let y = match gear {
+            0 => form_factor(virtual_teeth(pair.left, helix)),
+            _ if pair.ring => RACK_FORM_FACTOR,
+            _ => form_factor(virtual_teeth(pair.right, helix)),
+        };
+        kv * force / (self.face_width * m * y)
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, pair: &Pair, helix: f32, input_speed: f32, materials: &[Material]) {
+        egui::Grid::new("strength_inputs").show(ui, |ui| {
+            ui.label(tr("Face width"));
+            ui.add(egui::DragValue::new(&mut self.face_width).speed(0.1).clamp_range(0.1..=1000.0).suffix(" mm"));
+            ui.end_row();
+            ui.label(tr("Input torque"));
+            ui.add(egui::DragValue::new(&mut self.torque).speed(0.01).clamp_range(0.0..=100000.0).suffix(" Nm"));
+            ui.end_row();
+        });
+
+        egui::Grid::new("strength").striped(true).show(ui, |ui| {
+            for h in ["", "Material", "Stress", "Safety"] {
+                ui.label(egui::RichText::new(tr(h)).strong());
+            }
+            ui.end_row();
+            for (gear, name) in [(0, "Input gear"), (1, "Output gear")] {
+                ui.label(tr(name));
+                let stress = self.stress(pair, helix, input_speed, gear);
+                let selected = &mut self.materials[gear];
+                *selected = (*selected).min(materials.len().saturating_sub(1));
+                egui::ComboBox::from_id_source(("strength_material", gear))
+                    .selected_text(materials.get(*selected).map_or("", |m| m.name.as_str()))
+                    .show_ui(ui, |ui| {
+                        for (i, m) in materials.iter().enumerate() {
+                            ui.selectable_value(selected, i, m.name.as_str());
+                        }
+                    });
+                ui.label(format!("{:.1} MPa", stress));
+                match materials.get(*selected) {
+                    Some(m) => {
+                        let safety = m.allowable / stress;
+                        if safety < 1.0 {
+                            ui.colored_label(ui.visuals().error_fg_color, format!("{:.2}", safety));
+                        } else {
+                            ui.label(format!("{:.2}", safety));
+                        }
+                    }
+                    None => {
+                        ui.label("–");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        let right = if pair.ring { None } else { Some(pair.right) };
+        for (teeth, name) in [(Some(pair.left), "Input gear"), (right, "Output gear")] {
+            if teeth.is_some_and(|t| virtual_teeth(t, helix) < MIN_TABLE_TEETH) {
+                ui.colored_label(ui.visuals().warn_fg_color,
+                    format!("{}: {}", tr(name), tr("fewer teeth than the Lewis table, the stress is higher than shown.")));
+            }
+        }
+        ui.weak(tr("Lewis formula with the Barth velocity factor, bending only."));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn helical_teeth_use_the_virtual_count() {
+        assert_eq!(virtual_teeth(20, 0.0), 20.0);
+        // cos³ 30° = 0.6495
+        assert!((virtual_teeth(20, 30.0) - 30.79).abs() < 0.01);
+        assert!(form_factor(virtual_teeth(20, 30.0)) > form_factor(20.0));
+        assert_eq!(form_factor(26.0), 0.346);
+    }
+}
@@ -0,0 +1,142 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+use crate::ratio_model::RatioModel;
+
+/*
+ * Sweeping the input gear over a range of tooth counts with the given ratio kept fixed,
+ * the output gear is rounded like the pair rounds it, with the chosen rounding. Each step of the sweep is one row
+ * of the table, which can be exported as CSV for plotting elsewhere.
+ */
+
+struct SweepRow {
+    input_teeth: u32,
+    output_teeth: u32,
+    ratio: f64,
+    // divergence from the given ratio in percent
+    error: f64,
+    output_rpm: f64,
+}
+
+pub struct Sweep {
+    from: u32,
+    to: u32,
+    step: u32,
+    // input speed for the output speed column
+    rpm: f32,
+    #[cfg(not(target_arch = "wasm32"))]
+    path: String,
+    status: String,
+}
+
+impl Sweep {
+    pub fn new() -> Sweep {
+        Sweep {
+            from: 10,
+            to: 40,
+            step: 1,
+            rpm: 1000.0,
+            #[cfg(not(target_arch = "wasm32"))]
+            path: String::from("sweep.csv"),
+            status: String::new(),
+        }
+    }
+
+    fn rows(&self, model: &RatioModel) -> Vec<SweepRow> {
+        (self.from..=self.to)
+            .step_by(self.step as usize)
+            .map(|input_teeth| {
+                let output_teeth = model.round_teeth(input_teeth as f64 * model.given, |r| r / input_teeth as f64);
+                let ratio = output_teeth as f64 / input_teeth as f64;
+                SweepRow { input_teeth, output_teeth, ratio, error: model.error_of(ratio), output_rpm: self.rpm as f64 / ratio }
+            })
+            .collect()
+    }
+
+    fn csv(rows: &[SweepRow]) -> String {
+        let mut s = String::from("step,input_teeth,output_teeth,ratio,error_percent,output_rpm\n");
+        for (i, r) in rows.iter().enumerate() {
+            s += &format!("{},{},{},{:.6},{:.4},{:.3}\n", i, r.input_teeth, r.output_teeth, r.ratio, r.error, r.output_rpm);
+        }
+        s
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, model: &RatioModel) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Input teeth"));
+            ui.add(egui::DragValue::new(&mut self.from).clamp_range(1..=self.to));
+            ui.label(tr("to"));
+            ui.add(egui::DragValue::new(&mut self.to).clamp_range(self.from..=1000));
+            ui.label(tr("in steps of"));
+            ui.add(egui::DragValue::new(&mut self.step).clamp_range(1..=100));
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("Input speed"));
+            ui.add(egui::DragValue::new(&mut self.rpm).speed(10.0).clamp_range(0.0..=100000.0).suffix(" rpm"));
+        });
+        ui.label(format!("{} {:.3} {}", tr("Given ratio"), model.given, tr("is kept fixed.")));
+
+        let rows = self.rows(model);
+        egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+            egui::Grid::new("sweep").striped(true).show(ui, |ui| {
+                for h in ["Input", "Output", "Ratio", "Error", "Output rpm"] {
+                    ui.label(egui::RichText::new(tr(h)).strong());
+                }
+                ui.end_row();
+                for r in &rows {
+                    ui.label(format!("{}", r.input_teeth));
+                    ui.label(format!("{}", r.output_teeth));
+                    ui.label(format!("{:.3}", r.ratio));
+                    if model.diverges(r.ratio) {
+                        ui.colored_label(ui.visuals().warn_fg_color, format!("{:+.2} %", r.error));
+                    } else {
+                        ui.label(format!("{:+.2} %", r.error));
+                    }
+                    ui.label(format!("{:.1}", r.output_rpm));
+                    ui.end_row();
+                }
+            });
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.add(egui::TextEdit::singleline(&mut self.path).desired_width(160.0));
+                if ui.button(tr("Export CSV")).clicked() {
+                    self.status = match std::fs::write(&self.path, Sweep::csv(&rows)) {
+                        Ok(()) => format!("{} {}", tr("Rows saved:"), rows.len()),
+                        Err(e) => format!("{} {}", tr("Saving failed:"), e),
+                    };
+                }
+            }
+            // no file system in the browser
+            if ui.button(tr("Copy CSV")).clicked() {
+                let csv = Sweep::csv(&rows);
+                ui.output_mut(|o| o.copied_text = csv);
+                self.status = tr("Copied to clipboard.").to_string();
+            }
+        });
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratio_model::Rounding;
+
+    #[test]
+    fn rows_use_the_rounding() {
+        let sweep = Sweep { from: 10, to: 20, step: 10, ..Sweep::new() };
+        let mut model = RatioModel::new(2.54);
+        let output = |model: &RatioModel| sweep.rows(model).iter().map(|r| r.output_teeth).collect::<Vec<_>>();
+        assert_eq!(output(&model), [25, 51]);
+        model.rounding = Rounding::Up;
+        assert_eq!(output(&model), [26, 51]);
+        model.rounding = Rounding::Down;
+        assert_eq!(output(&model), [25, 50]);
+    }
+}
@@ -0,0 +1,115 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * Tire sizes the way they are printed on the sidewall, turned into the rolling
+ * circumference for the speed calculations:
+ * - metric "205/55R16": width in mm, sidewall height in percent of the width, rim in inches
+ * - inch "26x2.1" or "33x12.50R15": the first number is the outer diameter in inches
+ * - road bikes "700x25c": the 622 mm bead seat of 700c rims plus twice the width in mm
+ * - ETRTO "25-622": width and bead seat diameter in mm
+ * - a plain number: the circumference in mm, like measured by rolling the wheel
+ * From a size it is π times the outer diameter, a loaded tire rolls a bit shorter.
+ */
+
+// bead seat diameter of 700c rims in mm
+const BEAD_700C: f64 = 622.0;
+
+fn number(s: &str) -> Option<f64> {
+    s.trim().parse().ok().filter(|v: &f64| *v > 0.0)
+}
+
+// rolling circumference in mm
+pub fn parse_circumference(text: &str) -> Result<f64, &'static str> {
+    let mut words: Vec<&str> = text.split_whitespace().collect();
+    // load index and speed rating after a car tire size, like 91V
+    let rating = |w: &&str| w.len() > 1 && w.ends_with(|c: char| c.is_ascii_alphabetic()) && w[..w.len() - 1].chars().all(|c| c.is_ascii_digit());
+    if words.len() > 1 && words.last().is_some_and(rating) {
+        words.pop();
+    }
+    let s = words.concat().to_ascii_lowercase();
+    let s = s.strip_suffix("mm").unwrap_or(&s);
+    if let Some(c) = number(s) {
+        return Ok(c);
+    }
+    // P and LT in front of car tire sizes
+    let diameter = if let Some((width, rest)) = s.trim_start_matches(|c: char| c.is_ascii_alphabetic()).split_once('/') {
+        // 205/55R16, also ZR and the load index after the rim
+        let (aspect, rim) = rest.split_once(|c: char| c.is_ascii_alphabetic()).ok_or("Expected a size like 205/55R16.")?;
+        let rim = rim.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+        let rim = rim.split(|c: char| !(c.is_ascii_digit() || c == '.')).next().unwrap_or("");
+        match (number(width), number(aspect), number(rim)) {
+            (Some(w), Some(a), Some(r)) => r * 25.4 + 2.0 * w * a / 100.0,
+            _ => return Err("Expected a size like 205/55R16."),
+        }
+    } else if let Some((first, second)) = s.split_once('x') {
+        let second = second.split(|c: char| !(c.is_ascii_digit() || c == '.')).next().unwrap_or("");
+        match (number(first), number(second)) {
+            (Some(700.0), Some(w)) => BEAD_700C + 2.0 * w,
+            (Some(d), Some(_)) => d * 25.4,
+            _ => return Err("Expected a size like 26x2.1."),
+        }
+    } else if let Some((width, bead)) = s.split_once('-') {
+        match (number(width), number(bead)) {
+            (Some(w), Some(b)) => b + 2.0 * w,
+            _ => return Err("Expected a size like 25-622."),
+        }
+    } else {
+        return Err("Enter a size like 205/55R16, 26x2.1, 25-622 or a circumference in mm.");
+    };
+    Ok(std::f64::consts::PI * diameter)
+}
+
+// a text field for a tire size. returns the circumference in mm when a valid size was
+// entered.
+pub fn tire_input(ui: &mut egui::Ui, text: &mut String) -> Option<f64> {
+    let mut result = None;
+    ui.vertical(|ui| {
+        let changed = ui.add(egui::TextEdit::singleline(text).desired_width(120.0).hint_text("205/55R16")).changed();
+        match parse_circumference(text) {
+            Ok(c) => {
+                ui.weak(format!("{:.0} {}", c, tr("mm around")));
+                if changed {
+                    result = Some(c);
+                }
+            }
+            Err(e) if !text.trim().is_empty() => {
+                ui.colored_label(ui.visuals().warn_fg_color, tr(e));
+            }
+            Err(_) => {}
+        }
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn close(text: &str, mm: f64) {
+        let c = parse_circumference(text).unwrap();
+        assert!((c - mm).abs() < 1e-9, "{}: {} instead of {}", text, c, mm);
+    }
+
+    #[test]
+    fn formats() {
+        close("205/55R16", PI * (16.0 * 25.4 + 2.0 * 205.0 * 0.55));
+        close("205/55 R16 91V", PI * (16.0 * 25.4 + 2.0 * 205.0 * 0.55));
+        close("P215/65ZR15", PI * (15.0 * 25.4 + 2.0 * 215.0 * 0.65));
+        close("26x2.1", PI * 26.0 * 25.4);
+        close("33x12.50R15", PI * 33.0 * 25.4);
+        close("700x25c", PI * 672.0);
+        close("25-622", PI * 672.0);
+        close("2100", 2100.0);
+        close("2100 mm", 2100.0);
+    }
+
+    #[test]
+    fn bad_sizes() {
+        for text in ["", "banana", "205/55", "205/R16", "x2.1", "25-", "0", "-5"] {
+            assert!(parse_circumference(text).is_err(), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * A gear train is a chain of stages, each stage being a driver gear meshing with a driven
+ * gear. The driven gear of one stage sits on the same shaft as the driver of the next one,
+ * so the ratio of the whole train is the product of the stage ratios.
+ *
+ * Idlers can sit between the driver and the driven gear of a stage. They don't change the
+ * ratio, but every external mesh reverses the direction, so each idler flips it once more.
+ */
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Direction {
+    Same,
+    Reversed,
+}
+
+impl Direction {
+    pub fn flip(self) -> Direction {
+        match self {
+            Direction::Same => Direction::Reversed,
+            Direction::Reversed => Direction::Same,
+        }
+    }
+
+    // the direction after another part of the train turned this one
+    pub fn then(self, other: Direction) -> Direction {
+        if other == Direction::Reversed { self.flip() } else { self }
+    }
+
+    // taking the input as turning clockwise
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Direction::Same => "↻",
+            Direction::Reversed => "↺",
+        }
+    }
+
+    // an arrow that tells what it means when hovered
+    pub fn show(self, ui: &mut egui::Ui) {
+        ui.label(self.arrow()).on_hover_text(tr(self.name()));
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::Same => "same direction as the input",
+            Direction::Reversed => "reversed",
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Stage {
+    pub driver: u32,
+    pub driven: u32,
+    // idler gears between the two
+    pub idlers: u32,
+}
+
+impl Stage {
+    pub fn new(driver: u32, driven: u32) -> Stage {
+        Stage { driver, driven, idlers: 0 }
+    }
+
+    // the driven gear against the driver, one reversal per mesh
+    pub fn direction(&self) -> Direction {
+        if self.idlers % 2 == 0 { Direction::Reversed } else { Direction::Same }
+    }
+
+    // teeth on driven / teeth on driver, like the pair ratio
+    pub fn ratio(&self) -> f32 {
+        self.driven as f32 / self.driver as f32
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Train {
+    pub stages: Vec<Stage>,
+}
+
+impl Train {
+    pub fn new(stages: &[Stage]) -> Train {
+        Train { stages: stages.to_vec() }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.stages.iter().map(|s| s.ratio()).product()
+    }
+}
+
+// an editable list of stages. returns true if anything was changed.
+pub fn train_editor(ui: &mut egui::Ui, train: &mut Train, id_source: &str) -> bool {
+    let mut changed = false;
+    egui::Grid::new(id_source).striped(true).show(ui, |ui| {
+        ui.label(egui::RichText::new(tr("Stage")).strong());
+        ui.label(egui::RichText::new(tr("Driver")).strong());
+        ui.label(egui::RichText::new(tr("Driven")).strong());
+        ui.label(egui::RichText::new(tr("Ratio")).strong());
+        ui.end_row();
+
+        for (i, s) in train.stages.iter_mut().enumerate() {
+            ui.label(format!("{}", i + 1));
+            changed |= ui.add(egui::DragValue::new(&mut s.driver).clamp_range(1..=1000)).changed();
+            changed |= ui.add(egui::DragValue::new(&mut s.driven).clamp_range(1..=1000)).changed();
+            ui.label(format!("{:.3}", s.ratio()));
+            ui.end_row();
+        }
+    });
+
+    ui.horizontal(|ui| {
+        if ui.button(tr("Add stage")).clicked() {
+            let last = train.stages.last().copied().unwrap_or(Stage::new(10, 10));
+            train.stages.push(last);
+            changed = true;
+        }
+        if train.stages.len() > 1 && ui.button(tr("Remove stage")).clicked() {
+            train.stages.pop();
+            changed = true;
+        }
+        ui.label(format!("{} {:.4}", tr("Total ratio:"), train.ratio()));
+    });
+    changed
+}
@@ -0,0 +1,126 @@
+use crate::constraint::Quantity;
+use crate::quick_entry::Intent;
+
+/*
+ * The gear pair in the URL fragment, so a link like #l=11&r=72&lock=ratio opens the web
+ * build with that pair. The given ratio is only written when it isn't what the teeth make.
+ * Keys that aren't known are skipped, so old links keep working.
+ *
+ * The native build takes the same from its command line, `--left 11 --right 72 --lock ratio`
+ * (or `--left=11`), so a script or a desktop shortcut can open it on a machine's pair.
+ * `--config` belongs to the config file and is left alone.
+ */
+
+fn lock_name(q: Quantity) -> &'static str {
+    match q {
+        Quantity::Left => "left",
+        Quantity::Right => "right",
+        _ => "ratio",
+    }
+}
+
+pub fn encode(left: u32, right: u32, given: f64, lock: Quantity) -> String {
+    let mut s = format!("l={}&r={}", left, right);
+    if (given - right as f64 / left as f64).abs() > 1e-4 {
+        s += &format!("&ratio={}", given);
+    }
+    s + "&lock=" + lock_name(lock)
+}
+
+pub fn decode(fragment: &str) -> Intent {
+    let mut intent = Intent::default();
+    for pair in fragment.trim_start_matches('#').split('&') {
+        let (key, value) = match pair.split_once('=') {
+            Some(kv) => kv,
+            None => continue,
+        };
+        match key {
+            "l" => intent.left = value.parse().ok().filter(|t| *t > 0),
+            "r" => intent.right = value.parse().ok().filter(|t| *t > 0),
+            "ratio" => intent.ratio = value.parse().ok().filter(|r: &f64| *r > 0.0),
+            "lock" => intent.lock = [Quantity::Left, Quantity::Right, Quantity::Ratio].into_iter().find(|q| lock_name(*q) == value),
+            _ => {}
+        }
+    }
+    intent
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn fragment() -> Option<String> {
+    web_sys::window()?.location().hash().ok().filter(|h| h.len() > 1)
+}
+
+// the command line arguments as a fragment
+#[cfg(not(target_arch = "wasm32"))]
+fn from_args(args: impl Iterator<Item = String>) -> String {
+    let mut pairs = Vec::new();
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        let flag = match arg.strip_prefix("--") {
+            Some(f) => f,
+            None => {
+                log::warn!("ignoring argument {}", arg);
+                continue;
+            }
+        };
+        let (name, value) = match flag.split_once('=') {
+            Some((n, v)) => (n.to_string(), v.to_string()),
+            None => (flag.to_string(), args.next_if(|a| !a.starts_with("--")).unwrap_or_default()),
+        };
+        let key = match name.as_str() {
+            "left" => "l",
+            "right" => "r",
+            "ratio" => "ratio",
+            "lock" => "lock",
+            // read by the config module
+            "config" => continue,
+            _ => {
+                log::warn!("unknown option --{}", name);
+                continue;
+            }
+        };
+        pairs.push(format!("{}={}", key, value));
+    }
+    pairs.join("&")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn fragment() -> Option<String> {
+    Some(from_args(std::env::args().skip(1))).filter(|f| !f.is_empty())
+}
+
+// replaces the fragment without adding to the browser history
+#[cfg(target_arch = "wasm32")]
+pub fn set_fragment(fragment: &str) {
+    if let Some(w) = web_sys::window() {
+        w.location().replace(&format!("#{}", fragment)).ok();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_fragment(_fragment: &str) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let link = decode(&encode(11, 72, 72.0 / 11.0, Quantity::Ratio));
+        assert_eq!(link, Intent { left: Some(11), right: Some(72), ratio: None, lock: Some(Quantity::Ratio) });
+        let link = decode(&format!("#{}", encode(13, 40, 3.1, Quantity::Left)));
+        assert_eq!(link, Intent { left: Some(13), right: Some(40), ratio: Some(3.1), lock: Some(Quantity::Left) });
+    }
+
+    #[test]
+    fn unknown_and_bad_keys() {
+        let link = decode("l=0&r=x&ratio=-2&lock=banana&zoom=3&junk");
+        assert_eq!(link, Intent::default());
+    }
+
+    #[test]
+    fn command_line() {
+        let args = ["--left", "11", "--right=72", "--config", "a.toml", "--lock", "right", "stray"].map(String::from);
+        assert_eq!(from_args(args.into_iter()), "l=11&r=72&lock=right");
+    }
+}
@@ -0,0 +1,227 @@
+use eframe::egui;
+
+use crate::i18n::tr;
+use crate::plot;
+use crate::tire;
+
+/*
+ * The gearing chart of a car or motorcycle: every transmission gear times the final drive
+ * turns the engine speed into wheel speed, and the tire turns that into road speed
+ *     v = rpm / (gear · final drive) · tire circumference
+ * Upshifting at the redline drops the engine to redline · next gear / this gear, which is
+ * where the next line of the chart starts.
+ *
+ * Four wheel drives have a transfer case between the transmission and the axles, 1:1 in
+ * high range and a reduction in low range. The crawl ratio is everything stacked in the
+ * lowest setting, first gear · low range · final drive, and decides how slow the vehicle
+ * can idle over rocks.
+ *
+ * The speedometer counts turns of the transmission output, calibrated for the final drive
+ * and tire the vehicle came with. After regearing it shows
+ *     indicated / actual = (original tire / original final drive) / (tire / final drive)
+ */
+
+pub struct Vehicle {
+    gears: Vec<f64>,
+    final_drive: f64,
+    // transfer case reduction in low range, and if it's engaged
+    low_range: f64,
+    low: bool,
+    // rolling circumference of the tire in mm
+    circumference: f64,
+    tire_str: String,
+    redline: f64,
+    // the setup the speedometer was calibrated for
+    original_final_drive: f64,
+    original_circumference: f64,
+    original_tire_str: String,
+}
+
+impl Vehicle {
+    pub fn new() -> Vehicle {
+        Vehicle {
+            gears: vec![3.59, 2.06, 1.40, 1.00, 0.83],
+            final_drive: 3.9,
+            low_range: 2.72,
+            low: false,
+            circumference: 1985.0,
+            tire_str: String::from("205/55R16"),
+            redline: 6500.0,
+            original_final_drive: 3.9,
+            original_circumference: 1985.0,
+            original_tire_str: String::from("205/55R16"),
+        }
+    }
+
+    // the transfer case in the selected range
+    fn range(&self) -> f64 {
+        if self.low {
+            self.low_range
+        } else {
+            1.0
+        }
+    }
+
+    fn first_gear(&self) -> f64 {
+        self.gears.first().copied().unwrap_or(1.0)
+    }
+
+    // engine to wheel in first gear
+    pub fn ratio(&self) -> f32 {
+        (self.first_gear() * self.range() * self.final_drive) as f32
+    }
+
+    // engine to wheel in first gear and low range
+    fn crawl_ratio(&self) -> f64 {
+        self.first_gear() * self.low_range * self.final_drive
+    }
+
+    // km/h per engine rpm in a gear
+    fn speed_per_rpm(&self, gear: f64) -> f64 {
+        self.circumference * 60.0 / 1_000_000.0 / (gear * self.range() * self.final_drive)
+    }
+
+    // indicated over actual speed
+    fn speedometer_factor(&self) -> f64 {
+        (self.original_circumference / self.original_final_drive) / (self.circumference / self.final_drive)
+    }
+
+    fn speedometer_ui(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("speedometer_inputs").show(ui, |ui| {
+            ui.label(tr("Original final drive"));
+            ui.add(egui::DragValue::new(&mut self.original_final_drive).speed(0.01).clamp_range(0.1..=20.0));
+            ui.end_row();
+            ui.label(tr("Original tire size"));
+            if let Some(c) = tire::tire_input(ui, &mut self.original_tire_str) {
+                self.original_circumference = c;
+            }
+            ui.end_row();
+            ui.label(tr("Original circumference"));
+            ui.add(egui::DragValue::new(&mut self.original_circumference).speed(1.0).clamp_range(100.0..=7000.0).suffix(" mm"));
+            ui.end_row();
+        });
+        let factor = self.speedometer_factor();
+        let error = (factor - 1.0) * 100.0;
+        let text = if error.abs() < 0.05 {
+            tr("The speedometer reads correctly.").to_string()
+        } else if error > 0.0 {
+            format!("{} {:.1} % {}.", tr("The speedometer reads"), error, tr("high"))
+        } else {
+            format!("{} {:.1} % {}.", tr("The speedometer reads"), -error, tr("low"))
+        };
+        // most countries only allow a speedometer to read high
+        if error < -0.05 {
+            ui.colored_label(ui.visuals().warn_fg_color, text);
+        } else {
+            ui.label(text);
+        }
+        ui.label(format!("{} {:.1} km/h.", tr("Showing 100 km/h, you drive"), 100.0 / factor));
+        if ui.button(tr("Set as original")).on_hover_text(tr("Recalibrate for the current final drive and tire")).clicked() {
+            self.original_final_drive = self.final_drive;
+            self.original_circumference = self.circumference;
+            self.original_tire_str = self.tire_str.clone();
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("vehicle_inputs").show(ui, |ui| {
+            ui.label(tr("Final drive"));
+            ui.add(egui::DragValue::new(&mut self.final_drive).speed(0.01).clamp_range(0.1..=20.0));
+            ui.end_row();
+            ui.label(tr("Low range"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.low_range).speed(0.01).clamp_range(1.0..=10.0).max_decimals(3));
+                ui.checkbox(&mut self.low, tr("Engaged"));
+            });
+            ui.end_row();
+            ui.label(tr("Tire size"));
+            if let Some(c) = tire::tire_input(ui, &mut self.tire_str) {
+                self.circumference = c;
+            }
+            ui.end_row();
+            ui.label(tr("Tire circumference"));
+            ui.add(egui::DragValue::new(&mut self.circumference).speed(1.0).clamp_range(100.0..=7000.0).suffix(" mm"));
+            ui.end_row();
+            ui.label(tr("Redline"));
+            ui.add(egui::DragValue::new(&mut self.redline).speed(10.0).clamp_range(500.0..=25000.0).suffix(" rpm"));
+            ui.end_row();
+        });
+        ui.label(format!("{} {:.1} : 1", tr("Crawl ratio:"), self.crawl_ratio()));
+
+        ui.separator();
+        let mut remove = None;
+        egui::Grid::new("vehicle_gears").striped(true).show(ui, |ui| {
+            for h in ["Gear", "Ratio", "Overall", "km/h per 1000 rpm", "At redline", "Upshift to"] {
+                ui.label(egui::RichText::new(tr(h)).strong());
+            }
+            ui.end_row();
+            let count = self.gears.len();
+            for i in 0..count {
+                ui.label(format!("{}", i + 1));
+                ui.add(egui::DragValue::new(&mut self.gears[i]).speed(0.01).clamp_range(0.1..=20.0).max_decimals(3));
+                let gear = self.gears[i];
+                ui.label(format!("{:.3}", gear * self.range() * self.final_drive));
+                ui.label(format!("{:.1}", self.speed_per_rpm(gear) * 1000.0));
+                ui.label(format!("{:.0} km/h", self.speed_per_rpm(gear) * self.redline));
+                match self.gears.get(i + 1) {
+                    Some(next) => ui.label(format!("{:.0} rpm", self.redline * next / gear)),
+                    None => ui.label(""),
+                };
+                if ui.add_enabled(count > 1, egui::Button::new("🗑").small()).on_hover_text(tr("Delete gear")).clicked() {
+                    remove = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = remove {
+            self.gears.remove(i);
+        }
+        if ui.button(tr("Add gear")).clicked() {
+            let next = self.gears.last().map_or(1.0, |g| g * 0.8);
+            self.gears.push(next);
+        }
+
+        egui::CollapsingHeader::new(tr("Speedometer error")).id_source("vehicle_speedometer").show(ui, |ui| self.speedometer_ui(ui));
+
+        ui.separator();
+        let lines: Vec<f64> = self.gears.iter().map(|&g| self.speed_per_rpm(g)).collect();
+        plot::gearing_chart(ui, "vehicle_gearing", &lines, self.redline);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speed_per_rpm() {
+        let mut v = Vehicle::new();
+        // 1985 mm · 60 / 3.9, per 1000 rpm
+        assert!((v.speed_per_rpm(1.0) * 1000.0 - 30.54).abs() < 0.01);
+        assert!((v.speed_per_rpm(2.0) * 2.0 - v.speed_per_rpm(1.0)).abs() < 1e-12);
+        v.low = true;
+        assert!((v.speed_per_rpm(1.0) * 2.72 * 1000.0 - 30.54).abs() < 0.01);
+    }
+
+    #[test]
+    fn speedometer_factor() {
+        let mut v = Vehicle::new();
+        assert_eq!(v.speedometer_factor(), 1.0);
+        // a taller tire covers more road per turn, the speedometer reads low
+        v.circumference = 2100.0;
+        assert!(v.speedometer_factor() < 1.0);
+        // and a numerically higher final drive turns the output faster, it reads high
+        v.circumference = 1985.0;
+        v.final_drive = 4.56;
+        assert!((v.speedometer_factor() - 4.56 / 3.9).abs() < 1e-12);
+    }
+
+    #[test]
+    fn crawl_ratio() {
+        let mut v = Vehicle::new();
+        assert!((v.crawl_ratio() - 3.59 * 2.72 * 3.9).abs() < 1e-12);
+        // with low range engaged, first gear is the crawl ratio
+        v.low = true;
+        assert!((v.crawl_ratio() - v.ratio() as f64).abs() < 1e-4);
+    }
+}
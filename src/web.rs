@@ -0,0 +1,47 @@
+#![allow(clippy::mem_forget)] // false positives from the #[wasm_bindgen] macro
+
+use wasm_bindgen::prelude::*;
+
+use crate::RitzelApp;
+
+/*
+ * Entry point for pages that embed the app themselves instead of using the index.html
+ * trunk builds:
+ *     const app = new WebHandle();
+ *     await app.start("some_canvas_id");
+ * eframe keeps the canvas the size of its parent and turns touches into pointer events.
+ */
+
+#[derive(Clone)]
+#[wasm_bindgen]
+pub struct WebHandle {
+    runner: eframe::WebRunner,
+}
+
+#[wasm_bindgen]
+impl WebHandle {
+    #[allow(clippy::new_without_default)]
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        // log messages go to the browser console
+        eframe::WebLogger::init(log::LevelFilter::Debug).ok();
+        WebHandle { runner: eframe::WebRunner::new() }
+    }
+
+    #[wasm_bindgen]
+    pub async fn start(&self, canvas_id: &str) -> Result<(), wasm_bindgen::JsValue> {
+        self.runner
+            .start(canvas_id, eframe::WebOptions::default(), Box::new(|cc| Box::new(RitzelApp::new(cc))))
+            .await
+    }
+
+    #[wasm_bindgen]
+    pub fn destroy(&self) {
+        self.runner.destroy();
+    }
+
+    #[wasm_bindgen]
+    pub fn has_panicked(&self) -> bool {
+        self.runner.has_panicked()
+    }
+}
@@ -0,0 +1,124 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/*
+ * Long searches run on a worker thread, so the UI keeps drawing. The search reports how
+ * far it is through a Progress and stops early when it returns false, which happens when
+ * the user cancelled. The UI polls the Job every frame.
+ *
+ * The browser has no threads, there the search just runs right away.
+ */
+
+#[derive(Clone)]
+pub struct Progress {
+    // done fraction in 1/10000
+    done: Arc<AtomicU32>,
+    cancel: Arc<AtomicBool>,
+    ctx: Option<egui::Context>,
+    // the part of the whole job this progress covers
+    offset: f32,
+    scale: f32,
+}
+
+impl Progress {
+    fn new(ctx: Option<egui::Context>) -> Progress {
+        Progress {
+            done: Arc::new(AtomicU32::new(0)),
+            cancel: Arc::new(AtomicBool::new(false)),
+            ctx,
+            offset: 0.0,
+            scale: 1.0,
+        }
+    }
+
+    // progress for step `index` of `count` equal steps, for searches made of other searches
+    pub fn part(&self, index: usize, count: usize) -> Progress {
+        let scale = self.scale / count as f32;
+        Progress { offset: self.offset + index as f32 * scale, scale, ..self.clone() }
+    }
+
+    // sets the done fraction, returns false if the search should stop
+    pub fn report(&self, fraction: f32) -> bool {
+        let done = ((self.offset + fraction.clamp(0.0, 1.0) * self.scale) * 10000.0) as u32;
+        if self.done.swap(done, Ordering::Relaxed) != done {
+            if let Some(ctx) = &self.ctx {
+                ctx.request_repaint();
+            }
+        }
+        !self.cancel.load(Ordering::Relaxed)
+    }
+
+    pub fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    fn fraction(&self) -> f32 {
+        self.done.load(Ordering::Relaxed) as f32 / 10000.0
+    }
+}
+
+enum State<T> {
+    #[cfg(not(target_arch = "wasm32"))]
+    Running(std::thread::JoinHandle<Option<T>>),
+    Finished(Option<T>),
+}
+
+pub struct Job<T> {
+    state: Option<State<T>>,
+    progress: Progress,
+}
+
+impl<T: Send + 'static> Job<T> {
+    // the search returns None when it was cancelled
+    pub fn spawn(ctx: &egui::Context, search: impl FnOnce(&Progress) -> Option<T> + Send + 'static) -> Job<T> {
+        let progress = Progress::new(Some(ctx.clone()));
+        #[cfg(not(target_arch = "wasm32"))]
+        let state = {
+            let p = progress.clone();
+            State::Running(std::thread::spawn(move || search(&p)))
+        };
+        #[cfg(target_arch = "wasm32")]
+        let state = State::Finished(search(&progress));
+        Job { state: Some(state), progress }
+    }
+
+    fn finished(&mut self) -> bool {
+        match self.state.take() {
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(State::Running(handle)) if handle.is_finished() => {
+                self.state = Some(State::Finished(handle.join().unwrap_or(None)));
+            }
+            other => self.state = other,
+        }
+        matches!(self.state, Some(State::Finished(_)))
+    }
+
+    fn take(&mut self) -> Option<T> {
+        match self.state.take() {
+            Some(State::Finished(r)) => r,
+            _ => None,
+        }
+    }
+}
+
+// progress bar and cancel button while the job runs. Returns the result once, when the
+// job is done, and clears the job. A cancelled job just disappears.
+pub fn job_ui<T: Send + 'static>(ui: &mut egui::Ui, job: &mut Option<Job<T>>) -> Option<T> {
+    let j = job.as_mut()?;
+    if j.finished() {
+        let result = j.take();
+        *job = None;
+        return result;
+    }
+    ui.horizontal(|ui| {
+        ui.add(egui::ProgressBar::new(j.progress.fraction()).show_percentage().desired_width(200.0));
+        if ui.add_enabled(!j.progress.cancelled(), egui::Button::new(tr("Cancel"))).clicked() {
+            j.progress.cancel.store(true, Ordering::Relaxed);
+        }
+    });
+    None
+}